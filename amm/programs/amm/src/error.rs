@@ -79,4 +79,136 @@ pub enum ErrorCode {
 
     #[msg("Invalid constant product")]
     InvalidConstantProduct,
+
+    #[msg("Referral fee too high - maximum is 100% of the swap fee")]
+    InvalidReferralFee,
+
+    #[msg("Invalid referrer token account")]
+    InvalidReferrer,
+
+    #[msg("Transaction deadline exceeded")]
+    DeadlineExceeded,
+
+    #[msg("Invalid price impact limit - must not exceed 10000 bps")]
+    InvalidPriceImpactLimit,
+
+    #[msg("Invalid LP token metadata")]
+    InvalidMetadata,
+
+    #[msg("Unlock time must be in the future")]
+    InvalidUnlockTime,
+
+    #[msg("Liquidity is still locked")]
+    LiquidityStillLocked,
+
+    #[msg("Invalid farm reward schedule")]
+    InvalidFarmSchedule,
+
+    #[msg("Farm already has the maximum number of rewards")]
+    TooManyFarmRewards,
+
+    #[msg("Invalid farm reward index")]
+    InvalidRewardIndex,
+
+    #[msg("Invalid dynamic fee parameters")]
+    InvalidDynamicFee,
+
+    #[msg("Invalid trade-size fee tiers")]
+    InvalidFeeTiers,
+
+    #[msg("Invalid fee discount")]
+    InvalidFeeDiscount,
+
+    #[msg("Circuit breaker tripped - price moved too far within the slot")]
+    CircuitBreakerTripped,
+
+    #[msg("Instructions sysvar is required for this pool")]
+    MissingInstructionsSysvar,
+
+    #[msg("Transaction also swaps in the opposite direction on this pool")]
+    SandwichDetected,
+
+    #[msg("Too many sandwich-exempt programs")]
+    TooManyExemptPrograms,
+
+    #[msg("Revealed swap does not match the commitment")]
+    InvalidCommitment,
+
+    #[msg("Swap commitment cannot be revealed yet")]
+    RevealTooEarly,
+
+    #[msg("Swap commitment has expired")]
+    CommitExpired,
+
+    #[msg("Swap commitment has not expired yet")]
+    CommitNotExpired,
+
+    #[msg("Batch auctions are not enabled for this pool")]
+    BatchAuctionDisabled,
+
+    #[msg("Pool only accepts swaps through batch auctions")]
+    BatchAuctionOnly,
+
+    #[msg("Batch is no longer accepting orders")]
+    BatchClosed,
+
+    #[msg("Batch cannot be settled yet")]
+    BatchNotReady,
+
+    #[msg("Order accounts do not match the batch")]
+    InvalidBatchAccounts,
+
+    #[msg("Order expiry must be in the future")]
+    InvalidOrderExpiry,
+
+    #[msg("Order has expired")]
+    OrderExpired,
+
+    #[msg("Pool price has not reached the order's target price")]
+    LimitPriceNotReached,
+
+    #[msg("Invalid DCA order schedule")]
+    InvalidDcaSchedule,
+
+    #[msg("DCA order is not due for a fill yet")]
+    DcaFillNotDue,
+
+    #[msg("DCA order has no input left")]
+    DcaOrderEmpty,
+
+    #[msg("Invalid TWAP window")]
+    InvalidTwapWindow,
+
+    #[msg("Not enough price history for the TWAP window")]
+    TwapUnavailable,
+
+    #[msg("Trigger order condition not met")]
+    TriggerNotReached,
+
+    #[msg("Too many RFQ makers")]
+    TooManyRfqMakers,
+
+    #[msg("RFQ maker is not allowlisted for this pool")]
+    RfqMakerNotAllowed,
+
+    #[msg("RFQ quote does not match the swap")]
+    InvalidQuote,
+
+    #[msg("RFQ quote has expired")]
+    QuoteExpired,
+
+    #[msg("Missing or invalid ed25519 signature for the RFQ quote")]
+    InvalidQuoteSignature,
+
+    #[msg("Invalid number of batch swap entries")]
+    InvalidBatchSwap,
+
+    #[msg("Batch swap accounts do not match the entries")]
+    InvalidBatchSwapAccounts,
+
+    #[msg("Batch order has nothing to claim")]
+    NothingToClaim,
+
+    #[msg("RFQ quote has not expired yet")]
+    QuoteNotExpired,
 }
\ No newline at end of file
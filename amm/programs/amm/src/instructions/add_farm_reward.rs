@@ -0,0 +1,116 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::state::{Farm, FarmReward};
+use crate::error::ErrorCode;
+use crate::instructions::create_farm::FarmRewardAdded;
+use crate::instructions::shared::{
+    transfer_tokens, farm_reward_funding, add_reward_to_farm, update_farm_rewards,
+};
+
+pub fn add_farm_reward_handler(
+    ctx: Context<AddFarmReward>,
+    reward_per_second: u64,
+    start_time: i64,
+    end_time: i64,
+) -> Result<()> {
+    let clock = Clock::get()?;
+    let funding = farm_reward_funding(reward_per_second, start_time, end_time, clock.unix_timestamp)?;
+    
+    let reward_mint = ctx.accounts.reward_mint.key();
+    let farm = &mut ctx.accounts.farm;
+    require!(
+        farm.rewards[..farm.reward_count as usize]
+            .iter()
+            .all(|reward| reward.reward_mint != reward_mint),
+        ErrorCode::InvalidTokenMint
+    );
+    
+    require!(
+        ctx.accounts.funder_reward_token.amount >= funding,
+        ErrorCode::InsufficientBalance
+    );
+    
+    update_farm_rewards(farm, clock.unix_timestamp)?;
+    
+    let reward_index = add_reward_to_farm(
+        farm,
+        FarmReward {
+            reward_mint,
+            reward_vault: ctx.accounts.reward_vault.key(),
+            reward_per_second,
+            start_time,
+            end_time,
+            reward_per_share: 0,
+            last_update_time: start_time,
+        },
+    )?;
+    
+    transfer_tokens(
+        &ctx.accounts.funder_reward_token,
+        &ctx.accounts.reward_vault,
+        &ctx.accounts.reward_mint,
+        &ctx.accounts.authority,
+        &ctx.accounts.token_program,
+        funding,
+    )?;
+    
+    emit!(FarmRewardAdded {
+        farm: ctx.accounts.farm.key(),
+        reward_index: reward_index as u8,
+        reward_mint,
+        reward_per_second,
+        start_time,
+        end_time,
+        funding,
+        timestamp: clock.unix_timestamp,
+    });
+    
+    msg!(
+        "Farm reward {} added: {}/s from {} to {}",
+        reward_index,
+        reward_per_second,
+        start_time,
+        end_time
+    );
+    
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct AddFarmReward<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"farm", farm.pool_mint.as_ref()],
+        bump = farm.bump,
+        has_one = authority @ ErrorCode::Unauthorized,
+    )]
+    pub farm: Box<Account<'info, Farm>>,  // ✅ BOX
+    
+    #[account(mint::token_program = token_program)]
+    pub reward_mint: Box<InterfaceAccount<'info, Mint>>,  // ✅ BOX
+    
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"farm_reward_vault", farm.key().as_ref(), reward_mint.key().as_ref()],
+        bump,
+        token::mint = reward_mint,
+        token::authority = farm,
+        token::token_program = token_program,
+    )]
+    pub reward_vault: Box<InterfaceAccount<'info, TokenAccount>>,  // ✅ BOX
+    
+    #[account(
+        mut,
+        token::mint = reward_mint,
+        token::authority = authority,
+    )]
+    pub funder_reward_token: Box<InterfaceAccount<'info, TokenAccount>>,  // ✅ BOX
+    
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
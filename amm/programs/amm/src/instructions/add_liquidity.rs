@@ -7,7 +7,8 @@ use anchor_spl::{
 use crate::state::PoolState;
 use crate::error::ErrorCode;
 use crate::instructions::shared::{
-    transfer_tokens, mint_lp_tokens, integer_sqrt, calculate_optimal_amounts
+    transfer_tokens, mint_lp_tokens, calculate_optimal_amounts, calculate_lp_tokens, check_deadline,
+    update_price_oracle,
 };
 
 pub fn add_liquidity_handler(
@@ -17,7 +18,9 @@ pub fn add_liquidity_handler(
     amount_a_min: u64,
     amount_b_min: u64,
     min_lp_tokens: u64,
-) -> Result<()> {
+    deadline: Option<i64>,
+) -> Result<AddLiquidityResult> {
+    check_deadline(deadline)?;
     require!(amount_a_desired > 0, ErrorCode::InvalidAmount);
     require!(amount_b_desired > 0, ErrorCode::InvalidAmount);
     
@@ -42,31 +45,14 @@ pub fn add_liquidity_handler(
         ErrorCode::InsufficientBalance
     );
     
-    let lp_tokens = if pool_state.total_supply == 0 {
-        let product = (amount_a as u128)
-            .checked_mul(amount_b as u128)
-            .ok_or(ErrorCode::MathOverflow)?;
-        
-        let sqrt = integer_sqrt(product);
-        
-        require!(sqrt > 1000, ErrorCode::InsufficientLiquidity);
-        
-        sqrt - 1000
-    } else {
-        let lp_from_a = (amount_a as u128)
-            .checked_mul(pool_state.total_supply as u128)
-            .ok_or(ErrorCode::MathOverflow)?
-            .checked_div(pool_state.reserve_a as u128)
-            .ok_or(ErrorCode::DivisionByZero)? as u64;
-        
-        let lp_from_b = (amount_b as u128)
-            .checked_mul(pool_state.total_supply as u128)
-            .ok_or(ErrorCode::MathOverflow)?
-            .checked_div(pool_state.reserve_b as u128)
-            .ok_or(ErrorCode::DivisionByZero)? as u64;
-        
-        lp_from_a.min(lp_from_b)
-    };
+    let lp_tokens = calculate_lp_tokens(
+        amount_a,
+        amount_b,
+        pool_state.reserve_a,
+        pool_state.reserve_b,
+        pool_state.total_supply,
+        pool_state.minimum_liquidity,
+    )?;
     
     require!(lp_tokens >= min_lp_tokens, ErrorCode::InsufficientLPTokens);
     require!(lp_tokens > 0, ErrorCode::InsufficientLPTokens);
@@ -108,6 +94,9 @@ pub fn add_liquidity_handler(
         signer_seeds,
     )?;
     
+    let clock = Clock::get()?;
+    update_price_oracle(pool_state, clock.unix_timestamp)?;
+    
     pool_state.reserve_a = pool_state.reserve_a
         .checked_add(amount_a)
         .ok_or(ErrorCode::MathOverflow)?;
@@ -120,7 +109,6 @@ pub fn add_liquidity_handler(
         .checked_add(lp_tokens)
         .ok_or(ErrorCode::MathOverflow)?;
     
-    let clock = Clock::get()?;
     emit!(LiquidityAdded {
         pool: pool_state.key(),
         provider: ctx.accounts.user.key(),
@@ -138,7 +126,11 @@ pub fn add_liquidity_handler(
         lp_tokens
     );
     
-    Ok(())
+    Ok(AddLiquidityResult {
+        amount_a,
+        amount_b,
+        lp_tokens_minted: lp_tokens,
+    })
 }
 
 #[derive(Accounts)]
@@ -226,4 +218,12 @@ pub struct LiquidityAdded {
     pub lp_tokens_minted: u64,
     pub total_supply: u64,
     pub timestamp: i64,
+}
+
+/// Deposited amounts, returned to the caller through return data.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct AddLiquidityResult {
+    pub amount_a: u64,
+    pub amount_b: u64,
+    pub lp_tokens_minted: u64,
 }
\ No newline at end of file
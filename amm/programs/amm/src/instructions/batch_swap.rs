@@ -0,0 +1,147 @@
+use std::collections::BTreeSet;
+
+use anchor_lang::prelude::*;
+
+use crate::error::ErrorCode;
+use crate::instructions::swap::*;
+
+/// Most entries in one batch swap; bounded by the size of the return data.
+pub const MAX_BATCH_SWAP_ENTRIES: usize = 8;
+/// Accounts each entry takes from `remaining_accounts`, in `Swap` order. Absent
+/// optional accounts are passed as the program id.
+pub const BATCH_SWAP_ACCOUNTS_PER_ENTRY: usize = 15;
+
+/// One swap of a batch.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct BatchSwapEntry {
+    pub pool: Pubkey,
+    pub is_a_to_b: bool,
+    pub amount_in: u64,
+    pub min_amount_out: u64,
+}
+
+/// Outcome of one batch entry. `error_code` is set when the entry was skipped.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct BatchSwapEntryResult {
+    pub success: bool,
+    pub amount_in: u64,
+    pub amount_out: u64,
+    pub fee_amount: u64,
+    pub error_code: u32,
+}
+
+/// Runs `entries` in order, each through the regular swap path with its own
+/// `Swap` accounts from `remaining_accounts`. With `skip_failed` an entry that
+/// fails its checks is reported and skipped; otherwise the whole batch fails.
+/// A failing token transfer always aborts the transaction.
+pub fn batch_swap_handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, BatchSwap<'info>>,
+    entries: Vec<BatchSwapEntry>,
+    skip_failed: bool,
+) -> Result<Vec<BatchSwapEntryResult>> {
+    require!(
+        !entries.is_empty() && entries.len() <= MAX_BATCH_SWAP_ENTRIES,
+        ErrorCode::InvalidBatchSwap
+    );
+    require!(
+        ctx.remaining_accounts.len() == entries.len() * BATCH_SWAP_ACCOUNTS_PER_ENTRY,
+        ErrorCode::InvalidBatchSwapAccounts
+    );
+    
+    let mut results = Vec::with_capacity(entries.len());
+    
+    for (index, (entry, accounts)) in entries
+        .iter()
+        .zip(ctx.remaining_accounts.chunks(BATCH_SWAP_ACCOUNTS_PER_ENTRY))
+        .enumerate()
+    {
+        match execute_entry(ctx.program_id, &ctx.accounts.user, entry, accounts) {
+            Ok(swap_result) => results.push(BatchSwapEntryResult {
+                success: true,
+                amount_in: swap_result.amount_in,
+                amount_out: swap_result.amount_out,
+                fee_amount: swap_result.fee_amount,
+                error_code: 0,
+            }),
+            Err(error) if skip_failed => {
+                msg!("Batch swap entry {} skipped", index);
+                
+                results.push(BatchSwapEntryResult {
+                    success: false,
+                    amount_in: 0,
+                    amount_out: 0,
+                    fee_amount: 0,
+                    error_code: error_code(&error),
+                });
+            }
+            Err(error) => return Err(error),
+        }
+    }
+    
+    let succeeded = results.iter().filter(|result| result.success).count() as u8;
+    
+    let clock = Clock::get()?;
+    emit!(BatchSwapExecuted {
+        user: ctx.accounts.user.key(),
+        entry_count: entries.len() as u8,
+        succeeded,
+        skip_failed,
+        timestamp: clock.unix_timestamp,
+    });
+    
+    msg!("Batch swap: {}/{} entries executed", succeeded, entries.len());
+    
+    Ok(results)
+}
+
+/// Validates one entry's accounts as `Swap`, runs the swap and persists the
+/// accounts. Nothing is written when it fails.
+fn execute_entry<'info>(
+    program_id: &Pubkey,
+    user: &Signer<'info>,
+    entry: &BatchSwapEntry,
+    accounts: &'info [AccountInfo<'info>],
+) -> Result<SwapResult> {
+    let mut accounts = accounts;
+    let mut bumps = SwapBumps::default();
+    let mut reallocs = BTreeSet::new();
+    let mut swap = Swap::try_accounts(program_id, &mut accounts, &[], &mut bumps, &mut reallocs)?;
+    
+    require_keys_eq!(swap.user.key(), user.key(), ErrorCode::InvalidBatchSwapAccounts);
+    require_keys_eq!(swap.pool_state.key(), entry.pool, ErrorCode::InvalidBatchSwapAccounts);
+    
+    let result = process_swap(
+        &mut swap,
+        entry.amount_in,
+        entry.min_amount_out,
+        entry.is_a_to_b,
+        None,
+        None,
+    )?;
+    
+    swap.exit(program_id)?;
+    
+    Ok(result)
+}
+
+fn error_code(error: &Error) -> u32 {
+    match error {
+        Error::AnchorError(error) => error.error_code_number,
+        Error::ProgramError(error) => u64::from(error.program_error.clone()) as u32,
+    }
+}
+
+#[derive(Accounts)]
+pub struct BatchSwap<'info> {
+    /// Must also be the `user` of every entry.
+    pub user: Signer<'info>,
+}
+
+#[event]
+pub struct BatchSwapExecuted {
+    pub user: Pubkey,
+    pub entry_count: u8,
+    pub succeeded: u8,
+    pub skip_failed: bool,
+    pub timestamp: i64,
+}
@@ -0,0 +1,114 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::state::{DcaOrder, PoolState};
+use crate::error::ErrorCode;
+use crate::instructions::shared::{transfer_tokens_signed, close_token_account_signed};
+
+/// Returns the unswapped input of a DCA order to its owner and closes it.
+pub fn cancel_dca_order_handler(ctx: Context<CancelDcaOrder>) -> Result<()> {
+    let dca_order = &ctx.accounts.dca_order;
+    
+    let pool_key = ctx.accounts.pool_state.key();
+    let owner_key = ctx.accounts.owner.key();
+    let dca_order_seeds = &[
+        b"dca_order",
+        pool_key.as_ref(),
+        owner_key.as_ref(),
+        &[dca_order.is_a_to_b as u8],
+        &[dca_order.bump],
+    ];
+    let signer_seeds = &[&dca_order_seeds[..]];
+    
+    // Refund the escrow balance rather than `remaining_amount`: tokens sent to the
+    // escrow directly would otherwise keep it from closing.
+    let remaining_amount = ctx.accounts.dca_escrow.amount;
+    
+    if remaining_amount > 0 {
+        transfer_tokens_signed(
+            &ctx.accounts.dca_escrow,
+            &ctx.accounts.user_token_in,
+            &ctx.accounts.input_mint,
+            &ctx.accounts.dca_order.to_account_info(),
+            &ctx.accounts.token_program,
+            remaining_amount,
+            signer_seeds,
+        )?;
+    }
+    
+    close_token_account_signed(
+        &ctx.accounts.dca_escrow,
+        &ctx.accounts.owner.to_account_info(),
+        &ctx.accounts.dca_order.to_account_info(),
+        &ctx.accounts.token_program,
+        signer_seeds,
+    )?;
+    
+    ctx.accounts.dca_order.close(ctx.accounts.owner.to_account_info())?;
+    
+    let clock = Clock::get()?;
+    emit!(DcaOrderCancelled {
+        pool: pool_key,
+        owner: owner_key,
+        dca_order: ctx.accounts.dca_order.key(),
+        refunded_amount: remaining_amount,
+        timestamp: clock.unix_timestamp,
+    });
+    
+    msg!("DCA order cancelled: {} refunded", remaining_amount);
+    
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CancelDcaOrder<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    
+    #[account(
+        seeds = [b"pool_state", pool_state.token_mint_a.as_ref(), pool_state.token_mint_b.as_ref()],
+        bump = pool_state.bump,
+    )]
+    pub pool_state: Box<Account<'info, PoolState>>,  // ✅ BOX
+    
+    #[account(address = dca_escrow.mint)]
+    pub input_mint: Box<InterfaceAccount<'info, Mint>>,  // ✅ BOX
+    
+    #[account(
+        mut,
+        seeds = [
+            b"dca_order",
+            pool_state.key().as_ref(),
+            owner.key().as_ref(),
+            &[dca_order.is_a_to_b as u8],
+        ],
+        bump = dca_order.bump,
+        has_one = owner @ ErrorCode::Unauthorized,
+    )]
+    pub dca_order: Box<Account<'info, DcaOrder>>,  // ✅ BOX
+    
+    #[account(
+        mut,
+        seeds = [b"dca_escrow", dca_order.key().as_ref()],
+        bump = dca_order.escrow_bump,
+    )]
+    pub dca_escrow: Box<InterfaceAccount<'info, TokenAccount>>,  // ✅ BOX
+    
+    #[account(
+        mut,
+        token::mint = input_mint,
+        token::authority = owner,
+    )]
+    pub user_token_in: Box<InterfaceAccount<'info, TokenAccount>>,  // ✅ BOX
+    
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[event]
+pub struct DcaOrderCancelled {
+    pub pool: Pubkey,
+    pub owner: Pubkey,
+    pub dca_order: Pubkey,
+    pub refunded_amount: u64,
+    pub timestamp: i64,
+}
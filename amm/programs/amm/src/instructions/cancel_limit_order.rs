@@ -0,0 +1,111 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::state::{Order, PoolState};
+use crate::error::ErrorCode;
+use crate::instructions::shared::{transfer_tokens_signed, close_token_account_signed};
+
+/// Returns the escrowed input of an unfilled limit order to its owner.
+pub fn cancel_limit_order_handler(ctx: Context<CancelLimitOrder>) -> Result<()> {
+    let order = &ctx.accounts.order;
+    
+    let pool_key = ctx.accounts.pool_state.key();
+    let owner_key = ctx.accounts.owner.key();
+    let order_id_bytes = order.order_id.to_le_bytes();
+    let order_seeds = &[
+        b"order",
+        pool_key.as_ref(),
+        owner_key.as_ref(),
+        order_id_bytes.as_ref(),
+        &[order.bump],
+    ];
+    let signer_seeds = &[&order_seeds[..]];
+    
+    let amount_in = ctx.accounts.order_escrow.amount;
+    
+    transfer_tokens_signed(
+        &ctx.accounts.order_escrow,
+        &ctx.accounts.user_token_in,
+        &ctx.accounts.input_mint,
+        &ctx.accounts.order.to_account_info(),
+        &ctx.accounts.token_program,
+        amount_in,
+        signer_seeds,
+    )?;
+    
+    close_token_account_signed(
+        &ctx.accounts.order_escrow,
+        &ctx.accounts.owner.to_account_info(),
+        &ctx.accounts.order.to_account_info(),
+        &ctx.accounts.token_program,
+        signer_seeds,
+    )?;
+    
+    ctx.accounts.order.close(ctx.accounts.owner.to_account_info())?;
+    
+    let clock = Clock::get()?;
+    emit!(LimitOrderCancelled {
+        pool: pool_key,
+        owner: owner_key,
+        order: ctx.accounts.order.key(),
+        amount_in,
+        timestamp: clock.unix_timestamp,
+    });
+    
+    msg!("Limit order cancelled: {} refunded", amount_in);
+    
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CancelLimitOrder<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    
+    #[account(
+        seeds = [b"pool_state", pool_state.token_mint_a.as_ref(), pool_state.token_mint_b.as_ref()],
+        bump = pool_state.bump,
+    )]
+    pub pool_state: Box<Account<'info, PoolState>>,  // ✅ BOX
+    
+    #[account(address = order_escrow.mint)]
+    pub input_mint: Box<InterfaceAccount<'info, Mint>>,  // ✅ BOX
+    
+    #[account(
+        mut,
+        seeds = [
+            b"order",
+            pool_state.key().as_ref(),
+            owner.key().as_ref(),
+            &order.order_id.to_le_bytes(),
+        ],
+        bump = order.bump,
+        has_one = owner @ ErrorCode::Unauthorized,
+    )]
+    pub order: Box<Account<'info, Order>>,  // ✅ BOX
+    
+    #[account(
+        mut,
+        seeds = [b"order_escrow", order.key().as_ref()],
+        bump = order.escrow_bump,
+    )]
+    pub order_escrow: Box<InterfaceAccount<'info, TokenAccount>>,  // ✅ BOX
+    
+    #[account(
+        mut,
+        token::mint = input_mint,
+        token::authority = owner,
+    )]
+    pub user_token_in: Box<InterfaceAccount<'info, TokenAccount>>,  // ✅ BOX
+    
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[event]
+pub struct LimitOrderCancelled {
+    pub pool: Pubkey,
+    pub owner: Pubkey,
+    pub order: Pubkey,
+    pub amount_in: u64,
+    pub timestamp: i64,
+}
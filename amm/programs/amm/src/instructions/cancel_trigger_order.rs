@@ -0,0 +1,114 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::state::{PoolState, TriggerOrder};
+use crate::error::ErrorCode;
+use crate::instructions::shared::{transfer_tokens_signed, close_token_account_signed};
+
+/// Returns the escrowed input of an unfired trigger order to its owner.
+pub fn cancel_trigger_order_handler(ctx: Context<CancelTriggerOrder>) -> Result<()> {
+    let trigger_order = &ctx.accounts.trigger_order;
+    
+    let pool_key = ctx.accounts.pool_state.key();
+    let owner_key = ctx.accounts.owner.key();
+    let trigger_price_bytes = trigger_order.trigger_price.to_le_bytes();
+    let trigger_order_seeds = &[
+        b"trigger_order",
+        pool_key.as_ref(),
+        owner_key.as_ref(),
+        trigger_price_bytes.as_ref(),
+        &[trigger_order.is_a_to_b as u8],
+        &[trigger_order.bump],
+    ];
+    let signer_seeds = &[&trigger_order_seeds[..]];
+    
+    // Refund the escrow balance rather than `amount_in` so the escrow can close.
+    let amount_in = ctx.accounts.trigger_escrow.amount;
+    
+    transfer_tokens_signed(
+        &ctx.accounts.trigger_escrow,
+        &ctx.accounts.user_token_in,
+        &ctx.accounts.input_mint,
+        &ctx.accounts.trigger_order.to_account_info(),
+        &ctx.accounts.token_program,
+        amount_in,
+        signer_seeds,
+    )?;
+    
+    close_token_account_signed(
+        &ctx.accounts.trigger_escrow,
+        &ctx.accounts.owner.to_account_info(),
+        &ctx.accounts.trigger_order.to_account_info(),
+        &ctx.accounts.token_program,
+        signer_seeds,
+    )?;
+    
+    ctx.accounts.trigger_order.close(ctx.accounts.owner.to_account_info())?;
+    
+    let clock = Clock::get()?;
+    emit!(TriggerOrderCancelled {
+        pool: pool_key,
+        owner: owner_key,
+        trigger_order: ctx.accounts.trigger_order.key(),
+        amount_in,
+        timestamp: clock.unix_timestamp,
+    });
+    
+    msg!("Trigger order cancelled: {} refunded", amount_in);
+    
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CancelTriggerOrder<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    
+    #[account(
+        seeds = [b"pool_state", pool_state.token_mint_a.as_ref(), pool_state.token_mint_b.as_ref()],
+        bump = pool_state.bump,
+    )]
+    pub pool_state: Box<Account<'info, PoolState>>,  // ✅ BOX
+    
+    #[account(address = trigger_escrow.mint)]
+    pub input_mint: Box<InterfaceAccount<'info, Mint>>,  // ✅ BOX
+    
+    #[account(
+        mut,
+        seeds = [
+            b"trigger_order",
+            pool_state.key().as_ref(),
+            owner.key().as_ref(),
+            &trigger_order.trigger_price.to_le_bytes(),
+            &[trigger_order.is_a_to_b as u8],
+        ],
+        bump = trigger_order.bump,
+        has_one = owner @ ErrorCode::Unauthorized,
+    )]
+    pub trigger_order: Box<Account<'info, TriggerOrder>>,  // ✅ BOX
+    
+    #[account(
+        mut,
+        seeds = [b"trigger_escrow", trigger_order.key().as_ref()],
+        bump = trigger_order.escrow_bump,
+    )]
+    pub trigger_escrow: Box<InterfaceAccount<'info, TokenAccount>>,  // ✅ BOX
+    
+    #[account(
+        mut,
+        token::mint = input_mint,
+        token::authority = owner,
+    )]
+    pub user_token_in: Box<InterfaceAccount<'info, TokenAccount>>,  // ✅ BOX
+    
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[event]
+pub struct TriggerOrderCancelled {
+    pub pool: Pubkey,
+    pub owner: Pubkey,
+    pub trigger_order: Pubkey,
+    pub amount_in: u64,
+    pub timestamp: i64,
+}
@@ -0,0 +1,191 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::state::{Batch, PoolState};
+use crate::error::ErrorCode;
+use crate::instructions::shared::{
+    transfer_tokens_signed, sweep_token_account_signed, update_price_oracle,
+};
+
+/// Pays out the output or refund of a settled batch order that settlement could
+/// not deliver. The last claim closes the batch and its escrows.
+pub fn claim_batch_order_handler(ctx: Context<ClaimBatchOrder>, order_index: u8) -> Result<()> {
+    let batch = &ctx.accounts.batch;
+    
+    require!(batch.settled, ErrorCode::BatchNotReady);
+    require!(order_index < batch.order_count, ErrorCode::InvalidBatchAccounts);
+    
+    let order = batch.orders[order_index as usize];
+    require_keys_eq!(order.owner, ctx.accounts.owner.key(), ErrorCode::Unauthorized);
+    require!(order.claimable_amount > 0, ErrorCode::NothingToClaim);
+    
+    // Filled orders claim their output, the others their input back.
+    let claims_token_a = if order.filled { !order.is_a_to_b } else { order.is_a_to_b };
+    let (escrow, mint) = if claims_token_a {
+        (&ctx.accounts.batch_escrow_a, &ctx.accounts.token_mint_a)
+    } else {
+        (&ctx.accounts.batch_escrow_b, &ctx.accounts.token_mint_b)
+    };
+    
+    require_keys_eq!(
+        ctx.accounts.owner_token.mint,
+        mint.key(),
+        ErrorCode::InvalidBatchAccounts
+    );
+    
+    let pool_key = ctx.accounts.pool_state.key();
+    let batch_id = batch.batch_id;
+    let batch_id_bytes = batch_id.to_le_bytes();
+    let batch_seeds = &[
+        b"batch",
+        pool_key.as_ref(),
+        batch_id_bytes.as_ref(),
+        &[batch.bump],
+    ];
+    let batch_signer = &[&batch_seeds[..]];
+    
+    let batch_info = ctx.accounts.batch.to_account_info();
+    
+    transfer_tokens_signed(
+        escrow,
+        &ctx.accounts.owner_token,
+        mint,
+        &batch_info,
+        &ctx.accounts.token_program,
+        order.claimable_amount,
+        batch_signer,
+    )?;
+    
+    let batch = &mut ctx.accounts.batch;
+    batch.orders[order_index as usize].claimable_amount = 0;
+    
+    let order_count = batch.order_count as usize;
+    let fully_claimed = batch.orders[..order_count]
+        .iter()
+        .all(|order| order.claimable_amount == 0);
+    
+    let clock = Clock::get()?;
+    emit!(BatchOrderClaimed {
+        pool: pool_key,
+        batch_id,
+        order_index,
+        owner: order.owner,
+        mint: mint.key(),
+        amount: order.claimable_amount,
+        timestamp: clock.unix_timestamp,
+    });
+    
+    msg!(
+        "Batch {} order {} claimed: {}",
+        batch_id,
+        order_index,
+        order.claimable_amount
+    );
+    
+    if fully_claimed {
+        ctx.accounts.batch_escrow_a.reload()?;
+        ctx.accounts.batch_escrow_b.reload()?;
+        
+        let swept_a = sweep_token_account_signed(
+            &ctx.accounts.batch_escrow_a,
+            &ctx.accounts.vault_a,
+            &ctx.accounts.token_mint_a,
+            &batch_info,
+            &ctx.accounts.creator,
+            &ctx.accounts.token_program,
+            batch_signer,
+        )?;
+        
+        let swept_b = sweep_token_account_signed(
+            &ctx.accounts.batch_escrow_b,
+            &ctx.accounts.vault_b,
+            &ctx.accounts.token_mint_b,
+            &batch_info,
+            &ctx.accounts.creator,
+            &ctx.accounts.token_program,
+            batch_signer,
+        )?;
+        
+        ctx.accounts.batch.close(ctx.accounts.creator.to_account_info())?;
+        
+        if swept_a > 0 || swept_b > 0 {
+            let pool_state = &mut ctx.accounts.pool_state;
+            update_price_oracle(pool_state, clock.unix_timestamp)?;
+            
+            pool_state.reserve_a = pool_state.reserve_a
+                .checked_add(swept_a)
+                .ok_or(ErrorCode::MathOverflow)?;
+            pool_state.reserve_b = pool_state.reserve_b
+                .checked_add(swept_b)
+                .ok_or(ErrorCode::MathOverflow)?;
+        }
+    }
+    
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ClaimBatchOrder<'info> {
+    pub owner: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"pool_state", pool_state.token_mint_a.as_ref(), pool_state.token_mint_b.as_ref()],
+        bump = pool_state.bump,
+    )]
+    pub pool_state: Box<Account<'info, PoolState>>,  // ✅ BOX
+    
+    #[account(address = pool_state.token_mint_a)]
+    pub token_mint_a: Box<InterfaceAccount<'info, Mint>>,  // ✅ BOX
+    
+    #[account(address = pool_state.token_mint_b)]
+    pub token_mint_b: Box<InterfaceAccount<'info, Mint>>,  // ✅ BOX
+    
+    #[account(mut, address = pool_state.vault_a)]
+    pub vault_a: Box<InterfaceAccount<'info, TokenAccount>>,  // ✅ BOX
+    
+    #[account(mut, address = pool_state.vault_b)]
+    pub vault_b: Box<InterfaceAccount<'info, TokenAccount>>,  // ✅ BOX
+    
+    #[account(
+        mut,
+        seeds = [b"batch", pool_state.key().as_ref(), &batch.batch_id.to_le_bytes()],
+        bump = batch.bump,
+    )]
+    pub batch: Box<Account<'info, Batch>>,  // ✅ BOX
+    
+    #[account(
+        mut,
+        seeds = [b"batch_escrow_a", batch.key().as_ref()],
+        bump = batch.escrow_a_bump,
+    )]
+    pub batch_escrow_a: Box<InterfaceAccount<'info, TokenAccount>>,  // ✅ BOX
+    
+    #[account(
+        mut,
+        seeds = [b"batch_escrow_b", batch.key().as_ref()],
+        bump = batch.escrow_b_bump,
+    )]
+    pub batch_escrow_b: Box<InterfaceAccount<'info, TokenAccount>>,  // ✅ BOX
+    
+    /// Receives the claim, any account of the claimed mint.
+    #[account(mut)]
+    pub owner_token: Box<InterfaceAccount<'info, TokenAccount>>,  // ✅ BOX
+    
+    /// CHECK: receives the rent of the batch accounts with the last claim
+    #[account(mut, address = batch.creator)]
+    pub creator: AccountInfo<'info>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[event]
+pub struct BatchOrderClaimed {
+    pub pool: Pubkey,
+    pub batch_id: u64,
+    pub order_index: u8,
+    pub owner: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
@@ -0,0 +1,108 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::state::{Farm, FarmStake};
+use crate::error::ErrorCode;
+use crate::instructions::shared::{transfer_tokens_signed, update_farm_rewards, settle_farm_stake};
+
+pub fn claim_rewards_handler(ctx: Context<ClaimRewards>, reward_index: u8) -> Result<()> {
+    let index = reward_index as usize;
+    let farm = &mut ctx.accounts.farm;
+    let stake = &mut ctx.accounts.stake;
+    
+    require!(index < farm.reward_count as usize, ErrorCode::InvalidRewardIndex);
+    require_keys_eq!(
+        ctx.accounts.reward_mint.key(),
+        farm.rewards[index].reward_mint,
+        ErrorCode::InvalidTokenMint
+    );
+    require_keys_eq!(
+        ctx.accounts.reward_vault.key(),
+        farm.rewards[index].reward_vault,
+        ErrorCode::TokenAccountMismatch
+    );
+    
+    let clock = Clock::get()?;
+    update_farm_rewards(farm, clock.unix_timestamp)?;
+    settle_farm_stake(farm, stake)?;
+    
+    let amount = stake.pending_rewards[index];
+    require!(amount > 0, ErrorCode::InvalidAmount);
+    
+    let pool_mint_key = farm.pool_mint;
+    let farm_seeds = &[
+        b"farm",
+        pool_mint_key.as_ref(),
+        &[farm.bump],
+    ];
+    let signer_seeds = &[&farm_seeds[..]];
+    
+    transfer_tokens_signed(
+        &ctx.accounts.reward_vault,
+        &ctx.accounts.user_reward_token,
+        &ctx.accounts.reward_mint,
+        &farm.to_account_info(),
+        &ctx.accounts.token_program,
+        amount,
+        signer_seeds,
+    )?;
+    
+    stake.pending_rewards[index] = 0;
+    
+    emit!(RewardsClaimed {
+        farm: farm.key(),
+        owner: stake.owner,
+        reward_index,
+        reward_mint: ctx.accounts.reward_mint.key(),
+        amount,
+        timestamp: clock.unix_timestamp,
+    });
+    
+    msg!("Rewards claimed: {} of reward {}", amount, reward_index);
+    
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ClaimRewards<'info> {
+    pub owner: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"farm", farm.pool_mint.as_ref()],
+        bump = farm.bump,
+    )]
+    pub farm: Box<Account<'info, Farm>>,  // ✅ BOX
+    
+    #[account(
+        mut,
+        seeds = [b"farm_stake", farm.key().as_ref(), owner.key().as_ref()],
+        bump = stake.bump,
+    )]
+    pub stake: Box<Account<'info, FarmStake>>,  // ✅ BOX
+    
+    #[account(mint::token_program = token_program)]
+    pub reward_mint: Box<InterfaceAccount<'info, Mint>>,  // ✅ BOX
+    
+    #[account(mut)]
+    pub reward_vault: Box<InterfaceAccount<'info, TokenAccount>>,  // ✅ BOX
+    
+    #[account(
+        mut,
+        token::mint = reward_mint,
+        token::authority = owner,
+    )]
+    pub user_reward_token: Box<InterfaceAccount<'info, TokenAccount>>,  // ✅ BOX
+    
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[event]
+pub struct RewardsClaimed {
+    pub farm: Pubkey,
+    pub owner: Pubkey,
+    pub reward_index: u8,
+    pub reward_mint: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
@@ -0,0 +1,47 @@
+use anchor_lang::prelude::*;
+
+use crate::state::RfqQuoteFill;
+use crate::error::ErrorCode;
+
+/// Returns the rent of a filled quote's replay marker to the taker. Only allowed
+/// after the quote has expired, when `swap_rfq` would reject it anyway.
+pub fn close_rfq_quote_fill_handler(ctx: Context<CloseRfqQuoteFill>) -> Result<()> {
+    let quote_fill = &ctx.accounts.quote_fill;
+    let clock = Clock::get()?;
+    
+    require!(clock.unix_timestamp > quote_fill.expiry, ErrorCode::QuoteNotExpired);
+    
+    emit!(RfqQuoteFillClosed {
+        maker: quote_fill.maker,
+        nonce: quote_fill.nonce,
+        taker: quote_fill.taker,
+        timestamp: clock.unix_timestamp,
+    });
+    
+    msg!("RFQ quote fill closed: maker {} nonce {}", quote_fill.maker, quote_fill.nonce);
+    
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CloseRfqQuoteFill<'info> {
+    #[account(mut)]
+    pub taker: Signer<'info>,
+    
+    #[account(
+        mut,
+        close = taker,
+        has_one = taker @ ErrorCode::Unauthorized,
+        seeds = [b"rfq_quote", quote_fill.maker.as_ref(), &quote_fill.nonce.to_le_bytes()],
+        bump = quote_fill.bump,
+    )]
+    pub quote_fill: Box<Account<'info, RfqQuoteFill>>,  // ✅ BOX
+}
+
+#[event]
+pub struct RfqQuoteFillClosed {
+    pub maker: Pubkey,
+    pub nonce: u64,
+    pub taker: Pubkey,
+    pub timestamp: i64,
+}
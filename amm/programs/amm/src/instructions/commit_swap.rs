@@ -0,0 +1,135 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use solana_sha256_hasher::hashv;
+
+use crate::state::{PoolState, SwapCommit};
+use crate::error::ErrorCode;
+use crate::instructions::shared::transfer_tokens;
+
+/// Slots that must pass between a commit and its reveal.
+pub const COMMIT_REVEAL_MIN_DELAY_SLOTS: u64 = 2;
+/// Slots after a commit from which it can no longer be revealed, only refunded.
+pub const COMMIT_REVEAL_TIMEOUT_SLOTS: u64 = 150;
+
+/// Hash a commitment must match: `sha256(amount_in || min_amount_out || salt || owner)`.
+pub fn swap_commitment_hash(
+    amount_in: u64,
+    min_amount_out: u64,
+    salt: &[u8; 32],
+    owner: &Pubkey,
+) -> [u8; 32] {
+    hashv(&[
+        &amount_in.to_le_bytes(),
+        &min_amount_out.to_le_bytes(),
+        salt.as_ref(),
+        owner.as_ref(),
+    ])
+    .to_bytes()
+}
+
+/// Locks `deposit` of the input token in escrow behind a hash of the swap
+/// parameters. The deposit may exceed the amount swapped to hide the order size.
+pub fn commit_swap_handler(ctx: Context<CommitSwap>, hash: [u8; 32], deposit: u64) -> Result<()> {
+    require!(deposit > 0, ErrorCode::InvalidAmount);
+    require!(
+        ctx.accounts.user_token.amount >= deposit,
+        ErrorCode::InsufficientBalance
+    );
+    
+    let pool_state = &ctx.accounts.pool_state;
+    let deposit_mint = ctx.accounts.deposit_mint.key();
+    require!(
+        deposit_mint == pool_state.token_mint_a || deposit_mint == pool_state.token_mint_b,
+        ErrorCode::InvalidTokenMint
+    );
+    
+    transfer_tokens(
+        &ctx.accounts.user_token,
+        &ctx.accounts.commit_escrow,
+        &ctx.accounts.deposit_mint,
+        &ctx.accounts.user,
+        &ctx.accounts.token_program,
+        deposit,
+    )?;
+    
+    let clock = Clock::get()?;
+    let commit = &mut ctx.accounts.commit;
+    commit.pool = pool_state.key();
+    commit.owner = ctx.accounts.user.key();
+    commit.hash = hash;
+    commit.deposit = deposit;
+    commit.is_a_to_b = deposit_mint == pool_state.token_mint_a;
+    commit.committed_slot = clock.slot;
+    commit.bump = ctx.bumps.commit;
+    commit.escrow_bump = ctx.bumps.commit_escrow;
+    
+    emit!(SwapCommitted {
+        pool: commit.pool,
+        owner: commit.owner,
+        commit: commit.key(),
+        deposit_mint,
+        deposit,
+        slot: clock.slot,
+        timestamp: clock.unix_timestamp,
+    });
+    
+    msg!("Swap committed: {} deposited at slot {}", deposit, clock.slot);
+    
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(hash: [u8; 32])]
+pub struct CommitSwap<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+    
+    #[account(
+        seeds = [b"pool_state", pool_state.token_mint_a.as_ref(), pool_state.token_mint_b.as_ref()],
+        bump = pool_state.bump,
+    )]
+    pub pool_state: Box<Account<'info, PoolState>>,  // ✅ BOX
+    
+    pub deposit_mint: Box<InterfaceAccount<'info, Mint>>,  // ✅ BOX
+    
+    #[account(
+        init,
+        payer = user,
+        space = 8 + SwapCommit::INIT_SPACE,
+        seeds = [b"swap_commit", pool_state.key().as_ref(), user.key().as_ref(), hash.as_ref()],
+        bump,
+    )]
+    pub commit: Box<Account<'info, SwapCommit>>,  // ✅ BOX
+    
+    #[account(
+        init,
+        payer = user,
+        seeds = [b"commit_escrow", commit.key().as_ref()],
+        bump,
+        token::mint = deposit_mint,
+        token::authority = commit,
+        token::token_program = token_program,
+    )]
+    pub commit_escrow: Box<InterfaceAccount<'info, TokenAccount>>,  // ✅ BOX
+    
+    #[account(
+        mut,
+        token::mint = deposit_mint,
+        token::authority = user,
+    )]
+    pub user_token: Box<InterfaceAccount<'info, TokenAccount>>,  // ✅ BOX
+    
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[event]
+pub struct SwapCommitted {
+    pub pool: Pubkey,
+    pub owner: Pubkey,
+    pub commit: Pubkey,
+    pub deposit_mint: Pubkey,
+    pub deposit: u64,
+    pub slot: u64,
+    pub timestamp: i64,
+}
@@ -0,0 +1,168 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::state::{AmmConfig, Farm, FarmReward, PoolState};
+use crate::error::ErrorCode;
+use crate::instructions::shared::{transfer_tokens, farm_reward_funding, add_reward_to_farm};
+
+pub fn create_farm_handler(
+    ctx: Context<CreateFarm>,
+    reward_per_second: u64,
+    start_time: i64,
+    end_time: i64,
+) -> Result<()> {
+    let clock = Clock::get()?;
+    let funding = farm_reward_funding(reward_per_second, start_time, end_time, clock.unix_timestamp)?;
+    
+    require!(
+        ctx.accounts.funder_reward_token.amount >= funding,
+        ErrorCode::InsufficientBalance
+    );
+    
+    transfer_tokens(
+        &ctx.accounts.funder_reward_token,
+        &ctx.accounts.reward_vault,
+        &ctx.accounts.reward_mint,
+        &ctx.accounts.authority,
+        &ctx.accounts.token_program,
+        funding,
+    )?;
+    
+    let farm = &mut ctx.accounts.farm;
+    farm.pool = ctx.accounts.pool_state.key();
+    farm.pool_mint = ctx.accounts.pool_mint.key();
+    farm.authority = ctx.accounts.authority.key();
+    farm.stake_vault = ctx.accounts.stake_vault.key();
+    farm.total_staked = 0;
+    farm.bump = ctx.bumps.farm;
+    farm.stake_vault_bump = ctx.bumps.stake_vault;
+    
+    add_reward_to_farm(
+        farm,
+        FarmReward {
+            reward_mint: ctx.accounts.reward_mint.key(),
+            reward_vault: ctx.accounts.reward_vault.key(),
+            reward_per_second,
+            start_time,
+            end_time,
+            reward_per_share: 0,
+            last_update_time: start_time,
+        },
+    )?;
+    
+    emit!(FarmCreated {
+        farm: farm.key(),
+        pool: farm.pool,
+        pool_mint: farm.pool_mint,
+        authority: farm.authority,
+        timestamp: clock.unix_timestamp,
+    });
+    
+    emit!(FarmRewardAdded {
+        farm: farm.key(),
+        reward_index: 0,
+        reward_mint: ctx.accounts.reward_mint.key(),
+        reward_per_second,
+        start_time,
+        end_time,
+        funding,
+        timestamp: clock.unix_timestamp,
+    });
+    
+    msg!(
+        "Farm created: {}/s from {} to {} (funded {})",
+        reward_per_second,
+        start_time,
+        end_time,
+        funding
+    );
+    
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CreateFarm<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    #[account(
+        seeds = [b"amm_config"],
+        bump = config.bump,
+        constraint = config.admin == authority.key() @ ErrorCode::Unauthorized,
+    )]
+    pub config: Box<Account<'info, AmmConfig>>,  // ✅ BOX
+    
+    #[account(
+        seeds = [b"pool_state", pool_state.token_mint_a.as_ref(), pool_state.token_mint_b.as_ref()],
+        bump = pool_state.bump,
+    )]
+    pub pool_state: Box<Account<'info, PoolState>>,  // ✅ BOX
+    
+    #[account(address = pool_state.pool_mint)]
+    pub pool_mint: Box<InterfaceAccount<'info, Mint>>,  // ✅ BOX
+    
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + Farm::INIT_SPACE,
+        seeds = [b"farm", pool_mint.key().as_ref()],
+        bump,
+    )]
+    pub farm: Box<Account<'info, Farm>>,  // ✅ BOX
+    
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"farm_stake_vault", farm.key().as_ref()],
+        bump,
+        token::mint = pool_mint,
+        token::authority = farm,
+        token::token_program = token_program,
+    )]
+    pub stake_vault: Box<InterfaceAccount<'info, TokenAccount>>,  // ✅ BOX
+    
+    #[account(mint::token_program = token_program)]
+    pub reward_mint: Box<InterfaceAccount<'info, Mint>>,  // ✅ BOX
+    
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"farm_reward_vault", farm.key().as_ref(), reward_mint.key().as_ref()],
+        bump,
+        token::mint = reward_mint,
+        token::authority = farm,
+        token::token_program = token_program,
+    )]
+    pub reward_vault: Box<InterfaceAccount<'info, TokenAccount>>,  // ✅ BOX
+    
+    #[account(
+        mut,
+        token::mint = reward_mint,
+        token::authority = authority,
+    )]
+    pub funder_reward_token: Box<InterfaceAccount<'info, TokenAccount>>,  // ✅ BOX
+    
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[event]
+pub struct FarmCreated {
+    pub farm: Pubkey,
+    pub pool: Pubkey,
+    pub pool_mint: Pubkey,
+    pub authority: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct FarmRewardAdded {
+    pub farm: Pubkey,
+    pub reward_index: u8,
+    pub reward_mint: Pubkey,
+    pub reward_per_second: u64,
+    pub start_time: i64,
+    pub end_time: i64,
+    pub funding: u64,
+    pub timestamp: i64,
+}
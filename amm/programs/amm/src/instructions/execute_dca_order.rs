@@ -0,0 +1,145 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::TokenAccount;
+
+use crate::state::DcaOrder;
+use crate::error::ErrorCode;
+use crate::instructions::swap::*;
+use crate::instructions::shared::transfer_tokens_signed;
+
+/// Runs the next due slice of a DCA order. The crank signing as `swap.user`
+/// receives the slice, swaps it and forwards the output to the order's owner.
+pub fn execute_dca_order_handler(ctx: Context<ExecuteDcaOrder>) -> Result<()> {
+    let dca_order = &ctx.accounts.dca_order;
+    let clock = Clock::get()?;
+    
+    require!(dca_order.remaining_amount > 0, ErrorCode::DcaOrderEmpty);
+    require!(
+        clock.unix_timestamp >= dca_order.next_fill_at,
+        ErrorCode::DcaFillNotDue
+    );
+    
+    let is_a_to_b = dca_order.is_a_to_b;
+    let amount_in = dca_order.amount_per_interval.min(dca_order.remaining_amount);
+    
+    let min_amount_out = (dca_order.min_amount_out as u128)
+        .checked_mul(amount_in as u128)
+        .ok_or(ErrorCode::MathOverflow)?
+        .checked_div(dca_order.amount_per_interval as u128)
+        .ok_or(ErrorCode::DivisionByZero)?
+        .max(1) as u64;
+    
+    let pool_key = ctx.accounts.swap.pool_state.key();
+    let owner_key = dca_order.owner;
+    let dca_order_seeds = &[
+        b"dca_order",
+        pool_key.as_ref(),
+        owner_key.as_ref(),
+        &[is_a_to_b as u8],
+        &[dca_order.bump],
+    ];
+    let signer_seeds = &[&dca_order_seeds[..]];
+    
+    let swap = &mut ctx.accounts.swap;
+    let (keeper_in, mint_in) = if is_a_to_b {
+        (&swap.user_token_a, &swap.token_mint_a)
+    } else {
+        (&swap.user_token_b, &swap.token_mint_b)
+    };
+    
+    transfer_tokens_signed(
+        &ctx.accounts.dca_escrow,
+        keeper_in,
+        mint_in,
+        &ctx.accounts.dca_order.to_account_info(),
+        &swap.token_program,
+        amount_in,
+        signer_seeds,
+    )?;
+    
+    let result = process_keeper_swap(
+        swap,
+        amount_in,
+        min_amount_out,
+        is_a_to_b,
+        &ctx.accounts.owner_token_out,
+    )?;
+    
+    let dca_order = &mut ctx.accounts.dca_order;
+    dca_order.remaining_amount = dca_order.remaining_amount
+        .checked_sub(amount_in)
+        .ok_or(ErrorCode::MathOverflow)?;
+    dca_order.next_fill_at = clock.unix_timestamp
+        .checked_add(dca_order.interval)
+        .ok_or(ErrorCode::MathOverflow)?;
+    dca_order.fill_count = dca_order.fill_count
+        .checked_add(1)
+        .ok_or(ErrorCode::MathOverflow)?;
+    dca_order.total_amount_in = dca_order.total_amount_in
+        .checked_add(amount_in)
+        .ok_or(ErrorCode::MathOverflow)?;
+    dca_order.total_amount_out = dca_order.total_amount_out
+        .checked_add(result.amount_out)
+        .ok_or(ErrorCode::MathOverflow)?;
+    
+    emit!(DcaOrderFilled {
+        pool: pool_key,
+        owner: owner_key,
+        dca_order: dca_order.key(),
+        keeper: ctx.accounts.swap.user.key(),
+        amount_in,
+        amount_out: result.amount_out,
+        remaining_amount: dca_order.remaining_amount,
+        fill_count: dca_order.fill_count,
+        next_fill_at: dca_order.next_fill_at,
+        timestamp: clock.unix_timestamp,
+    });
+    
+    msg!(
+        "DCA fill {}: {}→{} ({} left)",
+        dca_order.fill_count, amount_in, result.amount_out, dca_order.remaining_amount
+    );
+    
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ExecuteDcaOrder<'info> {
+    /// Swap accounts with the crank as `user`.
+    pub swap: Swap<'info>,
+    
+    #[account(
+        mut,
+        seeds = [
+            b"dca_order",
+            swap.pool_state.key().as_ref(),
+            dca_order.owner.as_ref(),
+            &[dca_order.is_a_to_b as u8],
+        ],
+        bump = dca_order.bump,
+    )]
+    pub dca_order: Box<Account<'info, DcaOrder>>,  // ✅ BOX
+    
+    #[account(
+        mut,
+        seeds = [b"dca_escrow", dca_order.key().as_ref()],
+        bump = dca_order.escrow_bump,
+    )]
+    pub dca_escrow: Box<InterfaceAccount<'info, TokenAccount>>,  // ✅ BOX
+    
+    #[account(mut, address = dca_order.token_out_account)]
+    pub owner_token_out: Box<InterfaceAccount<'info, TokenAccount>>,  // ✅ BOX
+}
+
+#[event]
+pub struct DcaOrderFilled {
+    pub pool: Pubkey,
+    pub owner: Pubkey,
+    pub dca_order: Pubkey,
+    pub keeper: Pubkey,
+    pub amount_in: u64,
+    pub amount_out: u64,
+    pub remaining_amount: u64,
+    pub fill_count: u64,
+    pub next_fill_at: i64,
+    pub timestamp: i64,
+}
@@ -0,0 +1,169 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::TokenAccount;
+
+use crate::state::Order;
+use crate::error::ErrorCode;
+use crate::instructions::swap::*;
+use crate::instructions::place_limit_order::LIMIT_ORDER_KEEPER_TIP_BPS;
+use crate::instructions::shared::{
+    transfer_tokens_signed, close_token_account_signed, spot_price,
+    BPS_DENOMINATOR, PRICE_SCALE,
+};
+
+/// Fills a limit order once the pool's spot price has reached its target. The
+/// keeper signing as `swap.user` receives the escrow, keeps its tip, swaps the
+/// rest and forwards the output to the order's owner.
+pub fn execute_limit_order_handler(ctx: Context<ExecuteLimitOrder>) -> Result<()> {
+    let order = &ctx.accounts.order;
+    let clock = Clock::get()?;
+    
+    require!(clock.unix_timestamp <= order.expiry, ErrorCode::OrderExpired);
+    
+    let is_a_to_b = order.is_a_to_b;
+    let amount_in = order.amount_in;
+    let target_price = order.target_price;
+    
+    let pool_key = ctx.accounts.swap.pool_state.key();
+    let owner_key = order.owner;
+    let order_id_bytes = order.order_id.to_le_bytes();
+    let order_seeds = &[
+        b"order",
+        pool_key.as_ref(),
+        owner_key.as_ref(),
+        order_id_bytes.as_ref(),
+        &[order.bump],
+    ];
+    let signer_seeds = &[&order_seeds[..]];
+    
+    let swap = &mut ctx.accounts.swap;
+    let (reserve_in, reserve_out) = if is_a_to_b {
+        (swap.pool_state.reserve_a, swap.pool_state.reserve_b)
+    } else {
+        (swap.pool_state.reserve_b, swap.pool_state.reserve_a)
+    };
+    
+    require!(
+        spot_price(reserve_in, reserve_out)? >= target_price,
+        ErrorCode::LimitPriceNotReached
+    );
+    
+    let keeper_tip = amount_in
+        .checked_mul(LIMIT_ORDER_KEEPER_TIP_BPS)
+        .ok_or(ErrorCode::MathOverflow)?
+        .checked_div(BPS_DENOMINATOR)
+        .ok_or(ErrorCode::DivisionByZero)?;
+    
+    let swap_amount = amount_in
+        .checked_sub(keeper_tip)
+        .ok_or(ErrorCode::MathOverflow)?;
+    
+    // The fill itself, fees included, must pay at least the target price.
+    let min_amount_out = (swap_amount as u128)
+        .checked_mul(target_price as u128)
+        .ok_or(ErrorCode::MathOverflow)?
+        .checked_div(PRICE_SCALE)
+        .ok_or(ErrorCode::DivisionByZero)?;
+    let min_amount_out = u64::try_from(min_amount_out)
+        .map_err(|_| ErrorCode::MathOverflow)?
+        .max(1);
+    
+    let (keeper_in, mint_in) = if is_a_to_b {
+        (&swap.user_token_a, &swap.token_mint_a)
+    } else {
+        (&swap.user_token_b, &swap.token_mint_b)
+    };
+    
+    // The keeper takes the whole escrow, so anything sent to it on top of the
+    // order lets the escrow close and adds to the tip.
+    transfer_tokens_signed(
+        &ctx.accounts.order_escrow,
+        keeper_in,
+        mint_in,
+        &ctx.accounts.order.to_account_info(),
+        &swap.token_program,
+        ctx.accounts.order_escrow.amount,
+        signer_seeds,
+    )?;
+    
+    close_token_account_signed(
+        &ctx.accounts.order_escrow,
+        &ctx.accounts.owner,
+        &ctx.accounts.order.to_account_info(),
+        &swap.token_program,
+        signer_seeds,
+    )?;
+    
+    ctx.accounts.order.close(ctx.accounts.owner.to_account_info())?;
+    
+    let result = process_keeper_swap(
+        swap,
+        swap_amount,
+        min_amount_out,
+        is_a_to_b,
+        &ctx.accounts.owner_token_out,
+    )?;
+    
+    emit!(LimitOrderExecuted {
+        pool: pool_key,
+        owner: owner_key,
+        order: ctx.accounts.order.key(),
+        keeper: swap.user.key(),
+        amount_in,
+        amount_out: result.amount_out,
+        keeper_tip,
+        target_price,
+        timestamp: clock.unix_timestamp,
+    });
+    
+    msg!(
+        "Limit order executed: {}→{} (tip: {})",
+        amount_in, result.amount_out, keeper_tip
+    );
+    
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ExecuteLimitOrder<'info> {
+    /// Swap accounts with the keeper as `user`.
+    pub swap: Swap<'info>,
+    
+    #[account(
+        mut,
+        seeds = [
+            b"order",
+            swap.pool_state.key().as_ref(),
+            order.owner.as_ref(),
+            &order.order_id.to_le_bytes(),
+        ],
+        bump = order.bump,
+    )]
+    pub order: Box<Account<'info, Order>>,  // ✅ BOX
+    
+    #[account(
+        mut,
+        seeds = [b"order_escrow", order.key().as_ref()],
+        bump = order.escrow_bump,
+    )]
+    pub order_escrow: Box<InterfaceAccount<'info, TokenAccount>>,  // ✅ BOX
+    
+    /// CHECK: order owner, receives the rent of the order accounts
+    #[account(mut, address = order.owner)]
+    pub owner: AccountInfo<'info>,
+    
+    #[account(mut, address = order.token_out_account)]
+    pub owner_token_out: Box<InterfaceAccount<'info, TokenAccount>>,  // ✅ BOX
+}
+
+#[event]
+pub struct LimitOrderExecuted {
+    pub pool: Pubkey,
+    pub owner: Pubkey,
+    pub order: Pubkey,
+    pub keeper: Pubkey,
+    pub amount_in: u64,
+    pub amount_out: u64,
+    pub keeper_tip: u64,
+    pub target_price: u64,
+    pub timestamp: i64,
+}
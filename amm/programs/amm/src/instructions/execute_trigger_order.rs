@@ -0,0 +1,152 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::TokenAccount;
+
+use crate::state::{TriggerKind, TriggerOrder};
+use crate::error::ErrorCode;
+use crate::instructions::swap::*;
+use crate::instructions::shared::{
+    transfer_tokens_signed, close_token_account_signed, spot_price, twap_price,
+};
+
+/// Fills a trigger order once its price condition holds. The keeper signing as
+/// `swap.user` receives the escrow, swaps it and forwards the output to the owner.
+pub fn execute_trigger_order_handler(ctx: Context<ExecuteTriggerOrder>) -> Result<()> {
+    let trigger_order = &ctx.accounts.trigger_order;
+    let clock = Clock::get()?;
+    
+    let is_a_to_b = trigger_order.is_a_to_b;
+    // Swap the escrow balance rather than `amount_in` so nothing is left behind
+    // in the escrow when it is closed.
+    let amount_in = ctx.accounts.trigger_escrow.amount;
+    let pool_state = &ctx.accounts.swap.pool_state;
+    
+    let price = if trigger_order.twap_window > 0 {
+        twap_price(pool_state, is_a_to_b, trigger_order.twap_window, clock.unix_timestamp)?
+    } else if is_a_to_b {
+        spot_price(pool_state.reserve_a, pool_state.reserve_b)?
+    } else {
+        spot_price(pool_state.reserve_b, pool_state.reserve_a)?
+    };
+    
+    let triggered = match trigger_order.kind {
+        TriggerKind::StopLoss => price <= trigger_order.trigger_price,
+        TriggerKind::TakeProfit => price >= trigger_order.trigger_price,
+    };
+    require!(triggered, ErrorCode::TriggerNotReached);
+    
+    let pool_key = pool_state.key();
+    let owner_key = trigger_order.owner;
+    let trigger_price = trigger_order.trigger_price;
+    let trigger_price_bytes = trigger_price.to_le_bytes();
+    let trigger_order_seeds = &[
+        b"trigger_order",
+        pool_key.as_ref(),
+        owner_key.as_ref(),
+        trigger_price_bytes.as_ref(),
+        &[is_a_to_b as u8],
+        &[trigger_order.bump],
+    ];
+    let signer_seeds = &[&trigger_order_seeds[..]];
+    let min_amount_out = trigger_order.min_amount_out;
+    
+    let swap = &mut ctx.accounts.swap;
+    let (keeper_in, mint_in) = if is_a_to_b {
+        (&swap.user_token_a, &swap.token_mint_a)
+    } else {
+        (&swap.user_token_b, &swap.token_mint_b)
+    };
+    
+    transfer_tokens_signed(
+        &ctx.accounts.trigger_escrow,
+        keeper_in,
+        mint_in,
+        &ctx.accounts.trigger_order.to_account_info(),
+        &swap.token_program,
+        amount_in,
+        signer_seeds,
+    )?;
+    
+    close_token_account_signed(
+        &ctx.accounts.trigger_escrow,
+        &ctx.accounts.owner,
+        &ctx.accounts.trigger_order.to_account_info(),
+        &swap.token_program,
+        signer_seeds,
+    )?;
+    
+    ctx.accounts.trigger_order.close(ctx.accounts.owner.to_account_info())?;
+    
+    let result = process_keeper_swap(
+        swap,
+        amount_in,
+        min_amount_out,
+        is_a_to_b,
+        &ctx.accounts.owner_token_out,
+    )?;
+    
+    emit!(TriggerOrderExecuted {
+        pool: pool_key,
+        owner: owner_key,
+        trigger_order: ctx.accounts.trigger_order.key(),
+        keeper: swap.user.key(),
+        amount_in,
+        amount_out: result.amount_out,
+        trigger_price,
+        price,
+        timestamp: clock.unix_timestamp,
+    });
+    
+    msg!(
+        "Trigger order executed at {}: {}→{}",
+        price, amount_in, result.amount_out
+    );
+    
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ExecuteTriggerOrder<'info> {
+    /// Swap accounts with the keeper as `user`.
+    pub swap: Swap<'info>,
+    
+    #[account(
+        mut,
+        seeds = [
+            b"trigger_order",
+            swap.pool_state.key().as_ref(),
+            trigger_order.owner.as_ref(),
+            &trigger_order.trigger_price.to_le_bytes(),
+            &[trigger_order.is_a_to_b as u8],
+        ],
+        bump = trigger_order.bump,
+    )]
+    pub trigger_order: Box<Account<'info, TriggerOrder>>,  // ✅ BOX
+    
+    #[account(
+        mut,
+        seeds = [b"trigger_escrow", trigger_order.key().as_ref()],
+        bump = trigger_order.escrow_bump,
+    )]
+    pub trigger_escrow: Box<InterfaceAccount<'info, TokenAccount>>,  // ✅ BOX
+    
+    /// CHECK: order owner, receives the rent of the order accounts
+    #[account(mut, address = trigger_order.owner)]
+    pub owner: AccountInfo<'info>,
+    
+    #[account(mut, address = trigger_order.token_out_account)]
+    pub owner_token_out: Box<InterfaceAccount<'info, TokenAccount>>,  // ✅ BOX
+}
+
+#[event]
+pub struct TriggerOrderExecuted {
+    pub pool: Pubkey,
+    pub owner: Pubkey,
+    pub trigger_order: Pubkey,
+    pub keeper: Pubkey,
+    pub amount_in: u64,
+    pub amount_out: u64,
+    pub trigger_price: u64,
+    /// Spot price or TWAP that met the trigger.
+    pub price: u64,
+    pub timestamp: i64,
+}
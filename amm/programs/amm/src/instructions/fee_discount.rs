@@ -0,0 +1,101 @@
+use anchor_lang::prelude::*;
+
+use crate::state::{AmmConfig, FeeDiscount};
+use crate::error::ErrorCode;
+use crate::instructions::shared::BPS_DENOMINATOR;
+
+/// Grants `trader` a discount on swap fees, or updates an existing one.
+pub fn set_fee_discount_handler(
+    ctx: Context<SetFeeDiscount>,
+    trader: Pubkey,
+    discount_bps: u64,
+) -> Result<()> {
+    require!(
+        discount_bps <= BPS_DENOMINATOR,
+        ErrorCode::InvalidFeeDiscount
+    );
+    
+    let fee_discount = &mut ctx.accounts.fee_discount;
+    fee_discount.trader = trader;
+    fee_discount.discount_bps = discount_bps;
+    fee_discount.bump = ctx.bumps.fee_discount;
+    
+    let clock = Clock::get()?;
+    emit!(FeeDiscountUpdated {
+        trader,
+        discount_bps,
+        timestamp: clock.unix_timestamp,
+    });
+    
+    msg!("Fee discount set: {} -> {}bps", trader, discount_bps);
+    
+    Ok(())
+}
+
+pub fn remove_fee_discount_handler(ctx: Context<RemoveFeeDiscount>) -> Result<()> {
+    let trader = ctx.accounts.fee_discount.trader;
+    ctx.accounts.fee_discount.close(ctx.accounts.admin.to_account_info())?;
+    
+    let clock = Clock::get()?;
+    emit!(FeeDiscountUpdated {
+        trader,
+        discount_bps: 0,
+        timestamp: clock.unix_timestamp,
+    });
+    
+    msg!("Fee discount removed: {}", trader);
+    
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(trader: Pubkey)]
+pub struct SetFeeDiscount<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    
+    #[account(
+        seeds = [b"amm_config"],
+        bump = config.bump,
+        has_one = admin @ ErrorCode::Unauthorized,
+    )]
+    pub config: Box<Account<'info, AmmConfig>>,  // ✅ BOX
+    
+    #[account(
+        init_if_needed,
+        payer = admin,
+        space = 8 + FeeDiscount::INIT_SPACE,
+        seeds = [b"fee_discount", trader.as_ref()],
+        bump,
+    )]
+    pub fee_discount: Box<Account<'info, FeeDiscount>>,  // ✅ BOX
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RemoveFeeDiscount<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    
+    #[account(
+        seeds = [b"amm_config"],
+        bump = config.bump,
+        has_one = admin @ ErrorCode::Unauthorized,
+    )]
+    pub config: Box<Account<'info, AmmConfig>>,  // ✅ BOX
+    
+    #[account(
+        mut,
+        seeds = [b"fee_discount", fee_discount.trader.as_ref()],
+        bump = fee_discount.bump,
+    )]
+    pub fee_discount: Box<Account<'info, FeeDiscount>>,  // ✅ BOX
+}
+
+#[event]
+pub struct FeeDiscountUpdated {
+    pub trader: Pubkey,
+    pub discount_bps: u64,
+    pub timestamp: i64,
+}
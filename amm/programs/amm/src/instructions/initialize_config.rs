@@ -0,0 +1,60 @@
+use anchor_lang::prelude::*;
+
+use crate::state::{AmmConfig, HolderDiscountTier, MAX_HOLDER_DISCOUNT_TIERS};
+use crate::error::ErrorCode;
+use crate::program::Amm;
+
+/// Creates the program-wide config. Only the program's upgrade authority can call it.
+pub fn initialize_config_handler(ctx: Context<InitializeConfig>, admin: Pubkey) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    config.admin = admin;
+    config.discount_mint = Pubkey::default();
+    config.holder_discount_tier_count = 0;
+    config.holder_discount_tiers = [HolderDiscountTier::default(); MAX_HOLDER_DISCOUNT_TIERS];
+    config.bump = ctx.bumps.config;
+    
+    let clock = Clock::get()?;
+    emit!(ConfigInitialized {
+        config: config.key(),
+        admin,
+        timestamp: clock.unix_timestamp,
+    });
+    
+    msg!("Config initialized: admin={}", admin);
+    
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct InitializeConfig<'info> {
+    #[account(mut)]
+    pub upgrade_authority: Signer<'info>,
+    
+    #[account(
+        init,
+        payer = upgrade_authority,
+        space = 8 + AmmConfig::INIT_SPACE,
+        seeds = [b"amm_config"],
+        bump,
+    )]
+    pub config: Box<Account<'info, AmmConfig>>,  // ✅ BOX
+    
+    #[account(
+        constraint = program.programdata_address()? == Some(program_data.key()) @ ErrorCode::Unauthorized,
+    )]
+    pub program: Program<'info, Amm>,
+    
+    #[account(
+        constraint = program_data.upgrade_authority_address == Some(upgrade_authority.key()) @ ErrorCode::Unauthorized,
+    )]
+    pub program_data: Account<'info, ProgramData>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[event]
+pub struct ConfigInitialized {
+    pub config: Pubkey,
+    pub admin: Pubkey,
+    pub timestamp: i64,
+}
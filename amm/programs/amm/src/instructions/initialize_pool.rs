@@ -1,21 +1,62 @@
 use crate::error::ErrorCode;
-use crate::state::PoolState;
+use crate::instructions::shared::{
+    lp_mint_decimals, minimum_liquidity, validate_fee_tiers, BPS_DENOMINATOR,
+};
+use crate::state::{
+    FeeTier, PoolState, PriceObservation, VolumeDiscountTier, MAX_FEE_TIERS, MAX_PRICE_OBSERVATIONS,
+    MAX_RFQ_MAKERS, MAX_SANDWICH_EXEMPT_PROGRAMS, MAX_VOLUME_DISCOUNT_TIERS,
+};
 use anchor_lang::prelude::*;
 use anchor_spl::{
     associated_token::AssociatedToken,
+    metadata::{
+        create_metadata_accounts_v3,
+        mpl_token_metadata::{types::DataV2, MAX_NAME_LENGTH, MAX_URI_LENGTH},
+        CreateMetadataAccountsV3, Metadata, MetadataAccount,
+    },
     token_interface::{Mint, TokenAccount, TokenInterface},
 };
 
+const LP_TOKEN_SYMBOL: &str = "AMM-LP";
+const MINT_LABEL_FALLBACK_LENGTH: usize = 4;
+
+#[allow(clippy::too_many_arguments)]
 pub fn initialize_pool_handler(
     ctx: Context<InitializePool>,
-    fee_numerator: u64,
+    fee_numerator_a_to_b: u64,
+    fee_numerator_b_to_a: u64,
     fee_denominator: u64,
+    referral_fee_bps: u64,
+    max_price_impact_bps: u64,
+    lp_metadata_uri: String,
+    fee_tiers: Vec<FeeTier>,
 ) -> Result<()> {
     require!(fee_denominator > 0, ErrorCode::InvalidFeeParameters);
     require!(
-        fee_numerator <= fee_denominator / 100,
+        fee_numerator_a_to_b <= fee_denominator / 100,
         ErrorCode::FeeTooHigh
     );
+    require!(
+        fee_numerator_b_to_a <= fee_denominator / 100,
+        ErrorCode::FeeTooHigh
+    );
+    validate_fee_tiers(
+        &fee_tiers,
+        fee_numerator_a_to_b.min(fee_numerator_b_to_a),
+        fee_denominator,
+    )?;
+    require!(
+        referral_fee_bps <= BPS_DENOMINATOR,
+        ErrorCode::InvalidReferralFee
+    );
+    require!(
+        max_price_impact_bps <= BPS_DENOMINATOR,
+        ErrorCode::InvalidPriceImpactLimit
+    );
+    require!(
+        lp_metadata_uri.len() <= MAX_URI_LENGTH,
+        ErrorCode::InvalidMetadata
+    );
     require_keys_neq!(
         ctx.accounts.token_mint_a.key(),
         ctx.accounts.token_mint_b.key()
@@ -32,16 +73,88 @@ pub fn initialize_pool_handler(
     pool_state.vault_b = ctx.accounts.vault_b.key();
     pool_state.pool_mint = ctx.accounts.pool_mint.key();
 
-    pool_state.fee_numerator = fee_numerator;
+    pool_state.fee_numerator_a_to_b = fee_numerator_a_to_b;
+    pool_state.fee_numerator_b_to_a = fee_numerator_b_to_a;
     pool_state.fee_denominator = fee_denominator;
+    pool_state.referral_fee_bps = referral_fee_bps;
+    pool_state.max_price_impact_bps = max_price_impact_bps;
+    pool_state.max_slot_price_move_bps = 0;
+    pool_state.slot_reference_price = 0;
+    pool_state.slot_reference_slot = 0;
+    pool_state.sandwich_protection = false;
+    pool_state.sandwich_exempt_program_count = 0;
+    pool_state.sandwich_exempt_programs = [Pubkey::default(); MAX_SANDWICH_EXEMPT_PROGRAMS];
+    pool_state.rfq_maker_count = 0;
+    pool_state.rfq_makers = [Pubkey::default(); MAX_RFQ_MAKERS];
+    pool_state.batch_auction_slots = 0;
+    pool_state.current_batch_id = 0;
+    pool_state.max_fee_numerator = 0;
+    pool_state.volatility_cap_bps = 0;
+    pool_state.volatility_decay_period = 0;
+    pool_state.volatility_accumulator = 0;
+    pool_state.volatility_updated_at = 0;
+    pool_state.fee_tier_count = fee_tiers.len() as u8;
+    pool_state.fee_tiers = [FeeTier::default(); MAX_FEE_TIERS];
+    pool_state.fee_tiers[..fee_tiers.len()].copy_from_slice(&fee_tiers);
+    pool_state.volume_discount_tier_count = 0;
+    pool_state.volume_discount_tiers = [VolumeDiscountTier::default(); MAX_VOLUME_DISCOUNT_TIERS];
     pool_state.reserve_a = 0;
     pool_state.reserve_b = 0;
     pool_state.total_supply = 0;
+    pool_state.minimum_liquidity = minimum_liquidity(ctx.accounts.pool_mint.decimals)?;
+    pool_state.price_a_cumulative = 0;
+    pool_state.price_b_cumulative = 0;
+    pool_state.price_cumulative_updated_at = 0;
+    pool_state.price_observation_index = 0;
+    pool_state.price_observations = [PriceObservation::default(); MAX_PRICE_OBSERVATIONS];
 
     pool_state.bump = ctx.bumps.pool_state;
     pool_state.authority_bump = ctx.bumps.pool_authority;
     pool_state.pool_mint_bump = ctx.bumps.pool_mint;
 
+    let lp_name = lp_token_name(
+        &ctx.accounts.token_mint_a.key(),
+        ctx.accounts.token_mint_a_metadata.as_deref(),
+        &ctx.accounts.token_mint_b.key(),
+        ctx.accounts.token_mint_b_metadata.as_deref(),
+    );
+    
+    let pool_key = pool_state.key();
+    let authority_seeds = &[
+        b"authority",
+        pool_key.as_ref(),
+        &[pool_state.authority_bump],
+    ];
+    let signer_seeds = &[&authority_seeds[..]];
+    
+    create_metadata_accounts_v3(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_metadata_program.to_account_info(),
+            CreateMetadataAccountsV3 {
+                metadata: ctx.accounts.lp_metadata.to_account_info(),
+                mint: ctx.accounts.pool_mint.to_account_info(),
+                mint_authority: ctx.accounts.pool_authority.to_account_info(),
+                payer: ctx.accounts.payer.to_account_info(),
+                update_authority: ctx.accounts.pool_authority.to_account_info(),
+                system_program: ctx.accounts.system_program.to_account_info(),
+                rent: ctx.accounts.rent.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        DataV2 {
+            name: lp_name.clone(),
+            symbol: LP_TOKEN_SYMBOL.to_string(),
+            uri: lp_metadata_uri,
+            seller_fee_basis_points: 0,
+            creators: None,
+            collection: None,
+            uses: None,
+        },
+        true,
+        true,
+        None,
+    )?;
+
     let clock = Clock::get()?;
 
     emit!(PoolCreated {
@@ -55,9 +168,14 @@ pub fn initialize_pool_handler(
         vault_a: pool_state.vault_a,
         vault_b: pool_state.vault_b,
         pool_mint: pool_state.pool_mint,
+        lp_decimals: ctx.accounts.pool_mint.decimals,
 
-        fee_numerator,
+        fee_numerator_a_to_b,
+        fee_numerator_b_to_a,
         fee_denominator,
+        referral_fee_bps,
+        max_price_impact_bps,
+        fee_tiers,
 
         timestamp: clock.unix_timestamp,
 
@@ -67,8 +185,11 @@ pub fn initialize_pool_handler(
     });
 
     msg!(
-        "Pool initialized: fee={}/{}, creator={}, timestamp={}",
-        fee_numerator,
+        "Pool initialized: {}, fee={}/{} (A→B), {}/{} (B→A), creator={}, timestamp={}",
+        lp_name,
+        fee_numerator_a_to_b,
+        fee_denominator,
+        fee_numerator_b_to_a,
         fee_denominator,
         ctx.accounts.payer.key(),
         clock.unix_timestamp
@@ -77,6 +198,36 @@ pub fn initialize_pool_handler(
     Ok(())
 }
 
+/// Builds the LP token name from the underlying symbols, e.g. "AMM-LP USDC/SOL".
+/// Mints without Metaplex metadata are labelled by the start of their address.
+fn lp_token_name(
+    mint_a: &Pubkey,
+    metadata_a: Option<&Account<MetadataAccount>>,
+    mint_b: &Pubkey,
+    metadata_b: Option<&Account<MetadataAccount>>,
+) -> String {
+    let mut name = format!(
+        "{} {}/{}",
+        LP_TOKEN_SYMBOL,
+        mint_label(mint_a, metadata_a),
+        mint_label(mint_b, metadata_b),
+    );
+    name.truncate(MAX_NAME_LENGTH);
+    name
+}
+
+fn mint_label(mint: &Pubkey, metadata: Option<&Account<MetadataAccount>>) -> String {
+    let symbol = metadata
+        .map(|metadata| metadata.symbol.trim_matches(char::from(0)).trim().to_string())
+        .unwrap_or_default();
+    
+    if symbol.is_empty() {
+        mint.to_string()[..MINT_LABEL_FALLBACK_LENGTH].to_string()
+    } else {
+        symbol
+    }
+}
+
 #[derive(Accounts)]
 pub struct InitializePool<'info> {
     #[account(mut)]
@@ -109,12 +260,37 @@ pub struct InitializePool<'info> {
         payer = payer,
         seeds = [b"pool_mint", pool_state.key().as_ref()],
         bump,
-        mint::decimals = 9,
+        mint::decimals = lp_mint_decimals(token_mint_a.decimals, token_mint_b.decimals),
         mint::authority = pool_authority,
         mint::token_program = token_program,
     )]
     pub pool_mint: Box<InterfaceAccount<'info, Mint>>,  // ✅ BOX
 
+    /// Metaplex metadata of token A, used to name the LP token when present
+    #[account(
+        seeds = [b"metadata", token_metadata_program.key().as_ref(), token_mint_a.key().as_ref()],
+        seeds::program = token_metadata_program.key(),
+        bump,
+    )]
+    pub token_mint_a_metadata: Option<Box<Account<'info, MetadataAccount>>>,  // ✅ BOX
+
+    /// Metaplex metadata of token B, used to name the LP token when present
+    #[account(
+        seeds = [b"metadata", token_metadata_program.key().as_ref(), token_mint_b.key().as_ref()],
+        seeds::program = token_metadata_program.key(),
+        bump,
+    )]
+    pub token_mint_b_metadata: Option<Box<Account<'info, MetadataAccount>>>,  // ✅ BOX
+
+    /// CHECK: Metaplex metadata account for the LP mint, created by the token metadata program
+    #[account(
+        mut,
+        seeds = [b"metadata", token_metadata_program.key().as_ref(), pool_mint.key().as_ref()],
+        seeds::program = token_metadata_program.key(),
+        bump,
+    )]
+    pub lp_metadata: UncheckedAccount<'info>,
+
     #[account(
         init,
         payer = payer,
@@ -136,6 +312,8 @@ pub struct InitializePool<'info> {
     pub system_program: Program<'info, System>,
     pub token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_metadata_program: Program<'info, Metadata>,
+    pub rent: Sysvar<'info, Rent>,
 }
 
 #[event]
@@ -150,9 +328,14 @@ pub struct PoolCreated {
     pub vault_a: Pubkey,
     pub vault_b: Pubkey,
     pub pool_mint: Pubkey,
+    pub lp_decimals: u8,
 
-    pub fee_numerator: u64,
+    pub fee_numerator_a_to_b: u64,
+    pub fee_numerator_b_to_a: u64,
     pub fee_denominator: u64,
+    pub referral_fee_bps: u64,
+    pub max_price_impact_bps: u64,
+    pub fee_tiers: Vec<FeeTier>,
 
     pub timestamp: i64,
 
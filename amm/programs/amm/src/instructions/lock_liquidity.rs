@@ -0,0 +1,123 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::state::{LiquidityLock, PoolState};
+use crate::error::ErrorCode;
+use crate::instructions::shared::transfer_tokens;
+
+pub fn lock_liquidity_handler(
+    ctx: Context<LockLiquidity>,
+    lock_id: u64,
+    lp_amount: u64,
+    unlock_time: i64,
+    linear_vesting: bool,
+) -> Result<()> {
+    require!(lp_amount > 0, ErrorCode::InvalidAmount);
+    
+    let clock = Clock::get()?;
+    require!(unlock_time > clock.unix_timestamp, ErrorCode::InvalidUnlockTime);
+    
+    require!(
+        ctx.accounts.user_lp_token.amount >= lp_amount,
+        ErrorCode::InsufficientBalance
+    );
+    
+    transfer_tokens(
+        &ctx.accounts.user_lp_token,
+        &ctx.accounts.lock_escrow,
+        &ctx.accounts.pool_mint,
+        &ctx.accounts.owner,
+        &ctx.accounts.token_program,
+        lp_amount,
+    )?;
+    
+    let lock = &mut ctx.accounts.lock;
+    lock.pool = ctx.accounts.pool_state.key();
+    lock.owner = ctx.accounts.owner.key();
+    lock.lock_id = lock_id;
+    lock.amount = lp_amount;
+    lock.withdrawn = 0;
+    lock.locked_at = clock.unix_timestamp;
+    lock.unlock_time = unlock_time;
+    lock.linear_vesting = linear_vesting;
+    lock.bump = ctx.bumps.lock;
+    lock.escrow_bump = ctx.bumps.lock_escrow;
+    
+    emit!(LiquidityLocked {
+        pool: lock.pool,
+        owner: lock.owner,
+        lock: lock.key(),
+        lock_id,
+        lp_amount,
+        unlock_time,
+        linear_vesting,
+        timestamp: clock.unix_timestamp,
+    });
+    
+    msg!(
+        "Liquidity locked: {} LP tokens until {} (linear vesting: {})",
+        lp_amount,
+        unlock_time,
+        linear_vesting
+    );
+    
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(lock_id: u64)]
+pub struct LockLiquidity<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    
+    #[account(
+        seeds = [b"pool_state", pool_state.token_mint_a.as_ref(), pool_state.token_mint_b.as_ref()],
+        bump = pool_state.bump,
+    )]
+    pub pool_state: Box<Account<'info, PoolState>>,  // ✅ BOX
+    
+    #[account(address = pool_state.pool_mint)]
+    pub pool_mint: Box<InterfaceAccount<'info, Mint>>,  // ✅ BOX
+    
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + LiquidityLock::INIT_SPACE,
+        seeds = [b"lock", pool_state.key().as_ref(), owner.key().as_ref(), &lock_id.to_le_bytes()],
+        bump,
+    )]
+    pub lock: Box<Account<'info, LiquidityLock>>,  // ✅ BOX
+    
+    #[account(
+        init,
+        payer = owner,
+        seeds = [b"lock_escrow", lock.key().as_ref()],
+        bump,
+        token::mint = pool_mint,
+        token::authority = lock,
+        token::token_program = token_program,
+    )]
+    pub lock_escrow: Box<InterfaceAccount<'info, TokenAccount>>,  // ✅ BOX
+    
+    #[account(
+        mut,
+        token::mint = pool_mint,
+        token::authority = owner,
+    )]
+    pub user_lp_token: Box<InterfaceAccount<'info, TokenAccount>>,  // ✅ BOX
+    
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[event]
+pub struct LiquidityLocked {
+    pub pool: Pubkey,
+    pub owner: Pubkey,
+    pub lock: Pubkey,
+    pub lock_id: u64,
+    pub lp_amount: u64,
+    pub unlock_time: i64,
+    pub linear_vesting: bool,
+    pub timestamp: i64,
+}
@@ -10,5 +10,107 @@ pub use remove_liquidity::*;
 pub mod swap;
 pub use swap::*;
 
+pub mod swap_with_referral;
+pub use swap_with_referral::*;
+
+pub mod swap_rfq;
+pub use swap_rfq::*;
+
+pub mod close_rfq_quote_fill;
+pub use close_rfq_quote_fill::*;
+
+pub mod batch_swap;
+pub use batch_swap::*;
+
+pub mod commit_swap;
+pub use commit_swap::*;
+
+pub mod reveal_swap;
+pub use reveal_swap::*;
+
+pub mod refund_swap_commit;
+pub use refund_swap_commit::*;
+
+pub mod submit_batch_order;
+pub use submit_batch_order::*;
+
+pub mod settle_batch;
+pub use settle_batch::*;
+
+pub mod claim_batch_order;
+pub use claim_batch_order::*;
+
+pub mod place_limit_order;
+pub use place_limit_order::*;
+
+pub mod execute_limit_order;
+pub use execute_limit_order::*;
+
+pub mod cancel_limit_order;
+pub use cancel_limit_order::*;
+
+pub mod open_dca_order;
+pub use open_dca_order::*;
+
+pub mod execute_dca_order;
+pub use execute_dca_order::*;
+
+pub mod top_up_dca_order;
+pub use top_up_dca_order::*;
+
+pub mod cancel_dca_order;
+pub use cancel_dca_order::*;
+
+pub mod place_trigger_order;
+pub use place_trigger_order::*;
+
+pub mod execute_trigger_order;
+pub use execute_trigger_order::*;
+
+pub mod cancel_trigger_order;
+pub use cancel_trigger_order::*;
+
+pub mod lock_liquidity;
+pub use lock_liquidity::*;
+
+pub mod unlock_liquidity;
+pub use unlock_liquidity::*;
+
+pub mod create_farm;
+pub use create_farm::*;
+
+pub mod add_farm_reward;
+pub use add_farm_reward::*;
+
+pub mod stake_lp;
+pub use stake_lp::*;
+
+pub mod unstake_lp;
+pub use unstake_lp::*;
+
+pub mod claim_rewards;
+pub use claim_rewards::*;
+
+pub mod trader_stats;
+pub use trader_stats::*;
+
+pub mod position;
+pub use position::*;
+
+pub mod quote;
+pub use quote::*;
+
+pub mod initialize_config;
+pub use initialize_config::*;
+
+pub mod fee_discount;
+pub use fee_discount::*;
+
+pub mod update_config;
+pub use update_config::*;
+
+pub mod update_pool_config;
+pub use update_pool_config::*;
+
 pub mod shared;
 pub use shared::*;
\ No newline at end of file
@@ -0,0 +1,151 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::state::{DcaOrder, PoolState};
+use crate::error::ErrorCode;
+use crate::instructions::shared::transfer_tokens;
+
+/// Escrows `total_amount` to be swapped `amount_per_interval` at a time, at most
+/// once every `interval` seconds. The first fill is due immediately.
+pub fn open_dca_order_handler(
+    ctx: Context<OpenDcaOrder>,
+    total_amount: u64,
+    amount_per_interval: u64,
+    interval: i64,
+    min_amount_out: u64,
+    is_a_to_b: bool,
+) -> Result<()> {
+    require!(total_amount > 0, ErrorCode::InvalidAmount);
+    require!(
+        amount_per_interval > 0 && amount_per_interval <= total_amount,
+        ErrorCode::InvalidDcaSchedule
+    );
+    require!(interval > 0, ErrorCode::InvalidDcaSchedule);
+    require!(min_amount_out > 0, ErrorCode::InvalidAmount);
+    
+    let pool_state = &ctx.accounts.pool_state;
+    let (mint_in, mint_out) = if is_a_to_b {
+        (pool_state.token_mint_a, pool_state.token_mint_b)
+    } else {
+        (pool_state.token_mint_b, pool_state.token_mint_a)
+    };
+    require_keys_eq!(ctx.accounts.input_mint.key(), mint_in, ErrorCode::InvalidTokenMint);
+    require_keys_eq!(ctx.accounts.user_token_out.mint, mint_out, ErrorCode::InvalidTokenMint);
+    
+    require!(
+        ctx.accounts.user_token_in.amount >= total_amount,
+        ErrorCode::InsufficientBalance
+    );
+    
+    transfer_tokens(
+        &ctx.accounts.user_token_in,
+        &ctx.accounts.dca_escrow,
+        &ctx.accounts.input_mint,
+        &ctx.accounts.user,
+        &ctx.accounts.token_program,
+        total_amount,
+    )?;
+    
+    let clock = Clock::get()?;
+    let dca_order = &mut ctx.accounts.dca_order;
+    dca_order.pool = pool_state.key();
+    dca_order.owner = ctx.accounts.user.key();
+    dca_order.token_out_account = ctx.accounts.user_token_out.key();
+    dca_order.is_a_to_b = is_a_to_b;
+    dca_order.remaining_amount = total_amount;
+    dca_order.amount_per_interval = amount_per_interval;
+    dca_order.interval = interval;
+    dca_order.min_amount_out = min_amount_out;
+    dca_order.next_fill_at = clock.unix_timestamp;
+    dca_order.fill_count = 0;
+    dca_order.total_amount_in = 0;
+    dca_order.total_amount_out = 0;
+    dca_order.bump = ctx.bumps.dca_order;
+    dca_order.escrow_bump = ctx.bumps.dca_escrow;
+    
+    emit!(DcaOrderOpened {
+        pool: dca_order.pool,
+        owner: dca_order.owner,
+        dca_order: dca_order.key(),
+        total_amount,
+        amount_per_interval,
+        interval,
+        min_amount_out,
+        is_a_to_b,
+        timestamp: clock.unix_timestamp,
+    });
+    
+    msg!(
+        "DCA order opened: {} in slices of {} every {}s",
+        total_amount, amount_per_interval, interval
+    );
+    
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(
+    total_amount: u64,
+    amount_per_interval: u64,
+    interval: i64,
+    min_amount_out: u64,
+    is_a_to_b: bool,
+)]
+pub struct OpenDcaOrder<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+    
+    #[account(
+        seeds = [b"pool_state", pool_state.token_mint_a.as_ref(), pool_state.token_mint_b.as_ref()],
+        bump = pool_state.bump,
+    )]
+    pub pool_state: Box<Account<'info, PoolState>>,  // ✅ BOX
+    
+    pub input_mint: Box<InterfaceAccount<'info, Mint>>,  // ✅ BOX
+    
+    #[account(
+        init,
+        payer = user,
+        space = 8 + DcaOrder::INIT_SPACE,
+        seeds = [b"dca_order", pool_state.key().as_ref(), user.key().as_ref(), &[is_a_to_b as u8]],
+        bump,
+    )]
+    pub dca_order: Box<Account<'info, DcaOrder>>,  // ✅ BOX
+    
+    #[account(
+        init,
+        payer = user,
+        seeds = [b"dca_escrow", dca_order.key().as_ref()],
+        bump,
+        token::mint = input_mint,
+        token::authority = dca_order,
+        token::token_program = token_program,
+    )]
+    pub dca_escrow: Box<InterfaceAccount<'info, TokenAccount>>,  // ✅ BOX
+    
+    #[account(
+        mut,
+        token::mint = input_mint,
+        token::authority = user,
+    )]
+    pub user_token_in: Box<InterfaceAccount<'info, TokenAccount>>,  // ✅ BOX
+    
+    #[account(token::authority = user)]
+    pub user_token_out: Box<InterfaceAccount<'info, TokenAccount>>,  // ✅ BOX
+    
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[event]
+pub struct DcaOrderOpened {
+    pub pool: Pubkey,
+    pub owner: Pubkey,
+    pub dca_order: Pubkey,
+    pub total_amount: u64,
+    pub amount_per_interval: u64,
+    pub interval: i64,
+    pub min_amount_out: u64,
+    pub is_a_to_b: bool,
+    pub timestamp: i64,
+}
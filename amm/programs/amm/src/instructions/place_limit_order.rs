@@ -0,0 +1,143 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::state::{Order, PoolState};
+use crate::error::ErrorCode;
+use crate::instructions::shared::transfer_tokens;
+
+/// Share of a limit order's input paid to the keeper that fills it.
+pub const LIMIT_ORDER_KEEPER_TIP_BPS: u64 = 10;
+
+/// Escrows `amount_in` to be swapped once the pool pays at least `target_price`
+/// (output per unit of input, scaled by `PRICE_SCALE`) before `expiry`.
+pub fn place_limit_order_handler(
+    ctx: Context<PlaceLimitOrder>,
+    order_id: u64,
+    amount_in: u64,
+    target_price: u64,
+    is_a_to_b: bool,
+    expiry: i64,
+) -> Result<()> {
+    require!(amount_in > 0, ErrorCode::InvalidAmount);
+    require!(target_price > 0, ErrorCode::InvalidAmount);
+    
+    let clock = Clock::get()?;
+    require!(expiry > clock.unix_timestamp, ErrorCode::InvalidOrderExpiry);
+    
+    let pool_state = &ctx.accounts.pool_state;
+    let (mint_in, mint_out) = if is_a_to_b {
+        (pool_state.token_mint_a, pool_state.token_mint_b)
+    } else {
+        (pool_state.token_mint_b, pool_state.token_mint_a)
+    };
+    require_keys_eq!(ctx.accounts.input_mint.key(), mint_in, ErrorCode::InvalidTokenMint);
+    require_keys_eq!(ctx.accounts.user_token_out.mint, mint_out, ErrorCode::InvalidTokenMint);
+    
+    require!(
+        ctx.accounts.user_token_in.amount >= amount_in,
+        ErrorCode::InsufficientBalance
+    );
+    
+    transfer_tokens(
+        &ctx.accounts.user_token_in,
+        &ctx.accounts.order_escrow,
+        &ctx.accounts.input_mint,
+        &ctx.accounts.user,
+        &ctx.accounts.token_program,
+        amount_in,
+    )?;
+    
+    let order = &mut ctx.accounts.order;
+    order.pool = pool_state.key();
+    order.owner = ctx.accounts.user.key();
+    order.order_id = order_id;
+    order.token_out_account = ctx.accounts.user_token_out.key();
+    order.amount_in = amount_in;
+    order.target_price = target_price;
+    order.is_a_to_b = is_a_to_b;
+    order.expiry = expiry;
+    order.bump = ctx.bumps.order;
+    order.escrow_bump = ctx.bumps.order_escrow;
+    
+    emit!(LimitOrderPlaced {
+        pool: order.pool,
+        owner: order.owner,
+        order: order.key(),
+        order_id,
+        amount_in,
+        target_price,
+        is_a_to_b,
+        expiry,
+        timestamp: clock.unix_timestamp,
+    });
+    
+    msg!("Limit order placed: {} at {}", amount_in, target_price);
+    
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(order_id: u64)]
+pub struct PlaceLimitOrder<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+    
+    #[account(
+        seeds = [b"pool_state", pool_state.token_mint_a.as_ref(), pool_state.token_mint_b.as_ref()],
+        bump = pool_state.bump,
+    )]
+    pub pool_state: Box<Account<'info, PoolState>>,  // ✅ BOX
+    
+    pub input_mint: Box<InterfaceAccount<'info, Mint>>,  // ✅ BOX
+    
+    #[account(
+        init,
+        payer = user,
+        space = 8 + Order::INIT_SPACE,
+        seeds = [
+            b"order",
+            pool_state.key().as_ref(),
+            user.key().as_ref(),
+            &order_id.to_le_bytes(),
+        ],
+        bump,
+    )]
+    pub order: Box<Account<'info, Order>>,  // ✅ BOX
+    
+    #[account(
+        init,
+        payer = user,
+        seeds = [b"order_escrow", order.key().as_ref()],
+        bump,
+        token::mint = input_mint,
+        token::authority = order,
+        token::token_program = token_program,
+    )]
+    pub order_escrow: Box<InterfaceAccount<'info, TokenAccount>>,  // ✅ BOX
+    
+    #[account(
+        mut,
+        token::mint = input_mint,
+        token::authority = user,
+    )]
+    pub user_token_in: Box<InterfaceAccount<'info, TokenAccount>>,  // ✅ BOX
+    
+    #[account(token::authority = user)]
+    pub user_token_out: Box<InterfaceAccount<'info, TokenAccount>>,  // ✅ BOX
+    
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[event]
+pub struct LimitOrderPlaced {
+    pub pool: Pubkey,
+    pub owner: Pubkey,
+    pub order: Pubkey,
+    pub order_id: u64,
+    pub amount_in: u64,
+    pub target_price: u64,
+    pub is_a_to_b: bool,
+    pub expiry: i64,
+    pub timestamp: i64,
+}
@@ -0,0 +1,149 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::state::{PoolState, TriggerKind, TriggerOrder, MAX_PRICE_OBSERVATIONS};
+use crate::error::ErrorCode;
+use crate::instructions::shared::{transfer_tokens, PRICE_OBSERVATION_PERIOD};
+
+/// Escrows `amount_in` to be swapped for at least `min_amount_out` once the pool
+/// price crosses `trigger_price`. With a `twap_window` the price is the TWAP over
+/// that many seconds rather than the spot price.
+pub fn place_trigger_order_handler(
+    ctx: Context<PlaceTriggerOrder>,
+    amount_in: u64,
+    min_amount_out: u64,
+    is_a_to_b: bool,
+    kind: TriggerKind,
+    trigger_price: u64,
+    twap_window: i64,
+) -> Result<()> {
+    require!(amount_in > 0, ErrorCode::InvalidAmount);
+    require!(min_amount_out > 0, ErrorCode::InvalidAmount);
+    require!(trigger_price > 0, ErrorCode::InvalidAmount);
+    require!(
+        (0..=PRICE_OBSERVATION_PERIOD * (MAX_PRICE_OBSERVATIONS as i64 - 1)).contains(&twap_window),
+        ErrorCode::InvalidTwapWindow
+    );
+    
+    let pool_state = &ctx.accounts.pool_state;
+    let (mint_in, mint_out) = if is_a_to_b {
+        (pool_state.token_mint_a, pool_state.token_mint_b)
+    } else {
+        (pool_state.token_mint_b, pool_state.token_mint_a)
+    };
+    require_keys_eq!(ctx.accounts.input_mint.key(), mint_in, ErrorCode::InvalidTokenMint);
+    require_keys_eq!(ctx.accounts.user_token_out.mint, mint_out, ErrorCode::InvalidTokenMint);
+    
+    require!(
+        ctx.accounts.user_token_in.amount >= amount_in,
+        ErrorCode::InsufficientBalance
+    );
+    
+    transfer_tokens(
+        &ctx.accounts.user_token_in,
+        &ctx.accounts.trigger_escrow,
+        &ctx.accounts.input_mint,
+        &ctx.accounts.user,
+        &ctx.accounts.token_program,
+        amount_in,
+    )?;
+    
+    let trigger_order = &mut ctx.accounts.trigger_order;
+    trigger_order.pool = pool_state.key();
+    trigger_order.owner = ctx.accounts.user.key();
+    trigger_order.token_out_account = ctx.accounts.user_token_out.key();
+    trigger_order.amount_in = amount_in;
+    trigger_order.min_amount_out = min_amount_out;
+    trigger_order.is_a_to_b = is_a_to_b;
+    trigger_order.kind = kind;
+    trigger_order.trigger_price = trigger_price;
+    trigger_order.twap_window = twap_window;
+    trigger_order.bump = ctx.bumps.trigger_order;
+    trigger_order.escrow_bump = ctx.bumps.trigger_escrow;
+    
+    let clock = Clock::get()?;
+    emit!(TriggerOrderPlaced {
+        pool: trigger_order.pool,
+        owner: trigger_order.owner,
+        trigger_order: trigger_order.key(),
+        amount_in,
+        min_amount_out,
+        is_a_to_b,
+        kind,
+        trigger_price,
+        twap_window,
+        timestamp: clock.unix_timestamp,
+    });
+    
+    msg!("Trigger order placed: {} at {}", amount_in, trigger_price);
+    
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(amount_in: u64, min_amount_out: u64, is_a_to_b: bool, kind: TriggerKind, trigger_price: u64)]
+pub struct PlaceTriggerOrder<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+    
+    #[account(
+        seeds = [b"pool_state", pool_state.token_mint_a.as_ref(), pool_state.token_mint_b.as_ref()],
+        bump = pool_state.bump,
+    )]
+    pub pool_state: Box<Account<'info, PoolState>>,  // ✅ BOX
+    
+    pub input_mint: Box<InterfaceAccount<'info, Mint>>,  // ✅ BOX
+    
+    #[account(
+        init,
+        payer = user,
+        space = 8 + TriggerOrder::INIT_SPACE,
+        seeds = [
+            b"trigger_order",
+            pool_state.key().as_ref(),
+            user.key().as_ref(),
+            &trigger_price.to_le_bytes(),
+            &[is_a_to_b as u8],
+        ],
+        bump,
+    )]
+    pub trigger_order: Box<Account<'info, TriggerOrder>>,  // ✅ BOX
+    
+    #[account(
+        init,
+        payer = user,
+        seeds = [b"trigger_escrow", trigger_order.key().as_ref()],
+        bump,
+        token::mint = input_mint,
+        token::authority = trigger_order,
+        token::token_program = token_program,
+    )]
+    pub trigger_escrow: Box<InterfaceAccount<'info, TokenAccount>>,  // ✅ BOX
+    
+    #[account(
+        mut,
+        token::mint = input_mint,
+        token::authority = user,
+    )]
+    pub user_token_in: Box<InterfaceAccount<'info, TokenAccount>>,  // ✅ BOX
+    
+    #[account(token::authority = user)]
+    pub user_token_out: Box<InterfaceAccount<'info, TokenAccount>>,  // ✅ BOX
+    
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[event]
+pub struct TriggerOrderPlaced {
+    pub pool: Pubkey,
+    pub owner: Pubkey,
+    pub trigger_order: Pubkey,
+    pub amount_in: u64,
+    pub min_amount_out: u64,
+    pub is_a_to_b: bool,
+    pub kind: TriggerKind,
+    pub trigger_price: u64,
+    pub twap_window: i64,
+    pub timestamp: i64,
+}
@@ -0,0 +1,501 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::state::{PoolState, Position};
+use crate::error::ErrorCode;
+use crate::instructions::shared::{
+    transfer_tokens, transfer_tokens_signed, calculate_optimal_amounts, calculate_lp_tokens,
+    calculate_withdrawal_amounts, calculate_fees_earned, update_price_oracle,
+};
+
+pub fn open_position_handler(
+    ctx: Context<OpenPosition>,
+    amount_a_desired: u64,
+    amount_b_desired: u64,
+    amount_a_min: u64,
+    amount_b_min: u64,
+    min_lp_shares: u64,
+) -> Result<()> {
+    let liquidity = &mut ctx.accounts.liquidity;
+    let position = &mut ctx.accounts.position;
+    let clock = Clock::get()?;
+
+    position.pool = liquidity.pool_state.key();
+    position.owner = liquidity.owner.key();
+    position.lp_shares = 0;
+    position.fee_growth_checkpoint_a = liquidity.pool_state.fee_growth_global_a;
+    position.fee_growth_checkpoint_b = liquidity.pool_state.fee_growth_global_b;
+    position.opened_at = clock.unix_timestamp;
+    position.bump = ctx.bumps.position;
+
+    emit!(PositionOpened {
+        pool: position.pool,
+        owner: position.owner,
+        position: position.key(),
+        timestamp: clock.unix_timestamp,
+    });
+
+    liquidity.deposit(
+        position,
+        amount_a_desired,
+        amount_b_desired,
+        amount_a_min,
+        amount_b_min,
+        min_lp_shares,
+    )
+}
+
+pub fn increase_position_handler(
+    ctx: Context<ModifyPosition>,
+    amount_a_desired: u64,
+    amount_b_desired: u64,
+    amount_a_min: u64,
+    amount_b_min: u64,
+    min_lp_shares: u64,
+) -> Result<()> {
+    ctx.accounts.liquidity.deposit(
+        &mut ctx.accounts.position,
+        amount_a_desired,
+        amount_b_desired,
+        amount_a_min,
+        amount_b_min,
+        min_lp_shares,
+    )
+}
+
+pub fn decrease_position_handler(
+    ctx: Context<ModifyPosition>,
+    lp_shares: u64,
+    min_amount_a: u64,
+    min_amount_b: u64,
+) -> Result<()> {
+    ctx.accounts.liquidity.withdraw(
+        &mut ctx.accounts.position,
+        lp_shares,
+        min_amount_a,
+        min_amount_b,
+    )
+}
+
+pub fn close_position_handler(
+    ctx: Context<ModifyPosition>,
+    min_amount_a: u64,
+    min_amount_b: u64,
+) -> Result<()> {
+    let liquidity = &mut ctx.accounts.liquidity;
+    let position = &mut ctx.accounts.position;
+
+    if position.lp_shares > 0 {
+        let lp_shares = position.lp_shares;
+        liquidity.withdraw(position, lp_shares, min_amount_a, min_amount_b)?;
+    } else {
+        update_position_fees(position, &liquidity.pool_state);
+    }
+
+    let clock = Clock::get()?;
+    emit!(PositionClosed {
+        pool: position.pool,
+        owner: position.owner,
+        position: position.key(),
+        deposited_a: position.deposited_a,
+        deposited_b: position.deposited_b,
+        withdrawn_a: position.withdrawn_a,
+        withdrawn_b: position.withdrawn_b,
+        fees_earned_a: position.fees_earned_a,
+        fees_earned_b: position.fees_earned_b,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!(
+        "Position closed: fees earned {}A + {}B",
+        position.fees_earned_a,
+        position.fees_earned_b
+    );
+
+    position.close(liquidity.owner.to_account_info())
+}
+
+/// Folds the fees earned since the last checkpoint into the position.
+fn update_position_fees(position: &mut Position, pool_state: &PoolState) {
+    position.fees_earned_a = position.fees_earned_a.saturating_add(calculate_fees_earned(
+        position.lp_shares,
+        pool_state.fee_growth_global_a,
+        position.fee_growth_checkpoint_a,
+    ));
+    position.fees_earned_b = position.fees_earned_b.saturating_add(calculate_fees_earned(
+        position.lp_shares,
+        pool_state.fee_growth_global_b,
+        position.fee_growth_checkpoint_b,
+    ));
+
+    position.fee_growth_checkpoint_a = pool_state.fee_growth_global_a;
+    position.fee_growth_checkpoint_b = pool_state.fee_growth_global_b;
+}
+
+impl<'info> PositionLiquidity<'info> {
+    fn deposit(
+        &mut self,
+        position: &mut Position,
+        amount_a_desired: u64,
+        amount_b_desired: u64,
+        amount_a_min: u64,
+        amount_b_min: u64,
+        min_lp_shares: u64,
+    ) -> Result<()> {
+        require!(amount_a_desired > 0, ErrorCode::InvalidAmount);
+        require!(amount_b_desired > 0, ErrorCode::InvalidAmount);
+
+        update_position_fees(position, &self.pool_state);
+
+        let pool_state = &mut self.pool_state;
+
+        let (amount_a, amount_b) = calculate_optimal_amounts(
+            amount_a_desired,
+            amount_b_desired,
+            pool_state.reserve_a,
+            pool_state.reserve_b,
+        )?;
+
+        require!(amount_a >= amount_a_min, ErrorCode::InsufficientAmountA);
+        require!(amount_b >= amount_b_min, ErrorCode::InsufficientAmountB);
+
+        require!(
+            self.user_token_a.amount >= amount_a,
+            ErrorCode::InsufficientBalance
+        );
+        require!(
+            self.user_token_b.amount >= amount_b,
+            ErrorCode::InsufficientBalance
+        );
+
+        let lp_shares = calculate_lp_tokens(
+            amount_a,
+            amount_b,
+            pool_state.reserve_a,
+            pool_state.reserve_b,
+            pool_state.total_supply,
+            pool_state.minimum_liquidity,
+        )?;
+
+        require!(lp_shares >= min_lp_shares, ErrorCode::InsufficientLPTokens);
+        require!(lp_shares > 0, ErrorCode::InsufficientLPTokens);
+
+        transfer_tokens(
+            &self.user_token_a,
+            &self.vault_a,
+            &self.token_mint_a,
+            &self.owner,
+            &self.token_program,
+            amount_a,
+        )?;
+
+        transfer_tokens(
+            &self.user_token_b,
+            &self.vault_b,
+            &self.token_mint_b,
+            &self.owner,
+            &self.token_program,
+            amount_b,
+        )?;
+
+        let clock = Clock::get()?;
+        update_price_oracle(pool_state, clock.unix_timestamp)?;
+
+        pool_state.reserve_a = pool_state.reserve_a
+            .checked_add(amount_a)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        pool_state.reserve_b = pool_state.reserve_b
+            .checked_add(amount_b)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        pool_state.total_supply = pool_state.total_supply
+            .checked_add(lp_shares)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        position.lp_shares = position.lp_shares
+            .checked_add(lp_shares)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        position.deposited_a = position.deposited_a
+            .checked_add(amount_a)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        position.deposited_b = position.deposited_b
+            .checked_add(amount_b)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        position.entry_reserve_a = pool_state.reserve_a;
+        position.entry_reserve_b = pool_state.reserve_b;
+
+        emit!(PositionIncreased {
+            pool: pool_state.key(),
+            owner: self.owner.key(),
+            amount_a,
+            amount_b,
+            lp_shares_added: lp_shares,
+            lp_shares: position.lp_shares,
+            total_supply: pool_state.total_supply,
+            timestamp: clock.unix_timestamp,
+        });
+
+        msg!(
+            "Position increased: {}A + {}B = {} LP shares",
+            amount_a,
+            amount_b,
+            lp_shares
+        );
+
+        Ok(())
+    }
+
+    fn withdraw(
+        &mut self,
+        position: &mut Position,
+        lp_shares: u64,
+        min_amount_a: u64,
+        min_amount_b: u64,
+    ) -> Result<()> {
+        require!(lp_shares > 0, ErrorCode::InvalidAmount);
+        require!(
+            position.lp_shares >= lp_shares,
+            ErrorCode::InsufficientLPTokens
+        );
+
+        update_position_fees(position, &self.pool_state);
+
+        let pool_state = &mut self.pool_state;
+
+        let (amount_a, amount_b) = calculate_withdrawal_amounts(
+            lp_shares,
+            pool_state.reserve_a,
+            pool_state.reserve_b,
+            pool_state.total_supply,
+        )?;
+
+        require!(amount_a >= min_amount_a, ErrorCode::InsufficientAmountA);
+        require!(amount_b >= min_amount_b, ErrorCode::InsufficientAmountB);
+
+        require!(
+            self.vault_a.amount >= amount_a,
+            ErrorCode::InsufficientPoolLiquidity
+        );
+        require!(
+            self.vault_b.amount >= amount_b,
+            ErrorCode::InsufficientPoolLiquidity
+        );
+
+        let pool_key = pool_state.key();
+        let authority_bump = pool_state.authority_bump;
+        let authority_seeds = &[
+            b"authority",
+            pool_key.as_ref(),
+            &[authority_bump],
+        ];
+        let signer_seeds = &[&authority_seeds[..]];
+
+        transfer_tokens_signed(
+            &self.vault_a,
+            &self.user_token_a,
+            &self.token_mint_a,
+            &self.pool_authority,
+            &self.token_program,
+            amount_a,
+            signer_seeds,
+        )?;
+
+        transfer_tokens_signed(
+            &self.vault_b,
+            &self.user_token_b,
+            &self.token_mint_b,
+            &self.pool_authority,
+            &self.token_program,
+            amount_b,
+            signer_seeds,
+        )?;
+
+        let clock = Clock::get()?;
+        update_price_oracle(pool_state, clock.unix_timestamp)?;
+
+        pool_state.reserve_a = pool_state.reserve_a
+            .checked_sub(amount_a)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        pool_state.reserve_b = pool_state.reserve_b
+            .checked_sub(amount_b)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        pool_state.total_supply = pool_state.total_supply
+            .checked_sub(lp_shares)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        position.lp_shares = position.lp_shares
+            .checked_sub(lp_shares)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        position.withdrawn_a = position.withdrawn_a
+            .checked_add(amount_a)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        position.withdrawn_b = position.withdrawn_b
+            .checked_add(amount_b)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        emit!(PositionDecreased {
+            pool: pool_state.key(),
+            owner: self.owner.key(),
+            amount_a,
+            amount_b,
+            lp_shares_removed: lp_shares,
+            lp_shares: position.lp_shares,
+            fees_earned_a: position.fees_earned_a,
+            fees_earned_b: position.fees_earned_b,
+            total_supply: pool_state.total_supply,
+            timestamp: clock.unix_timestamp,
+        });
+
+        msg!(
+            "Position decreased: {} LP shares -> {}A + {}B",
+            lp_shares,
+            amount_a,
+            amount_b
+        );
+
+        Ok(())
+    }
+}
+
+/// Pool accounts shared by every position instruction.
+#[derive(Accounts)]
+pub struct PositionLiquidity<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"pool_state", pool_state.token_mint_a.as_ref(), pool_state.token_mint_b.as_ref()],
+        bump = pool_state.bump,
+    )]
+    pub pool_state: Box<Account<'info, PoolState>>,  // ✅ BOX
+
+    /// CHECK: PDA authority derived from pool_state, used as signer for vault operations
+    #[account(
+        seeds = [b"authority", pool_state.key().as_ref()],
+        bump = pool_state.authority_bump,
+    )]
+    pub pool_authority: AccountInfo<'info>,
+
+    #[account(mint::token_program = token_program)]
+    pub token_mint_a: Box<InterfaceAccount<'info, Mint>>,  // ✅ BOX
+
+    #[account(mint::token_program = token_program)]
+    pub token_mint_b: Box<InterfaceAccount<'info, Mint>>,  // ✅ BOX
+
+    #[account(
+        mut,
+        address = pool_state.vault_a,
+        token::mint = token_mint_a,
+        token::authority = pool_authority,
+    )]
+    pub vault_a: Box<InterfaceAccount<'info, TokenAccount>>,  // ✅ BOX
+
+    #[account(
+        mut,
+        address = pool_state.vault_b,
+        token::mint = token_mint_b,
+        token::authority = pool_authority,
+    )]
+    pub vault_b: Box<InterfaceAccount<'info, TokenAccount>>,  // ✅ BOX
+
+    #[account(
+        mut,
+        token::mint = token_mint_a,
+        token::authority = owner,
+    )]
+    pub user_token_a: Box<InterfaceAccount<'info, TokenAccount>>,  // ✅ BOX
+
+    #[account(
+        mut,
+        token::mint = token_mint_b,
+        token::authority = owner,
+    )]
+    pub user_token_b: Box<InterfaceAccount<'info, TokenAccount>>,  // ✅ BOX
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct OpenPosition<'info> {
+    pub liquidity: PositionLiquidity<'info>,
+
+    #[account(
+        init,
+        payer = liquidity.owner,
+        space = 8 + Position::INIT_SPACE,
+        seeds = [b"position", liquidity.pool_state.key().as_ref(), liquidity.owner.key().as_ref()],
+        bump,
+    )]
+    pub position: Box<Account<'info, Position>>,  // ✅ BOX
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ModifyPosition<'info> {
+    pub liquidity: PositionLiquidity<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"position", liquidity.pool_state.key().as_ref(), liquidity.owner.key().as_ref()],
+        bump = position.bump,
+    )]
+    pub position: Box<Account<'info, Position>>,  // ✅ BOX
+}
+
+#[event]
+pub struct PositionOpened {
+    pub pool: Pubkey,
+    pub owner: Pubkey,
+    pub position: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PositionIncreased {
+    pub pool: Pubkey,
+    pub owner: Pubkey,
+    pub amount_a: u64,
+    pub amount_b: u64,
+    pub lp_shares_added: u64,
+    pub lp_shares: u64,
+    pub total_supply: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PositionDecreased {
+    pub pool: Pubkey,
+    pub owner: Pubkey,
+    pub amount_a: u64,
+    pub amount_b: u64,
+    pub lp_shares_removed: u64,
+    pub lp_shares: u64,
+    pub fees_earned_a: u64,
+    pub fees_earned_b: u64,
+    pub total_supply: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PositionClosed {
+    pub pool: Pubkey,
+    pub owner: Pubkey,
+    pub position: Pubkey,
+    pub deposited_a: u64,
+    pub deposited_b: u64,
+    pub withdrawn_a: u64,
+    pub withdrawn_b: u64,
+    pub fees_earned_a: u64,
+    pub fees_earned_b: u64,
+    pub timestamp: i64,
+}
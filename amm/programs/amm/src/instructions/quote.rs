@@ -0,0 +1,214 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::sysvar;
+use anchor_spl::token_interface::TokenAccount;
+
+use crate::state::{AmmConfig, FeeDiscount, PoolState, TraderStats};
+use crate::error::ErrorCode;
+use crate::instructions::swap::{apply_swap, fee_discount_bps, AppliedSwap};
+use crate::instructions::shared::{
+    calculate_optimal_amounts, calculate_lp_tokens, calculate_withdrawal_amounts,
+    SECONDS_PER_DAY,
+};
+
+/// Quotes a swap without a referrer for the trader owning the given discount
+/// accounts. Runs the same checks and pool updates as `swap` on a copy of the pool,
+/// so it fails under the same conditions, except the slippage and balance checks.
+pub fn quote_swap_handler(
+    ctx: Context<QuoteSwap>,
+    amount_in: u64,
+    is_a_to_b: bool,
+) -> Result<SwapQuote> {
+    let clock = Clock::get()?;
+    let day = clock.unix_timestamp.div_euclid(SECONDS_PER_DAY);
+    let fee_discount_bps = ctx.accounts.fee_discount_bps(day)?;
+    
+    let mut pool_state = (**ctx.accounts.pool_state).clone();
+    let AppliedSwap {
+        fee_amount,
+        amount_out,
+        price_impact_bps,
+        fee_numerator,
+        ..
+    } = apply_swap(
+        &mut pool_state,
+        &ctx.accounts.pool_state.key(),
+        amount_in,
+        is_a_to_b,
+        fee_discount_bps,
+        false,
+        ctx.accounts.instructions_sysvar.as_deref(),
+        &clock,
+    )?;
+    
+    Ok(SwapQuote {
+        amount_in,
+        amount_out,
+        fee_amount,
+        fee_numerator,
+        price_impact_bps,
+        reserve_a_after: pool_state.reserve_a,
+        reserve_b_after: pool_state.reserve_b,
+    })
+}
+
+pub fn quote_add_liquidity_handler(
+    ctx: Context<Quote>,
+    amount_a_desired: u64,
+    amount_b_desired: u64,
+) -> Result<AddLiquidityQuote> {
+    require!(amount_a_desired > 0, ErrorCode::InvalidAmount);
+    require!(amount_b_desired > 0, ErrorCode::InvalidAmount);
+    
+    let pool_state = &ctx.accounts.pool_state;
+    
+    let (amount_a, amount_b) = calculate_optimal_amounts(
+        amount_a_desired,
+        amount_b_desired,
+        pool_state.reserve_a,
+        pool_state.reserve_b,
+    )?;
+    
+    let lp_tokens = calculate_lp_tokens(
+        amount_a,
+        amount_b,
+        pool_state.reserve_a,
+        pool_state.reserve_b,
+        pool_state.total_supply,
+        pool_state.minimum_liquidity,
+    )?;
+    
+    require!(lp_tokens > 0, ErrorCode::InsufficientLPTokens);
+    
+    Ok(AddLiquidityQuote {
+        amount_a,
+        amount_b,
+        lp_tokens,
+        reserve_a_after: pool_state.reserve_a.checked_add(amount_a).ok_or(ErrorCode::MathOverflow)?,
+        reserve_b_after: pool_state.reserve_b.checked_add(amount_b).ok_or(ErrorCode::MathOverflow)?,
+        total_supply_after: pool_state.total_supply.checked_add(lp_tokens).ok_or(ErrorCode::MathOverflow)?,
+    })
+}
+
+pub fn quote_remove_liquidity_handler(
+    ctx: Context<Quote>,
+    lp_tokens: u64,
+) -> Result<RemoveLiquidityQuote> {
+    require!(lp_tokens > 0, ErrorCode::InvalidAmount);
+    
+    let pool_state = &ctx.accounts.pool_state;
+    
+    let (amount_a, amount_b) = calculate_withdrawal_amounts(
+        lp_tokens,
+        pool_state.reserve_a,
+        pool_state.reserve_b,
+        pool_state.total_supply,
+    )?;
+    
+    Ok(RemoveLiquidityQuote {
+        amount_a,
+        amount_b,
+        lp_tokens,
+        reserve_a_after: pool_state.reserve_a.checked_sub(amount_a).ok_or(ErrorCode::MathOverflow)?,
+        reserve_b_after: pool_state.reserve_b.checked_sub(amount_b).ok_or(ErrorCode::MathOverflow)?,
+        total_supply_after: pool_state.total_supply.checked_sub(lp_tokens).ok_or(ErrorCode::MathOverflow)?,
+    })
+}
+
+#[derive(Accounts)]
+pub struct Quote<'info> {
+    #[account(
+        seeds = [b"pool_state", pool_state.token_mint_a.as_ref(), pool_state.token_mint_b.as_ref()],
+        bump = pool_state.bump,
+    )]
+    pub pool_state: Box<Account<'info, PoolState>>,  // ✅ BOX
+}
+
+/// The optional accounts match those of `Swap` and must all belong to the trader
+/// being quoted.
+#[derive(Accounts)]
+pub struct QuoteSwap<'info> {
+    #[account(
+        seeds = [b"pool_state", pool_state.token_mint_a.as_ref(), pool_state.token_mint_b.as_ref()],
+        bump = pool_state.bump,
+    )]
+    pub pool_state: Box<Account<'info, PoolState>>,  // ✅ BOX
+    
+    #[account(
+        seeds = [b"fee_discount", fee_discount.trader.as_ref()],
+        bump = fee_discount.bump,
+    )]
+    pub fee_discount: Option<Box<Account<'info, FeeDiscount>>>,  // ✅ BOX
+    
+    #[account(
+        seeds = [b"amm_config"],
+        bump = config.bump,
+    )]
+    pub config: Option<Box<Account<'info, AmmConfig>>>,  // ✅ BOX
+    
+    pub discount_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,  // ✅ BOX
+    
+    #[account(
+        seeds = [b"trader_stats", pool_state.key().as_ref(), trader_stats.owner.as_ref()],
+        bump = trader_stats.bump,
+    )]
+    pub trader_stats: Option<Box<Account<'info, TraderStats>>>,  // ✅ BOX
+    
+    /// CHECK: instructions sysvar, required when the pool has sandwich protection on
+    #[account(address = sysvar::instructions::ID)]
+    pub instructions_sysvar: Option<UncheckedAccount<'info>>,
+}
+
+impl<'info> QuoteSwap<'info> {
+    /// Best fee discount of the quoted trader on `day`.
+    pub fn fee_discount_bps(&self, day: i64) -> Result<u64> {
+        let traders = [
+            self.fee_discount.as_ref().map(|fee_discount| fee_discount.trader),
+            self.discount_token_account.as_ref().map(|account| account.owner),
+            self.trader_stats.as_ref().map(|trader_stats| trader_stats.owner),
+        ];
+        let mut traders = traders.into_iter().flatten();
+        if let Some(trader) = traders.next() {
+            require!(traders.all(|other| other == trader), ErrorCode::InvalidFeeDiscount);
+        }
+        
+        fee_discount_bps(
+            &self.pool_state,
+            self.fee_discount.as_deref().map(|fee_discount| &**fee_discount),
+            self.config.as_deref().map(|config| &**config),
+            self.discount_token_account.as_deref().map(|account| &**account),
+            self.trader_stats.as_deref().map(|trader_stats| &**trader_stats),
+            day,
+        )
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct SwapQuote {
+    pub amount_in: u64,
+    pub amount_out: u64,
+    pub fee_amount: u64,
+    pub fee_numerator: u64,
+    pub price_impact_bps: u64,
+    pub reserve_a_after: u64,
+    pub reserve_b_after: u64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct AddLiquidityQuote {
+    pub amount_a: u64,
+    pub amount_b: u64,
+    pub lp_tokens: u64,
+    pub reserve_a_after: u64,
+    pub reserve_b_after: u64,
+    pub total_supply_after: u64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct RemoveLiquidityQuote {
+    pub amount_a: u64,
+    pub amount_b: u64,
+    pub lp_tokens: u64,
+    pub reserve_a_after: u64,
+    pub reserve_b_after: u64,
+    pub total_supply_after: u64,
+}
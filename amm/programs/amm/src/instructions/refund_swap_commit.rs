@@ -0,0 +1,112 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::state::{PoolState, SwapCommit};
+use crate::error::ErrorCode;
+use crate::instructions::commit_swap::COMMIT_REVEAL_TIMEOUT_SLOTS;
+use crate::instructions::shared::{transfer_tokens_signed, close_token_account_signed};
+
+/// Returns the escrowed deposit of a commitment that was not revealed before the
+/// timeout, along with anything else sent to the escrow.
+pub fn refund_swap_commit_handler(ctx: Context<RefundSwapCommit>) -> Result<()> {
+    let commit = &ctx.accounts.commit;
+    let clock = Clock::get()?;
+    
+    require!(
+        clock.slot.saturating_sub(commit.committed_slot) >= COMMIT_REVEAL_TIMEOUT_SLOTS,
+        ErrorCode::CommitNotExpired
+    );
+    
+    let pool_key = ctx.accounts.pool_state.key();
+    let owner_key = ctx.accounts.owner.key();
+    let commit_seeds = &[
+        b"swap_commit",
+        pool_key.as_ref(),
+        owner_key.as_ref(),
+        commit.hash.as_ref(),
+        &[commit.bump],
+    ];
+    let signer_seeds = &[&commit_seeds[..]];
+    
+    let deposit = ctx.accounts.commit_escrow.amount;
+    
+    transfer_tokens_signed(
+        &ctx.accounts.commit_escrow,
+        &ctx.accounts.user_token,
+        &ctx.accounts.deposit_mint,
+        &ctx.accounts.commit.to_account_info(),
+        &ctx.accounts.token_program,
+        deposit,
+        signer_seeds,
+    )?;
+    
+    close_token_account_signed(
+        &ctx.accounts.commit_escrow,
+        &ctx.accounts.owner.to_account_info(),
+        &ctx.accounts.commit.to_account_info(),
+        &ctx.accounts.token_program,
+        signer_seeds,
+    )?;
+    
+    ctx.accounts.commit.close(ctx.accounts.owner.to_account_info())?;
+    
+    emit!(SwapCommitRefunded {
+        pool: pool_key,
+        owner: owner_key,
+        commit: ctx.accounts.commit.key(),
+        deposit,
+        timestamp: clock.unix_timestamp,
+    });
+    
+    msg!("Swap commit refunded: {}", deposit);
+    
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct RefundSwapCommit<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    
+    #[account(
+        seeds = [b"pool_state", pool_state.token_mint_a.as_ref(), pool_state.token_mint_b.as_ref()],
+        bump = pool_state.bump,
+    )]
+    pub pool_state: Box<Account<'info, PoolState>>,  // ✅ BOX
+    
+    #[account(address = commit_escrow.mint)]
+    pub deposit_mint: Box<InterfaceAccount<'info, Mint>>,  // ✅ BOX
+    
+    #[account(
+        mut,
+        seeds = [b"swap_commit", pool_state.key().as_ref(), owner.key().as_ref(), commit.hash.as_ref()],
+        bump = commit.bump,
+        has_one = owner @ ErrorCode::Unauthorized,
+    )]
+    pub commit: Box<Account<'info, SwapCommit>>,  // ✅ BOX
+    
+    #[account(
+        mut,
+        seeds = [b"commit_escrow", commit.key().as_ref()],
+        bump = commit.escrow_bump,
+    )]
+    pub commit_escrow: Box<InterfaceAccount<'info, TokenAccount>>,  // ✅ BOX
+    
+    #[account(
+        mut,
+        token::mint = deposit_mint,
+        token::authority = owner,
+    )]
+    pub user_token: Box<InterfaceAccount<'info, TokenAccount>>,  // ✅ BOX
+    
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[event]
+pub struct SwapCommitRefunded {
+    pub pool: Pubkey,
+    pub owner: Pubkey,
+    pub commit: Pubkey,
+    pub deposit: u64,
+    pub timestamp: i64,
+}
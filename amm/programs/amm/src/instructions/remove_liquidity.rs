@@ -6,14 +6,19 @@ use anchor_spl::{
 
 use crate::state::PoolState;
 use crate::error::ErrorCode;
-use crate::instructions::shared::{transfer_tokens_signed, burn_lp_tokens};
+use crate::instructions::shared::{
+    transfer_tokens_signed, burn_lp_tokens, calculate_withdrawal_amounts, check_deadline,
+    update_price_oracle,
+};
 
 pub fn remove_liquidity_handler(
     ctx: Context<RemoveLiquidity>,
     lp_tokens_to_burn: u64,
     min_amount_a: u64,
     min_amount_b: u64,
-) -> Result<()> {
+    deadline: Option<i64>,
+) -> Result<RemoveLiquidityResult> {
+    check_deadline(deadline)?;
     require!(lp_tokens_to_burn > 0, ErrorCode::InvalidAmount);
     
     let pool_state = &mut ctx.accounts.pool_state;
@@ -23,22 +28,12 @@ pub fn remove_liquidity_handler(
         ErrorCode::InsufficientBalance
     );
     
-    require!(
-        pool_state.total_supply >= lp_tokens_to_burn,
-        ErrorCode::InsufficientLPTokens
-    );
-    
-    let amount_a = (lp_tokens_to_burn as u128)
-        .checked_mul(pool_state.reserve_a as u128)
-        .ok_or(ErrorCode::MathOverflow)?
-        .checked_div(pool_state.total_supply as u128)
-        .ok_or(ErrorCode::DivisionByZero)? as u64;
-    
-    let amount_b = (lp_tokens_to_burn as u128)
-        .checked_mul(pool_state.reserve_b as u128)
-        .ok_or(ErrorCode::MathOverflow)?
-        .checked_div(pool_state.total_supply as u128)
-        .ok_or(ErrorCode::DivisionByZero)? as u64;
+    let (amount_a, amount_b) = calculate_withdrawal_amounts(
+        lp_tokens_to_burn,
+        pool_state.reserve_a,
+        pool_state.reserve_b,
+        pool_state.total_supply,
+    )?;
     
     require!(amount_a >= min_amount_a, ErrorCode::InsufficientAmountA);
     require!(amount_b >= min_amount_b, ErrorCode::InsufficientAmountB);
@@ -89,6 +84,9 @@ pub fn remove_liquidity_handler(
         signer_seeds,
     )?;
     
+    let clock = Clock::get()?;
+    update_price_oracle(pool_state, clock.unix_timestamp)?;
+    
     pool_state.reserve_a = pool_state.reserve_a
         .checked_sub(amount_a)
         .ok_or(ErrorCode::MathOverflow)?;
@@ -101,7 +99,6 @@ pub fn remove_liquidity_handler(
         .checked_sub(lp_tokens_to_burn)
         .ok_or(ErrorCode::MathOverflow)?;
     
-    let clock = Clock::get()?;
     emit!(LiquidityRemoved {
         pool: pool_state.key(),
         provider: ctx.accounts.user.key(),
@@ -119,7 +116,11 @@ pub fn remove_liquidity_handler(
         amount_b
     );
     
-    Ok(())
+    Ok(RemoveLiquidityResult {
+        amount_a,
+        amount_b,
+        lp_tokens_burned: lp_tokens_to_burn,
+    })
 }
 
 #[derive(Accounts)]
@@ -206,4 +207,12 @@ pub struct LiquidityRemoved {
     pub lp_tokens_burned: u64,
     pub total_supply: u64,
     pub timestamp: i64,
+}
+
+/// Withdrawn amounts, returned to the caller through return data.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct RemoveLiquidityResult {
+    pub amount_a: u64,
+    pub amount_b: u64,
+    pub lp_tokens_burned: u64,
 }
\ No newline at end of file
@@ -0,0 +1,131 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::TokenAccount;
+
+use crate::state::SwapCommit;
+use crate::error::ErrorCode;
+use crate::instructions::swap::*;
+use crate::instructions::commit_swap::{
+    swap_commitment_hash, COMMIT_REVEAL_MIN_DELAY_SLOTS, COMMIT_REVEAL_TIMEOUT_SLOTS,
+};
+use crate::instructions::shared::{transfer_tokens_signed, close_token_account_signed};
+
+/// Checks the revealed parameters against the commitment, returns the escrowed
+/// deposit to the user and runs the swap. The unswapped rest of the deposit
+/// stays with the user.
+pub fn reveal_swap_handler(
+    ctx: Context<RevealSwap>,
+    amount_in: u64,
+    min_amount_out: u64,
+    salt: [u8; 32],
+) -> Result<SwapResult> {
+    let commit = &ctx.accounts.commit;
+    let clock = Clock::get()?;
+    
+    require!(
+        swap_commitment_hash(amount_in, min_amount_out, &salt, &commit.owner) == commit.hash,
+        ErrorCode::InvalidCommitment
+    );
+    require!(amount_in <= commit.deposit, ErrorCode::InvalidCommitment);
+    
+    let elapsed_slots = clock.slot.saturating_sub(commit.committed_slot);
+    require!(
+        elapsed_slots >= COMMIT_REVEAL_MIN_DELAY_SLOTS,
+        ErrorCode::RevealTooEarly
+    );
+    require!(
+        elapsed_slots < COMMIT_REVEAL_TIMEOUT_SLOTS,
+        ErrorCode::CommitExpired
+    );
+    
+    let is_a_to_b = commit.is_a_to_b;
+    // Everything in the escrow goes back, including tokens sent to it directly,
+    // so that it can be closed.
+    let escrow_amount = ctx.accounts.commit_escrow.amount;
+    
+    let pool_key = ctx.accounts.swap.pool_state.key();
+    let owner_key = commit.owner;
+    let commit_seeds = &[
+        b"swap_commit",
+        pool_key.as_ref(),
+        owner_key.as_ref(),
+        commit.hash.as_ref(),
+        &[commit.bump],
+    ];
+    let signer_seeds = &[&commit_seeds[..]];
+    
+    let swap = &mut ctx.accounts.swap;
+    let (user_in, mint_in) = if is_a_to_b {
+        (&swap.user_token_a, &swap.token_mint_a)
+    } else {
+        (&swap.user_token_b, &swap.token_mint_b)
+    };
+    
+    transfer_tokens_signed(
+        &ctx.accounts.commit_escrow,
+        user_in,
+        mint_in,
+        &ctx.accounts.commit.to_account_info(),
+        &swap.token_program,
+        escrow_amount,
+        signer_seeds,
+    )?;
+    
+    close_token_account_signed(
+        &ctx.accounts.commit_escrow,
+        &swap.user.to_account_info(),
+        &ctx.accounts.commit.to_account_info(),
+        &swap.token_program,
+        signer_seeds,
+    )?;
+    
+    ctx.accounts.commit.close(swap.user.to_account_info())?;
+    
+    if is_a_to_b {
+        swap.user_token_a.reload()?;
+    } else {
+        swap.user_token_b.reload()?;
+    }
+    
+    emit!(SwapRevealed {
+        pool: pool_key,
+        owner: owner_key,
+        commit: ctx.accounts.commit.key(),
+        amount_in,
+        min_amount_out,
+        slot: clock.slot,
+        timestamp: clock.unix_timestamp,
+    });
+    
+    process_swap(swap, amount_in, min_amount_out, is_a_to_b, None, None)
+}
+
+#[derive(Accounts)]
+pub struct RevealSwap<'info> {
+    pub swap: Swap<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"swap_commit", swap.pool_state.key().as_ref(), swap.user.key().as_ref(), commit.hash.as_ref()],
+        bump = commit.bump,
+        constraint = commit.owner == swap.user.key() @ ErrorCode::Unauthorized,
+    )]
+    pub commit: Box<Account<'info, SwapCommit>>,  // ✅ BOX
+    
+    #[account(
+        mut,
+        seeds = [b"commit_escrow", commit.key().as_ref()],
+        bump = commit.escrow_bump,
+    )]
+    pub commit_escrow: Box<InterfaceAccount<'info, TokenAccount>>,  // ✅ BOX
+}
+
+#[event]
+pub struct SwapRevealed {
+    pub pool: Pubkey,
+    pub owner: Pubkey,
+    pub commit: Pubkey,
+    pub amount_in: u64,
+    pub min_amount_out: u64,
+    pub slot: u64,
+    pub timestamp: i64,
+}
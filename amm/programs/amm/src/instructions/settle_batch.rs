@@ -0,0 +1,559 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::state::{Batch, BatchOrder, PoolState, MAX_BATCH_ORDERS};
+use crate::error::ErrorCode;
+use crate::instructions::shared::{
+    transfer_tokens_signed, sweep_token_account_signed, calculate_fee_amount,
+    effective_fee_numerator, accrue_fee_growth, update_price_oracle, update_volatility, spot_price,
+    refresh_slot_reference, slot_price_move_bps, PRICE_SCALE,
+};
+use crate::instructions::swap::CircuitBreakerTriggered;
+
+/// Orders of a batch that clear, what each receives, and the clearing price.
+struct BatchClearing {
+    filled: [bool; MAX_BATCH_ORDERS],
+    amounts_out: [u64; MAX_BATCH_ORDERS],
+    /// Token B per token A, scaled by `PRICE_SCALE`.
+    clearing_price: u64,
+}
+
+/// Token amounts a settlement moves in and out of the pool.
+#[derive(Default, Clone, Copy)]
+struct BatchTotals {
+    amount_in_a: u64,
+    amount_in_b: u64,
+    amount_out_a: u64,
+    amount_out_b: u64,
+    fee_a: u64,
+    fee_b: u64,
+}
+
+impl BatchTotals {
+    /// Pool reserves once these amounts are applied.
+    fn reserves_after(&self, pool_state: &PoolState) -> Result<(u64, u64)> {
+        let reserve_a = pool_state.reserve_a
+            .checked_add(self.amount_in_a)
+            .ok_or(ErrorCode::MathOverflow)?
+            .checked_sub(self.amount_out_a)
+            .ok_or(ErrorCode::MathOverflow)?;
+        
+        let reserve_b = pool_state.reserve_b
+            .checked_add(self.amount_in_b)
+            .ok_or(ErrorCode::MathOverflow)?
+            .checked_sub(self.amount_out_b)
+            .ok_or(ErrorCode::MathOverflow)?;
+        
+        Ok((reserve_a, reserve_b))
+    }
+}
+
+/// Fills every order of a closed batch at one clearing price and refunds the
+/// orders whose `min_amount_out` it cannot meet. A clearing price past the pool's
+/// circuit breaker refunds every order. Payouts the recorded account can no
+/// longer receive stay in the batch escrows for `claim_batch_order`, and the
+/// batch is only closed once nothing is left to claim. `remaining_accounts` holds
+/// each order's input and output token account, in order.
+pub fn settle_batch_handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, SettleBatch<'info>>,
+) -> Result<()> {
+    let clock = Clock::get()?;
+    let batch = &ctx.accounts.batch;
+    
+    require!(clock.slot > batch.end_slot, ErrorCode::BatchNotReady);
+    
+    let order_count = batch.order_count as usize;
+    let orders: Vec<BatchOrder> = batch.orders[..order_count].to_vec();
+    let batch_id = batch.batch_id;
+    let batch_bump = batch.bump;
+    
+    require!(
+        ctx.remaining_accounts.len() == order_count * 2,
+        ErrorCode::InvalidBatchAccounts
+    );
+    
+    let pool_state = &ctx.accounts.pool_state;
+    let mut fees = [0u64; MAX_BATCH_ORDERS];
+    for (fee, order) in fees.iter_mut().zip(orders.iter()) {
+        let reserve_in = if order.is_a_to_b { pool_state.reserve_a } else { pool_state.reserve_b };
+        let fee_numerator = effective_fee_numerator(pool_state, order.is_a_to_b, clock.unix_timestamp)?;
+        *fee = calculate_fee_amount(pool_state, order.amount_in, reserve_in, fee_numerator)?;
+    }
+    
+    let BatchClearing {
+        mut filled,
+        amounts_out,
+        clearing_price,
+    } = clear_batch(pool_state.reserve_a, pool_state.reserve_b, &orders, &fees)?;
+    
+    let mut totals = batch_totals(&orders, &filled, &amounts_out, &fees)?;
+    
+    let pool_state = &mut ctx.accounts.pool_state;
+    if pool_state.max_slot_price_move_bps > 0 && filled.contains(&true) {
+        refresh_slot_reference(pool_state, clock.slot)?;
+        
+        let (reserve_a_after, reserve_b_after) = totals.reserves_after(pool_state)?;
+        let price_move_bps = slot_price_move_bps(pool_state, reserve_a_after, reserve_b_after)?;
+        
+        if price_move_bps > pool_state.max_slot_price_move_bps {
+            emit!(CircuitBreakerTriggered {
+                pool: pool_state.key(),
+                slot: clock.slot,
+                reference_price: pool_state.slot_reference_price,
+                price_move_bps,
+                max_slot_price_move_bps: pool_state.max_slot_price_move_bps,
+                timestamp: clock.unix_timestamp,
+            });
+            
+            // Failing would trip again on every retry, so the whole batch is
+            // refunded instead.
+            filled = [false; MAX_BATCH_ORDERS];
+            totals = BatchTotals::default();
+        }
+    }
+    
+    let BatchTotals {
+        amount_in_a,
+        amount_in_b,
+        amount_out_a,
+        amount_out_b,
+        fee_a,
+        fee_b,
+    } = totals;
+    
+    let pool_key = ctx.accounts.pool_state.key();
+    let batch_id_bytes = batch_id.to_le_bytes();
+    let batch_seeds = &[
+        b"batch",
+        pool_key.as_ref(),
+        batch_id_bytes.as_ref(),
+        &[batch_bump],
+    ];
+    let batch_signer = &[&batch_seeds[..]];
+    
+    let authority_bump = ctx.accounts.pool_state.authority_bump;
+    let authority_seeds = &[
+        b"authority",
+        pool_key.as_ref(),
+        &[authority_bump],
+    ];
+    let authority_signer = &[&authority_seeds[..]];
+    
+    let batch_info = ctx.accounts.batch.to_account_info();
+    
+    if amount_in_a > 0 {
+        transfer_tokens_signed(
+            &ctx.accounts.batch_escrow_a,
+            &ctx.accounts.vault_a,
+            &ctx.accounts.token_mint_a,
+            &batch_info,
+            &ctx.accounts.token_program,
+            amount_in_a,
+            batch_signer,
+        )?;
+    }
+    
+    if amount_in_b > 0 {
+        transfer_tokens_signed(
+            &ctx.accounts.batch_escrow_b,
+            &ctx.accounts.vault_b,
+            &ctx.accounts.token_mint_b,
+            &batch_info,
+            &ctx.accounts.token_program,
+            amount_in_b,
+            batch_signer,
+        )?;
+    }
+    
+    let mut claimable = [0u64; MAX_BATCH_ORDERS];
+    
+    for (index, order) in orders.iter().enumerate() {
+        let token_in_info = &ctx.remaining_accounts[index * 2];
+        let token_out_info = &ctx.remaining_accounts[index * 2 + 1];
+        
+        require_keys_eq!(
+            token_in_info.key(),
+            order.token_in_account,
+            ErrorCode::InvalidBatchAccounts
+        );
+        require_keys_eq!(
+            token_out_info.key(),
+            order.token_out_account,
+            ErrorCode::InvalidBatchAccounts
+        );
+        
+        let (vault_out, mint_out, escrow_in, mint_in, escrow_out) = if order.is_a_to_b {
+            (
+                &ctx.accounts.vault_b,
+                &ctx.accounts.token_mint_b,
+                &ctx.accounts.batch_escrow_a,
+                &ctx.accounts.token_mint_a,
+                &ctx.accounts.batch_escrow_b,
+            )
+        } else {
+            (
+                &ctx.accounts.vault_a,
+                &ctx.accounts.token_mint_a,
+                &ctx.accounts.batch_escrow_b,
+                &ctx.accounts.token_mint_b,
+                &ctx.accounts.batch_escrow_a,
+            )
+        };
+        
+        // A recorded account that can no longer receive tokens must not block the
+        // whole batch: its payout moves to (or stays in) the batch escrow instead.
+        if filled[index] {
+            match payable_token_account(token_out_info, mint_out, &ctx.accounts.token_program) {
+                Some(token_out) => transfer_tokens_signed(
+                    vault_out,
+                    &token_out,
+                    mint_out,
+                    &ctx.accounts.pool_authority,
+                    &ctx.accounts.token_program,
+                    amounts_out[index],
+                    authority_signer,
+                )?,
+                None => {
+                    transfer_tokens_signed(
+                        vault_out,
+                        escrow_out,
+                        mint_out,
+                        &ctx.accounts.pool_authority,
+                        &ctx.accounts.token_program,
+                        amounts_out[index],
+                        authority_signer,
+                    )?;
+                    claimable[index] = amounts_out[index];
+                }
+            }
+        } else {
+            match payable_token_account(token_in_info, mint_in, &ctx.accounts.token_program) {
+                Some(token_in) => transfer_tokens_signed(
+                    escrow_in,
+                    &token_in,
+                    mint_in,
+                    &batch_info,
+                    &ctx.accounts.token_program,
+                    order.amount_in,
+                    batch_signer,
+                )?,
+                None => claimable[index] = order.amount_in,
+            }
+        }
+    }
+    
+    let batch = &mut ctx.accounts.batch;
+    batch.settled = true;
+    for (index, order) in batch.orders[..order_count].iter_mut().enumerate() {
+        order.filled = filled[index];
+        order.claimable_amount = claimable[index];
+    }
+    
+    let claimable_orders = claimable.iter().filter(|amount| **amount > 0).count() as u8;
+    let mut swept_a = 0;
+    let mut swept_b = 0;
+    
+    if claimable_orders == 0 {
+        ctx.accounts.batch_escrow_a.reload()?;
+        ctx.accounts.batch_escrow_b.reload()?;
+        
+        swept_a = sweep_token_account_signed(
+            &ctx.accounts.batch_escrow_a,
+            &ctx.accounts.vault_a,
+            &ctx.accounts.token_mint_a,
+            &batch_info,
+            &ctx.accounts.creator,
+            &ctx.accounts.token_program,
+            batch_signer,
+        )?;
+        
+        swept_b = sweep_token_account_signed(
+            &ctx.accounts.batch_escrow_b,
+            &ctx.accounts.vault_b,
+            &ctx.accounts.token_mint_b,
+            &batch_info,
+            &ctx.accounts.creator,
+            &ctx.accounts.token_program,
+            batch_signer,
+        )?;
+        
+        ctx.accounts.batch.close(ctx.accounts.creator.to_account_info())?;
+    }
+    
+    let filled_orders = filled[..order_count].iter().filter(|filled| **filled).count() as u8;
+    
+    let pool_state = &mut ctx.accounts.pool_state;
+    update_price_oracle(pool_state, clock.unix_timestamp)?;
+    
+    if filled_orders > 0 {
+        let price_before = spot_price(pool_state.reserve_a, pool_state.reserve_b)?;
+        
+        let k_before = (pool_state.reserve_a as u128)
+            .checked_mul(pool_state.reserve_b as u128)
+            .ok_or(ErrorCode::MathOverflow)?;
+        
+        (pool_state.reserve_a, pool_state.reserve_b) = totals.reserves_after(pool_state)?;
+        
+        accrue_fee_growth(pool_state, fee_a, true)?;
+        accrue_fee_growth(pool_state, fee_b, false)?;
+        
+        let k_after = (pool_state.reserve_a as u128)
+            .checked_mul(pool_state.reserve_b as u128)
+            .ok_or(ErrorCode::MathOverflow)?;
+        
+        require!(
+            k_after >= k_before,
+            ErrorCode::InvalidConstantProduct
+        );
+        
+        let price_after = spot_price(pool_state.reserve_a, pool_state.reserve_b)?;
+        update_volatility(pool_state, price_before, price_after, clock.unix_timestamp)?;
+    }
+    
+    // Tokens sent to the escrows outside of orders end up with the LPs.
+    pool_state.reserve_a = pool_state.reserve_a
+        .checked_add(swept_a)
+        .ok_or(ErrorCode::MathOverflow)?;
+    pool_state.reserve_b = pool_state.reserve_b
+        .checked_add(swept_b)
+        .ok_or(ErrorCode::MathOverflow)?;
+    
+    pool_state.current_batch_id = pool_state.current_batch_id
+        .checked_add(1)
+        .ok_or(ErrorCode::MathOverflow)?;
+    
+    emit!(BatchSettled {
+        pool: pool_key,
+        batch_id,
+        clearing_price,
+        filled_orders,
+        refunded_orders: order_count as u8 - filled_orders,
+        claimable_orders,
+        amount_in_a,
+        amount_in_b,
+        amount_out_a,
+        amount_out_b,
+        reserve_a: pool_state.reserve_a,
+        reserve_b: pool_state.reserve_b,
+        timestamp: clock.unix_timestamp,
+    });
+    
+    msg!(
+        "Batch {} settled at {} ({} filled, {} refunded, {} left to claim)",
+        batch_id,
+        clearing_price,
+        filled_orders,
+        order_count as u8 - filled_orders,
+        claimable_orders
+    );
+    
+    Ok(())
+}
+
+/// `info` as a token account of `mint` that can receive a transfer, or `None` when
+/// it was closed, reassigned or frozen after the order was submitted.
+fn payable_token_account<'info>(
+    info: &'info AccountInfo<'info>,
+    mint: &InterfaceAccount<'info, Mint>,
+    token_program: &Interface<'info, TokenInterface>,
+) -> Option<InterfaceAccount<'info, TokenAccount>> {
+    if info.owner != token_program.key {
+        return None;
+    }
+    
+    let account = InterfaceAccount::<TokenAccount>::try_from(info).ok()?;
+    (account.mint == mint.key() && !account.is_frozen()).then_some(account)
+}
+
+/// Sums the inputs, outputs and fees of the filled orders of a batch.
+fn batch_totals(
+    orders: &[BatchOrder],
+    filled: &[bool; MAX_BATCH_ORDERS],
+    amounts_out: &[u64; MAX_BATCH_ORDERS],
+    fees: &[u64; MAX_BATCH_ORDERS],
+) -> Result<BatchTotals> {
+    let mut totals = BatchTotals::default();
+    
+    for (index, order) in orders.iter().enumerate() {
+        if !filled[index] {
+            continue;
+        }
+        
+        let (amount_in, amount_out, fee) = if order.is_a_to_b {
+            (&mut totals.amount_in_a, &mut totals.amount_out_b, &mut totals.fee_a)
+        } else {
+            (&mut totals.amount_in_b, &mut totals.amount_out_a, &mut totals.fee_b)
+        };
+        
+        *amount_in = amount_in.checked_add(order.amount_in).ok_or(ErrorCode::MathOverflow)?;
+        *amount_out = amount_out.checked_add(amounts_out[index]).ok_or(ErrorCode::MathOverflow)?;
+        *fee = fee.checked_add(fees[index]).ok_or(ErrorCode::MathOverflow)?;
+    }
+    
+    Ok(totals)
+}
+
+/// Clears a batch at `p = (reserve_b + b_in) / (reserve_a + a_in)` token B per
+/// token A, where `a_in` and `b_in` are the after-fee inputs of the filled orders.
+/// Paying every order at `p` leaves `reserve_a * reserve_b` unchanged, and with a
+/// single direction it matches the constant-product output. Orders below their
+/// `min_amount_out` are dropped and the price recomputed until every remaining
+/// order clears.
+fn clear_batch(
+    reserve_a: u64,
+    reserve_b: u64,
+    orders: &[BatchOrder],
+    fees: &[u64; MAX_BATCH_ORDERS],
+) -> Result<BatchClearing> {
+    let mut filled = [false; MAX_BATCH_ORDERS];
+    let mut amounts_out = [0u64; MAX_BATCH_ORDERS];
+    
+    if reserve_a == 0 || reserve_b == 0 {
+        return Ok(BatchClearing { filled, amounts_out, clearing_price: 0 });
+    }
+    
+    filled[..orders.len()].fill(true);
+    
+    loop {
+        let mut a_in: u128 = 0;
+        let mut b_in: u128 = 0;
+        
+        for (index, order) in orders.iter().enumerate() {
+            if !filled[index] {
+                continue;
+            }
+            
+            let amount_in_after_fee = order.amount_in
+                .checked_sub(fees[index])
+                .ok_or(ErrorCode::MathOverflow)? as u128;
+            
+            if order.is_a_to_b {
+                a_in = a_in.checked_add(amount_in_after_fee).ok_or(ErrorCode::MathOverflow)?;
+            } else {
+                b_in = b_in.checked_add(amount_in_after_fee).ok_or(ErrorCode::MathOverflow)?;
+            }
+        }
+        
+        let price_numerator = (reserve_b as u128)
+            .checked_add(b_in)
+            .ok_or(ErrorCode::MathOverflow)?;
+        let price_denominator = (reserve_a as u128)
+            .checked_add(a_in)
+            .ok_or(ErrorCode::MathOverflow)?;
+        
+        let mut dropped_order = false;
+        
+        for (index, order) in orders.iter().enumerate() {
+            if !filled[index] {
+                continue;
+            }
+            
+            let amount_in_after_fee = (order.amount_in - fees[index]) as u128;
+            let (numerator, denominator) = if order.is_a_to_b {
+                (price_numerator, price_denominator)
+            } else {
+                (price_denominator, price_numerator)
+            };
+            
+            let amount_out = amount_in_after_fee
+                .checked_mul(numerator)
+                .ok_or(ErrorCode::MathOverflow)?
+                .checked_div(denominator)
+                .ok_or(ErrorCode::DivisionByZero)?;
+            let amount_out = u64::try_from(amount_out).map_err(|_| ErrorCode::MathOverflow)?;
+            
+            if amount_out < order.min_amount_out.max(1) {
+                filled[index] = false;
+                dropped_order = true;
+            } else {
+                amounts_out[index] = amount_out;
+            }
+        }
+        
+        if !dropped_order {
+            let clearing_price = price_numerator
+                .checked_mul(PRICE_SCALE)
+                .ok_or(ErrorCode::MathOverflow)?
+                .checked_div(price_denominator)
+                .ok_or(ErrorCode::DivisionByZero)? as u64;
+            
+            return Ok(BatchClearing { filled, amounts_out, clearing_price });
+        }
+    }
+}
+
+#[derive(Accounts)]
+pub struct SettleBatch<'info> {
+    pub settler: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"pool_state", pool_state.token_mint_a.as_ref(), pool_state.token_mint_b.as_ref()],
+        bump = pool_state.bump,
+    )]
+    pub pool_state: Box<Account<'info, PoolState>>,  // ✅ BOX
+    
+    /// CHECK: PDA authority
+    #[account(
+        seeds = [b"authority", pool_state.key().as_ref()],
+        bump = pool_state.authority_bump,
+    )]
+    pub pool_authority: AccountInfo<'info>,
+    
+    #[account(address = pool_state.token_mint_a)]
+    pub token_mint_a: Box<InterfaceAccount<'info, Mint>>,  // ✅ BOX
+    
+    #[account(address = pool_state.token_mint_b)]
+    pub token_mint_b: Box<InterfaceAccount<'info, Mint>>,  // ✅ BOX
+    
+    #[account(mut, address = pool_state.vault_a)]
+    pub vault_a: Box<InterfaceAccount<'info, TokenAccount>>,  // ✅ BOX
+    
+    #[account(mut, address = pool_state.vault_b)]
+    pub vault_b: Box<InterfaceAccount<'info, TokenAccount>>,  // ✅ BOX
+    
+    #[account(
+        mut,
+        seeds = [b"batch", pool_state.key().as_ref(), &pool_state.current_batch_id.to_le_bytes()],
+        bump = batch.bump,
+    )]
+    pub batch: Box<Account<'info, Batch>>,  // ✅ BOX
+    
+    #[account(
+        mut,
+        seeds = [b"batch_escrow_a", batch.key().as_ref()],
+        bump = batch.escrow_a_bump,
+    )]
+    pub batch_escrow_a: Box<InterfaceAccount<'info, TokenAccount>>,  // ✅ BOX
+    
+    #[account(
+        mut,
+        seeds = [b"batch_escrow_b", batch.key().as_ref()],
+        bump = batch.escrow_b_bump,
+    )]
+    pub batch_escrow_b: Box<InterfaceAccount<'info, TokenAccount>>,  // ✅ BOX
+    
+    /// CHECK: receives the rent of the batch accounts
+    #[account(mut, address = batch.creator)]
+    pub creator: AccountInfo<'info>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[event]
+pub struct BatchSettled {
+    pub pool: Pubkey,
+    pub batch_id: u64,
+    pub clearing_price: u64,
+    pub filled_orders: u8,
+    pub refunded_orders: u8,
+    /// Orders whose payout could not be delivered and waits in the batch escrows.
+    pub claimable_orders: u8,
+    pub amount_in_a: u64,
+    pub amount_in_b: u64,
+    pub amount_out_a: u64,
+    pub amount_out_b: u64,
+    pub reserve_a: u64,
+    pub reserve_b: u64,
+    pub timestamp: i64,
+}
@@ -1,11 +1,53 @@
 use anchor_lang::prelude::*;
+use anchor_lang::Discriminator;
+use anchor_lang::solana_program::sysvar::instructions::{
+    load_current_index_checked, load_instruction_at_checked,
+};
 use anchor_spl::token_interface::{
     Mint, TokenAccount, TokenInterface,
     transfer_checked, TransferChecked,
     mint_to, MintTo,
     burn, Burn,
+    close_account, CloseAccount,
 };
+use solana_sdk_ids::ed25519_program;
 use crate::error::ErrorCode;
+use crate::state::{
+    AmmConfig, Farm, FarmReward, FarmStake, FeeTier, HolderDiscountTier, PoolState, PriceObservation,
+    TraderStats, VolumeDiscountTier, MAX_FEE_TIERS, MAX_HOLDER_DISCOUNT_TIERS, MAX_PRICE_OBSERVATIONS,
+    MAX_VOLUME_DISCOUNT_TIERS, TRADER_STATS_WINDOW_DAYS,
+};
+
+pub const BPS_DENOMINATOR: u64 = 10_000;
+pub const PRICE_SCALE: u128 = 1_000_000;
+/// LP base units locked on the first deposit, whatever the LP mint decimals.
+const MINIMUM_LIQUIDITY_FLOOR: u64 = 1000;
+/// LP mints with more decimals than this scale the locked amount up from the floor.
+const MINIMUM_LIQUIDITY_DECIMALS: u8 = 9;
+const MINIMUM_OUTPUT: u64 = 1;
+const FEE_GROWTH_FRACTIONAL_BITS: u32 = 64;
+/// Offset of `is_a_to_b` in swap instruction data: discriminator, `amount_in`, `min_amount_out`.
+const SWAP_DIRECTION_OFFSET: usize = 24;
+/// Position of the pool state in the account list of swap instructions.
+const SWAP_POOL_ACCOUNT_INDEX: usize = 1;
+/// Start and size of the signature offsets record in ed25519 program instruction data.
+const ED25519_OFFSETS_START: usize = 2;
+const ED25519_OFFSETS_SIZE: usize = 14;
+pub const REWARD_PER_SHARE_PRECISION: u128 = 1_000_000_000_000;
+pub const SECONDS_PER_DAY: i64 = 86_400;
+/// Minimum seconds between two stored price observations.
+pub const PRICE_OBSERVATION_PERIOD: i64 = 60;
+
+/// Result of running the fee and curve math for a swap, before any transfer.
+pub struct SwapComputation {
+    pub fee_amount: u64,
+    pub amount_in_after_fee: u64,
+    pub amount_out: u64,
+    pub price_before: u64,
+    pub price_impact_bps: u64,
+    /// Base fee rate charged, over the pool's `fee_denominator`, before size tiers.
+    pub fee_numerator: u64,
+}
 
 pub fn transfer_tokens<'info>(
     from: &InterfaceAccount<'info, TokenAccount>,
@@ -101,6 +143,71 @@ pub fn burn_lp_tokens<'info>(
     Ok(())
 }
 
+pub fn close_token_account_signed<'info>(
+    account: &InterfaceAccount<'info, TokenAccount>,
+    destination: &AccountInfo<'info>,
+    authority: &AccountInfo<'info>,
+    token_program: &Interface<'info, TokenInterface>,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let cpi_accounts = CloseAccount {
+        account: account.to_account_info(),
+        destination: destination.to_account_info(),
+        authority: authority.to_account_info(),
+    };
+    
+    let cpi_ctx = CpiContext::new_with_signer(
+        token_program.to_account_info(),
+        cpi_accounts,
+        signer_seeds,
+    );
+    
+    close_account(cpi_ctx)?;
+    
+    Ok(())
+}
+
+/// Transfers the whole balance of `account` to `destination`, closes `account`
+/// and returns the amount transferred.
+pub fn sweep_token_account_signed<'info>(
+    account: &InterfaceAccount<'info, TokenAccount>,
+    destination: &InterfaceAccount<'info, TokenAccount>,
+    mint: &InterfaceAccount<'info, Mint>,
+    authority: &AccountInfo<'info>,
+    rent_destination: &AccountInfo<'info>,
+    token_program: &Interface<'info, TokenInterface>,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<u64> {
+    let amount = account.amount;
+    
+    if amount > 0 {
+        transfer_tokens_signed(
+            account,
+            destination,
+            mint,
+            authority,
+            token_program,
+            amount,
+            signer_seeds,
+        )?;
+    }
+    
+    close_token_account_signed(account, rent_destination, authority, token_program, signer_seeds)?;
+    
+    Ok(amount)
+}
+
+pub fn check_deadline(deadline: Option<i64>) -> Result<()> {
+    if let Some(deadline) = deadline {
+        require!(
+            Clock::get()?.unix_timestamp <= deadline,
+            ErrorCode::DeadlineExceeded
+        );
+    }
+    
+    Ok(())
+}
+
 pub fn integer_sqrt(value: u128) -> u64 {
     if value == 0 {
         return 0;
@@ -174,4 +281,817 @@ pub fn calculate_swap_output(
     require!(amount_out > 0, ErrorCode::OutputBelowMinimum);
     
     Ok(amount_out)
+}
+
+/// Price impact in bps of receiving `amount_out` for `amount_in`, measured
+/// against the spot price `reserve_out / reserve_in` before the trade.
+pub fn calculate_price_impact_bps(
+    amount_in: u64,
+    amount_out: u64,
+    reserve_in: u64,
+    reserve_out: u64,
+) -> Result<u64> {
+    let spot_value = (amount_in as u128)
+        .checked_mul(reserve_out as u128)
+        .ok_or(ErrorCode::MathOverflow)?;
+    
+    let execution_value = (amount_out as u128)
+        .checked_mul(reserve_in as u128)
+        .ok_or(ErrorCode::MathOverflow)?;
+    
+    let price_impact_bps = spot_value
+        .saturating_sub(execution_value)
+        .checked_mul(BPS_DENOMINATOR as u128)
+        .ok_or(ErrorCode::MathOverflow)?
+        .checked_div(spot_value)
+        .ok_or(ErrorCode::DivisionByZero)? as u64;
+    
+    Ok(price_impact_bps)
+}
+
+/// Spot price of the output token in input-token units, scaled by `PRICE_SCALE`.
+pub fn spot_price(reserve_in: u64, reserve_out: u64) -> Result<u64> {
+    let price = (reserve_out as u128)
+        .checked_mul(PRICE_SCALE)
+        .ok_or(ErrorCode::MathOverflow)?
+        .checked_div(reserve_in as u128)
+        .ok_or(ErrorCode::DivisionByZero)? as u64;
+    
+    Ok(price)
+}
+
+/// Fails when the transaction holding the current instruction also swaps on `pool`
+/// in the opposite direction, either directly or as a `batch_swap` entry. Swaps
+/// invoked through an exempt program are let through; swaps other programs make
+/// through CPI are not visible in the transaction and are not checked.
+pub fn check_sandwich(
+    pool_state: &PoolState,
+    pool: &Pubkey,
+    instructions_sysvar: &AccountInfo,
+    is_a_to_b: bool,
+) -> Result<()> {
+    let current_index = load_current_index_checked(instructions_sysvar)?;
+    let current = load_instruction_at_checked(current_index as usize, instructions_sysvar)?;
+    
+    let exempt_programs =
+        &pool_state.sandwich_exempt_programs[..pool_state.sandwich_exempt_program_count as usize];
+    if exempt_programs.contains(&current.program_id) {
+        return Ok(());
+    }
+    
+    let instruction_count = {
+        let data = instructions_sysvar.try_borrow_data()?;
+        u16::from_le_bytes([data[0], data[1]])
+    };
+    
+    let swap_discriminators = [
+        crate::instruction::Swap::DISCRIMINATOR,
+        crate::instruction::SwapWithDeadline::DISCRIMINATOR,
+        crate::instruction::SwapWithReferral::DISCRIMINATOR,
+        crate::instruction::SwapRfq::DISCRIMINATOR,
+    ];
+    
+    for index in 0..instruction_count {
+        let instruction = load_instruction_at_checked(index as usize, instructions_sysvar)?;
+        if instruction.program_id != crate::ID {
+            continue;
+        }
+        
+        // Scanned even when current, so the entries of one batch can't trade
+        // against each other.
+        if instruction.data.starts_with(crate::instruction::BatchSwap::DISCRIMINATOR) {
+            let batch_swap = crate::instruction::BatchSwap::try_from_slice(
+                &instruction.data[crate::instruction::BatchSwap::DISCRIMINATOR.len()..],
+            )
+            .map_err(|_| ErrorCode::InvalidBatchSwap)?;
+            
+            require!(
+                batch_swap.entries
+                    .iter()
+                    .all(|entry| entry.pool != *pool || entry.is_a_to_b == is_a_to_b),
+                ErrorCode::SandwichDetected
+            );
+            continue;
+        }
+        
+        if index == current_index
+            || instruction.data.len() <= SWAP_DIRECTION_OFFSET
+            || instruction.accounts.get(SWAP_POOL_ACCOUNT_INDEX).map(|meta| &meta.pubkey) != Some(pool)
+        {
+            continue;
+        }
+        
+        let is_swap = swap_discriminators
+            .iter()
+            .any(|discriminator| instruction.data.starts_with(discriminator));
+        let other_is_a_to_b = instruction.data[SWAP_DIRECTION_OFFSET] != 0;
+        
+        require!(
+            !is_swap || other_is_a_to_b == is_a_to_b,
+            ErrorCode::SandwichDetected
+        );
+    }
+    
+    Ok(())
+}
+
+/// Checks that the instruction right before the current one has the ed25519 program
+/// verify a single signature by `signer` over `message`, with all data inline.
+pub fn verify_ed25519_signature(
+    instructions_sysvar: &AccountInfo,
+    signer: &Pubkey,
+    message: &[u8],
+) -> Result<()> {
+    let current_index = load_current_index_checked(instructions_sysvar)?;
+    require!(current_index > 0, ErrorCode::InvalidQuoteSignature);
+    
+    let instruction = load_instruction_at_checked(current_index as usize - 1, instructions_sysvar)?;
+    require_keys_eq!(
+        instruction.program_id,
+        ed25519_program::ID,
+        ErrorCode::InvalidQuoteSignature
+    );
+    
+    // Layout: signature count, padding, then one offsets record of seven u16 values.
+    let data = &instruction.data;
+    require!(
+        data.len() >= ED25519_OFFSETS_START + ED25519_OFFSETS_SIZE && data[0] == 1,
+        ErrorCode::InvalidQuoteSignature
+    );
+    
+    let read_u16 = |index: usize| {
+        let offset = ED25519_OFFSETS_START + index * 2;
+        u16::from_le_bytes([data[offset], data[offset + 1]])
+    };
+    let signature_instruction_index = read_u16(1);
+    let public_key_offset = read_u16(2) as usize;
+    let public_key_instruction_index = read_u16(3);
+    let message_offset = read_u16(4) as usize;
+    let message_size = read_u16(5) as usize;
+    let message_instruction_index = read_u16(6);
+    
+    require!(
+        signature_instruction_index == u16::MAX
+            && public_key_instruction_index == u16::MAX
+            && message_instruction_index == u16::MAX,
+        ErrorCode::InvalidQuoteSignature
+    );
+    
+    let public_key = data
+        .get(public_key_offset..public_key_offset + 32)
+        .ok_or(ErrorCode::InvalidQuoteSignature)?;
+    let signed_message = data
+        .get(message_offset..message_offset + message_size)
+        .ok_or(ErrorCode::InvalidQuoteSignature)?;
+    
+    require!(
+        public_key == signer.as_ref() && signed_message == message,
+        ErrorCode::InvalidQuoteSignature
+    );
+    
+    Ok(())
+}
+
+/// Records the A/B spot price as the slot reference on the first swap of `slot`.
+pub fn refresh_slot_reference(pool_state: &mut PoolState, slot: u64) -> Result<()> {
+    if pool_state.slot_reference_slot != slot {
+        pool_state.slot_reference_price = spot_price(pool_state.reserve_a, pool_state.reserve_b)?;
+        pool_state.slot_reference_slot = slot;
+    }
+    
+    Ok(())
+}
+
+/// Move of the A/B spot price at `reserve_a` and `reserve_b` away from the slot
+/// reference, in bps.
+pub fn slot_price_move_bps(pool_state: &PoolState, reserve_a: u64, reserve_b: u64) -> Result<u64> {
+    let price = spot_price(reserve_a, reserve_b)?;
+    
+    let price_move_bps = (price.abs_diff(pool_state.slot_reference_price) as u128)
+        .checked_mul(BPS_DENOMINATOR as u128)
+        .ok_or(ErrorCode::MathOverflow)?
+        .checked_div(pool_state.slot_reference_price as u128)
+        .ok_or(ErrorCode::DivisionByZero)? as u64;
+    
+    Ok(price_move_bps)
+}
+
+/// Cumulative prices of `pool_state` carried forward to `now` at the current spot prices.
+fn cumulative_prices(pool_state: &PoolState, now: i64) -> Result<(u128, u128)> {
+    let elapsed = now.saturating_sub(pool_state.price_cumulative_updated_at);
+    
+    if pool_state.price_cumulative_updated_at == 0
+        || elapsed <= 0
+        || pool_state.reserve_a == 0
+        || pool_state.reserve_b == 0
+    {
+        return Ok((pool_state.price_a_cumulative, pool_state.price_b_cumulative));
+    }
+    
+    let price_a = spot_price(pool_state.reserve_a, pool_state.reserve_b)? as u128;
+    let price_b = spot_price(pool_state.reserve_b, pool_state.reserve_a)? as u128;
+    
+    Ok((
+        pool_state.price_a_cumulative.wrapping_add(price_a.wrapping_mul(elapsed as u128)),
+        pool_state.price_b_cumulative.wrapping_add(price_b.wrapping_mul(elapsed as u128)),
+    ))
+}
+
+/// Accumulates the spot prices since the last update and stores an observation when
+/// `PRICE_OBSERVATION_PERIOD` has passed since the latest one. Call before the reserves change.
+pub fn update_price_oracle(pool_state: &mut PoolState, now: i64) -> Result<()> {
+    let (price_a_cumulative, price_b_cumulative) = cumulative_prices(pool_state, now)?;
+    pool_state.price_a_cumulative = price_a_cumulative;
+    pool_state.price_b_cumulative = price_b_cumulative;
+    pool_state.price_cumulative_updated_at = now;
+    
+    let latest = pool_state.price_observations[pool_state.price_observation_index as usize];
+    if now.saturating_sub(latest.timestamp) >= PRICE_OBSERVATION_PERIOD {
+        let index = (pool_state.price_observation_index as usize + 1) % MAX_PRICE_OBSERVATIONS;
+        pool_state.price_observations[index] = PriceObservation {
+            timestamp: now,
+            price_a_cumulative,
+            price_b_cumulative,
+        };
+        pool_state.price_observation_index = index as u8;
+    }
+    
+    Ok(())
+}
+
+/// Time-weighted average of the output-per-input price, scaled by `PRICE_SCALE`, since
+/// the newest observation at least `window` seconds old.
+pub fn twap_price(pool_state: &PoolState, is_a_to_b: bool, window: i64, now: i64) -> Result<u64> {
+    require!(window > 0, ErrorCode::InvalidTwapWindow);
+    
+    let start = now.checked_sub(window).ok_or(ErrorCode::MathOverflow)?;
+    let observation = pool_state.price_observations
+        .iter()
+        .filter(|observation| observation.timestamp > 0 && observation.timestamp <= start)
+        .max_by_key(|observation| observation.timestamp)
+        .ok_or(ErrorCode::TwapUnavailable)?;
+    
+    let (price_a_cumulative, price_b_cumulative) = cumulative_prices(pool_state, now)?;
+    let price_cumulative_delta = if is_a_to_b {
+        price_a_cumulative.wrapping_sub(observation.price_a_cumulative)
+    } else {
+        price_b_cumulative.wrapping_sub(observation.price_b_cumulative)
+    };
+    
+    let twap = price_cumulative_delta
+        .checked_div((now - observation.timestamp) as u128)
+        .ok_or(ErrorCode::DivisionByZero)?;
+    
+    Ok(u64::try_from(twap).map_err(|_| ErrorCode::MathOverflow)?)
+}
+
+/// Volatility accumulator of `pool_state` decayed linearly up to `now`.
+pub fn decayed_volatility(pool_state: &PoolState, now: i64) -> u64 {
+    let period = pool_state.volatility_decay_period;
+    let elapsed = now.saturating_sub(pool_state.volatility_updated_at).max(0);
+    
+    if period <= 0 || elapsed >= period {
+        return 0;
+    }
+    
+    ((pool_state.volatility_accumulator as u128) * ((period - elapsed) as u128)
+        / (period as u128)) as u64
+}
+
+/// Fee numerator of the swap direction.
+pub fn direction_fee_numerator(pool_state: &PoolState, is_a_to_b: bool) -> u64 {
+    if is_a_to_b {
+        pool_state.fee_numerator_a_to_b
+    } else {
+        pool_state.fee_numerator_b_to_a
+    }
+}
+
+/// Fee numerator charged at `now` in the swap direction. With the dynamic fee enabled
+/// it rises linearly from the direction's fee to `max_fee_numerator` as volatility
+/// approaches the cap.
+pub fn effective_fee_numerator(pool_state: &PoolState, is_a_to_b: bool, now: i64) -> Result<u64> {
+    let base_fee_numerator = direction_fee_numerator(pool_state, is_a_to_b);
+    
+    if pool_state.max_fee_numerator == 0 {
+        return Ok(base_fee_numerator);
+    }
+    
+    let volatility = decayed_volatility(pool_state, now).min(pool_state.volatility_cap_bps);
+    let fee_range = pool_state.max_fee_numerator.saturating_sub(base_fee_numerator);
+    
+    let dynamic_fee = (fee_range as u128)
+        .checked_mul(volatility as u128)
+        .ok_or(ErrorCode::MathOverflow)?
+        .checked_div(pool_state.volatility_cap_bps as u128)
+        .ok_or(ErrorCode::DivisionByZero)? as u64;
+    
+    let fee_numerator = base_fee_numerator
+        .checked_add(dynamic_fee)
+        .ok_or(ErrorCode::MathOverflow)?;
+    
+    Ok(fee_numerator)
+}
+
+/// Adds the price move of a swap to the decayed volatility accumulator.
+/// No-op while the dynamic fee is disabled.
+pub fn update_volatility(
+    pool_state: &mut PoolState,
+    price_before: u64,
+    price_after: u64,
+    now: i64,
+) -> Result<()> {
+    if pool_state.max_fee_numerator == 0 {
+        return Ok(());
+    }
+    
+    let price_move_bps = (price_before.abs_diff(price_after) as u128)
+        .checked_mul(BPS_DENOMINATOR as u128)
+        .ok_or(ErrorCode::MathOverflow)?
+        .checked_div(price_before as u128)
+        .ok_or(ErrorCode::DivisionByZero)? as u64;
+    
+    pool_state.volatility_accumulator = decayed_volatility(pool_state, now)
+        .saturating_add(price_move_bps);
+    pool_state.volatility_updated_at = now;
+    
+    Ok(())
+}
+
+/// Checks a trade-size fee schedule: at most `MAX_FEE_TIERS` tiers with strictly
+/// increasing thresholds and non-decreasing fees between `fee_numerator` and the fee cap.
+pub fn validate_fee_tiers(
+    fee_tiers: &[FeeTier],
+    fee_numerator: u64,
+    fee_denominator: u64,
+) -> Result<()> {
+    require!(fee_tiers.len() <= MAX_FEE_TIERS, ErrorCode::InvalidFeeTiers);
+    
+    let mut previous_threshold_bps = 0;
+    let mut previous_fee_numerator = fee_numerator;
+    
+    for tier in fee_tiers {
+        require!(
+            tier.threshold_bps > previous_threshold_bps,
+            ErrorCode::InvalidFeeTiers
+        );
+        require!(
+            tier.fee_numerator >= previous_fee_numerator,
+            ErrorCode::InvalidFeeTiers
+        );
+        require!(
+            tier.fee_numerator <= fee_denominator / 100,
+            ErrorCode::FeeTooHigh
+        );
+        
+        previous_threshold_bps = tier.threshold_bps;
+        previous_fee_numerator = tier.fee_numerator;
+    }
+    
+    Ok(())
+}
+
+/// Fee on `amount_in`, charging each slice of the trade the rate of the size tier
+/// it falls in. Slices below the first threshold pay `base_fee_numerator`, and no
+/// tier charges less than it.
+pub fn calculate_fee_amount(
+    pool_state: &PoolState,
+    amount_in: u64,
+    reserve_in: u64,
+    base_fee_numerator: u64,
+) -> Result<u64> {
+    let mut weighted_amount: u128 = 0;
+    let mut slice_start: u64 = 0;
+    let mut fee_numerator = base_fee_numerator;
+    
+    for tier in &pool_state.fee_tiers[..pool_state.fee_tier_count as usize] {
+        let threshold = (reserve_in as u128)
+            .checked_mul(tier.threshold_bps as u128)
+            .ok_or(ErrorCode::MathOverflow)?
+            .checked_div(BPS_DENOMINATOR as u128)
+            .ok_or(ErrorCode::DivisionByZero)?
+            .min(amount_in as u128) as u64;
+        
+        let slice = threshold.saturating_sub(slice_start);
+        weighted_amount = weighted_amount
+            .checked_add(
+                (slice as u128)
+                    .checked_mul(fee_numerator as u128)
+                    .ok_or(ErrorCode::MathOverflow)?,
+            )
+            .ok_or(ErrorCode::MathOverflow)?;
+        
+        slice_start = slice_start.max(threshold);
+        fee_numerator = tier.fee_numerator.max(base_fee_numerator);
+    }
+    
+    let remainder = amount_in.saturating_sub(slice_start);
+    weighted_amount = weighted_amount
+        .checked_add(
+            (remainder as u128)
+                .checked_mul(fee_numerator as u128)
+                .ok_or(ErrorCode::MathOverflow)?,
+        )
+        .ok_or(ErrorCode::MathOverflow)?;
+    
+    let fee_amount = weighted_amount
+        .checked_div(pool_state.fee_denominator as u128)
+        .ok_or(ErrorCode::DivisionByZero)? as u64;
+    
+    Ok(fee_amount)
+}
+
+/// Checks `(threshold, discount_bps)` tiers: strictly increasing thresholds and
+/// non-decreasing discounts up to 100%.
+fn validate_discount_tiers(tiers: impl Iterator<Item = (u64, u64)>) -> Result<()> {
+    let mut previous: Option<(u64, u64)> = None;
+    
+    for (threshold, discount_bps) in tiers {
+        require!(
+            discount_bps <= BPS_DENOMINATOR,
+            ErrorCode::InvalidFeeDiscount
+        );
+        
+        if let Some((previous_threshold, previous_discount_bps)) = previous {
+            require!(
+                threshold > previous_threshold && discount_bps >= previous_discount_bps,
+                ErrorCode::InvalidFeeDiscount
+            );
+        }
+        
+        previous = Some((threshold, discount_bps));
+    }
+    
+    Ok(())
+}
+
+pub fn validate_holder_discount_tiers(tiers: &[HolderDiscountTier]) -> Result<()> {
+    require!(
+        tiers.len() <= MAX_HOLDER_DISCOUNT_TIERS,
+        ErrorCode::InvalidFeeDiscount
+    );
+    
+    validate_discount_tiers(tiers.iter().map(|tier| (tier.min_balance, tier.discount_bps)))
+}
+
+pub fn validate_volume_discount_tiers(tiers: &[VolumeDiscountTier]) -> Result<()> {
+    require!(
+        tiers.len() <= MAX_VOLUME_DISCOUNT_TIERS,
+        ErrorCode::InvalidFeeDiscount
+    );
+    
+    validate_discount_tiers(tiers.iter().map(|tier| (tier.min_volume, tier.discount_bps)))
+}
+
+/// Discount of the highest holder tier reached by `balance`.
+pub fn holder_discount_bps(config: &AmmConfig, balance: u64) -> u64 {
+    config.holder_discount_tiers[..config.holder_discount_tier_count as usize]
+        .iter()
+        .rev()
+        .find(|tier| balance >= tier.min_balance)
+        .map_or(0, |tier| tier.discount_bps)
+}
+
+/// Discount of the highest volume tier of `pool_state` reached by `volume`.
+pub fn volume_discount_bps(pool_state: &PoolState, volume: u64) -> u64 {
+    pool_state.volume_discount_tiers[..pool_state.volume_discount_tier_count as usize]
+        .iter()
+        .rev()
+        .find(|tier| volume >= tier.min_volume)
+        .map_or(0, |tier| tier.discount_bps)
+}
+
+/// Volume recorded in `stats` over the window ending on `day`.
+pub fn rolling_volume(stats: &TraderStats, day: i64) -> u64 {
+    let window = TRADER_STATS_WINDOW_DAYS as i64;
+    let first_day = (day - window + 1).max(stats.last_day - window + 1);
+    
+    (first_day..=stats.last_day.min(day))
+        .map(|d| stats.daily_volume[d.rem_euclid(window) as usize])
+        .fold(0u64, |total, volume| total.saturating_add(volume))
+}
+
+/// Adds `volume` to the bucket of `day`, clearing buckets of days that left the window.
+pub fn record_trader_volume(stats: &mut TraderStats, day: i64, volume: u64) -> Result<()> {
+    let window = TRADER_STATS_WINDOW_DAYS as i64;
+    let day = day.max(stats.last_day);
+    
+    if day > stats.last_day {
+        let first_stale_day = (stats.last_day + 1).max(day - window + 1);
+        for d in first_stale_day..=day {
+            stats.daily_volume[d.rem_euclid(window) as usize] = 0;
+        }
+        stats.last_day = day;
+    }
+    
+    let bucket = &mut stats.daily_volume[day.rem_euclid(window) as usize];
+    *bucket = bucket.saturating_add(volume);
+    
+    stats.total_volume = stats.total_volume
+        .checked_add(volume)
+        .ok_or(ErrorCode::MathOverflow)?;
+    
+    Ok(())
+}
+
+/// Reduces `fee_amount` by `discount_bps`.
+pub fn apply_fee_discount(fee_amount: u64, discount_bps: u64) -> Result<u64> {
+    let discount = (fee_amount as u128)
+        .checked_mul(discount_bps.min(BPS_DENOMINATOR) as u128)
+        .ok_or(ErrorCode::MathOverflow)?
+        .checked_div(BPS_DENOMINATOR as u128)
+        .ok_or(ErrorCode::DivisionByZero)? as u64;
+    
+    let fee_amount = fee_amount
+        .checked_sub(discount)
+        .ok_or(ErrorCode::MathOverflow)?;
+    
+    Ok(fee_amount)
+}
+
+/// Runs the fee, curve and price impact checks of a swap against `pool_state`
+/// at time `now` without moving any tokens. `fee_discount_bps` is taken off the
+/// fee before the curve. Slippage is left to the caller.
+pub fn compute_swap(
+    pool_state: &PoolState,
+    amount_in: u64,
+    is_a_to_b: bool,
+    now: i64,
+    fee_discount_bps: u64,
+) -> Result<SwapComputation> {
+    require!(amount_in > 0, ErrorCode::InvalidAmount);
+    
+    let (reserve_in, reserve_out) = if is_a_to_b {
+        (pool_state.reserve_a, pool_state.reserve_b)
+    } else {
+        (pool_state.reserve_b, pool_state.reserve_a)
+    };
+    
+    require!(
+        reserve_in > 0 && reserve_out > 0, 
+        ErrorCode::EmptyReserves
+    );
+    
+    let price_before = spot_price(reserve_in, reserve_out)?;
+    let fee_numerator = effective_fee_numerator(pool_state, is_a_to_b, now)?;
+    
+    let fee_amount = calculate_fee_amount(pool_state, amount_in, reserve_in, fee_numerator)?;
+    let fee_amount = apply_fee_discount(fee_amount, fee_discount_bps)?;
+    
+    let amount_in_after_fee = amount_in
+        .checked_sub(fee_amount)
+        .ok_or(ErrorCode::MathOverflow)?;
+    
+    let amount_out = calculate_swap_output(
+        amount_in_after_fee,
+        reserve_in,
+        reserve_out,
+    )?;
+    
+    require!(
+        amount_out >= MINIMUM_OUTPUT,
+        ErrorCode::OutputTooSmall
+    );
+    
+    let price_impact_bps = calculate_price_impact_bps(
+        amount_in_after_fee,
+        amount_out,
+        reserve_in,
+        reserve_out,
+    )?;
+    
+    if pool_state.max_price_impact_bps > 0 {
+        require!(
+            price_impact_bps <= pool_state.max_price_impact_bps,
+            ErrorCode::PriceImpactTooHigh
+        );
+    }
+    
+    Ok(SwapComputation {
+        fee_amount,
+        amount_in_after_fee,
+        amount_out,
+        price_before,
+        price_impact_bps,
+        fee_numerator,
+    })
+}
+
+/// Decimals of the LP mint for a pair. The first deposit mints
+/// `sqrt(amount_a * amount_b)`, which carries the mean of the two decimals.
+pub fn lp_mint_decimals(decimals_a: u8, decimals_b: u8) -> u8 {
+    ((decimals_a as u16 + decimals_b as u16) / 2) as u8
+}
+
+/// LP amount locked out of circulation on the first deposit: 1000 base units,
+/// scaled up by 10 for every LP mint decimal above 9.
+pub fn minimum_liquidity(lp_decimals: u8) -> Result<u64> {
+    let minimum_liquidity = 10u64
+        .checked_pow(lp_decimals.saturating_sub(MINIMUM_LIQUIDITY_DECIMALS) as u32)
+        .and_then(|scale| scale.checked_mul(MINIMUM_LIQUIDITY_FLOOR))
+        .ok_or(ErrorCode::MathOverflow)?;
+    
+    Ok(minimum_liquidity)
+}
+
+/// LP tokens minted for depositing `amount_a` and `amount_b`. The first deposit
+/// locks `minimum_liquidity` out of circulation.
+pub fn calculate_lp_tokens(
+    amount_a: u64,
+    amount_b: u64,
+    reserve_a: u64,
+    reserve_b: u64,
+    total_supply: u64,
+    minimum_liquidity: u64,
+) -> Result<u64> {
+    let lp_tokens = if total_supply == 0 {
+        let product = (amount_a as u128)
+            .checked_mul(amount_b as u128)
+            .ok_or(ErrorCode::MathOverflow)?;
+        
+        let sqrt = integer_sqrt(product);
+        
+        require!(sqrt > minimum_liquidity, ErrorCode::InsufficientLiquidity);
+        
+        sqrt - minimum_liquidity
+    } else {
+        let lp_from_a = (amount_a as u128)
+            .checked_mul(total_supply as u128)
+            .ok_or(ErrorCode::MathOverflow)?
+            .checked_div(reserve_a as u128)
+            .ok_or(ErrorCode::DivisionByZero)? as u64;
+        
+        let lp_from_b = (amount_b as u128)
+            .checked_mul(total_supply as u128)
+            .ok_or(ErrorCode::MathOverflow)?
+            .checked_div(reserve_b as u128)
+            .ok_or(ErrorCode::DivisionByZero)? as u64;
+        
+        lp_from_a.min(lp_from_b)
+    };
+    
+    Ok(lp_tokens)
+}
+
+/// Token amounts paid out for burning `lp_tokens` of `total_supply`.
+pub fn calculate_withdrawal_amounts(
+    lp_tokens: u64,
+    reserve_a: u64,
+    reserve_b: u64,
+    total_supply: u64,
+) -> Result<(u64, u64)> {
+    require!(
+        total_supply >= lp_tokens,
+        ErrorCode::InsufficientLPTokens
+    );
+    
+    let amount_a = (lp_tokens as u128)
+        .checked_mul(reserve_a as u128)
+        .ok_or(ErrorCode::MathOverflow)?
+        .checked_div(total_supply as u128)
+        .ok_or(ErrorCode::DivisionByZero)? as u64;
+    
+    let amount_b = (lp_tokens as u128)
+        .checked_mul(reserve_b as u128)
+        .ok_or(ErrorCode::MathOverflow)?
+        .checked_div(total_supply as u128)
+        .ok_or(ErrorCode::DivisionByZero)? as u64;
+    
+    Ok((amount_a, amount_b))
+}
+
+/// Adds `lp_fee_amount` of the input token to the pool's per-share fee growth.
+pub fn accrue_fee_growth(
+    pool_state: &mut PoolState,
+    lp_fee_amount: u64,
+    is_a_to_b: bool,
+) -> Result<()> {
+    if pool_state.total_supply == 0 || lp_fee_amount == 0 {
+        return Ok(());
+    }
+    
+    let growth = ((lp_fee_amount as u128) << FEE_GROWTH_FRACTIONAL_BITS)
+        .checked_div(pool_state.total_supply as u128)
+        .ok_or(ErrorCode::DivisionByZero)?;
+    
+    if is_a_to_b {
+        pool_state.fee_growth_global_a = pool_state.fee_growth_global_a.wrapping_add(growth);
+    } else {
+        pool_state.fee_growth_global_b = pool_state.fee_growth_global_b.wrapping_add(growth);
+    }
+    
+    Ok(())
+}
+
+/// Fees earned by `lp_shares` while fee growth moved from `checkpoint` to `fee_growth`.
+pub fn calculate_fees_earned(lp_shares: u64, fee_growth: u128, checkpoint: u128) -> u64 {
+    let delta = fee_growth.wrapping_sub(checkpoint);
+    let fractional_mask = (1u128 << FEE_GROWTH_FRACTIONAL_BITS) - 1;
+    
+    let whole = (lp_shares as u128).saturating_mul(delta >> FEE_GROWTH_FRACTIONAL_BITS);
+    let fractional = (lp_shares as u128)
+        .saturating_mul(delta & fractional_mask)
+        >> FEE_GROWTH_FRACTIONAL_BITS;
+    
+    u64::try_from(whole.saturating_add(fractional)).unwrap_or(u64::MAX)
+}
+
+/// Validates a reward schedule and returns the amount needed to fund it.
+pub fn farm_reward_funding(
+    reward_per_second: u64,
+    start_time: i64,
+    end_time: i64,
+    now: i64,
+) -> Result<u64> {
+    require!(reward_per_second > 0, ErrorCode::InvalidFarmSchedule);
+    require!(
+        start_time >= now && end_time > start_time,
+        ErrorCode::InvalidFarmSchedule
+    );
+    
+    let funding = (reward_per_second as u128)
+        .checked_mul((end_time - start_time) as u128)
+        .ok_or(ErrorCode::MathOverflow)?;
+    
+    u64::try_from(funding).map_err(|_| error!(ErrorCode::MathOverflow))
+}
+
+/// Appends a reward stream to `farm`.
+pub fn add_reward_to_farm(farm: &mut Farm, reward: FarmReward) -> Result<usize> {
+    let index = farm.reward_count as usize;
+    require!(index < farm.rewards.len(), ErrorCode::TooManyFarmRewards);
+    
+    farm.rewards[index] = reward;
+    farm.reward_count += 1;
+    
+    Ok(index)
+}
+
+/// Accrues every reward stream of `farm` up to `now`. Time with nothing staked
+/// emits nothing and is appended to the end of the stream instead.
+pub fn update_farm_rewards(farm: &mut Farm, now: i64) -> Result<()> {
+    let total_staked = farm.total_staked;
+    let reward_count = farm.reward_count as usize;
+    
+    for reward in farm.rewards[..reward_count].iter_mut() {
+        let from = reward.last_update_time.max(reward.start_time);
+        let until = now.min(reward.end_time);
+        
+        if total_staked == 0 && until > from {
+            reward.end_time = reward.end_time
+                .checked_add(until - from)
+                .ok_or(ErrorCode::MathOverflow)?;
+        } else if until > from {
+            let emitted = (reward.reward_per_second as u128)
+                .checked_mul((until - from) as u128)
+                .ok_or(ErrorCode::MathOverflow)?;
+            
+            let increase = emitted
+                .checked_mul(REWARD_PER_SHARE_PRECISION)
+                .ok_or(ErrorCode::MathOverflow)?
+                .checked_div(total_staked as u128)
+                .ok_or(ErrorCode::DivisionByZero)?;
+            
+            reward.reward_per_share = reward.reward_per_share
+                .checked_add(increase)
+                .ok_or(ErrorCode::MathOverflow)?;
+        }
+        
+        reward.last_update_time = reward.last_update_time.max(now);
+    }
+    
+    Ok(())
+}
+
+/// Moves rewards accrued by `stake` since its last settlement into
+/// `pending_rewards`. Call after `update_farm_rewards`.
+pub fn settle_farm_stake(farm: &Farm, stake: &mut FarmStake) -> Result<()> {
+    for index in 0..farm.reward_count as usize {
+        let accumulated = (stake.amount as u128)
+            .checked_mul(farm.rewards[index].reward_per_share)
+            .ok_or(ErrorCode::MathOverflow)?;
+        
+        let accrued = accumulated
+            .saturating_sub(stake.reward_debt[index])
+            .checked_div(REWARD_PER_SHARE_PRECISION)
+            .ok_or(ErrorCode::DivisionByZero)? as u64;
+        
+        stake.pending_rewards[index] = stake.pending_rewards[index]
+            .checked_add(accrued)
+            .ok_or(ErrorCode::MathOverflow)?;
+        stake.reward_debt[index] = accumulated;
+    }
+    
+    Ok(())
+}
+
+/// Resets the reward debt of `stake` after its amount changed.
+pub fn reset_farm_stake_debt(farm: &Farm, stake: &mut FarmStake) -> Result<()> {
+    for index in 0..farm.reward_count as usize {
+        stake.reward_debt[index] = (stake.amount as u128)
+            .checked_mul(farm.rewards[index].reward_per_share)
+            .ok_or(ErrorCode::MathOverflow)?;
+    }
+    
+    Ok(())
 }
\ No newline at end of file
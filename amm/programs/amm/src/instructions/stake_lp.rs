@@ -0,0 +1,112 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::state::{Farm, FarmStake};
+use crate::error::ErrorCode;
+use crate::instructions::shared::{
+    transfer_tokens, update_farm_rewards, settle_farm_stake, reset_farm_stake_debt,
+};
+
+pub fn stake_lp_handler(ctx: Context<StakeLp>, amount: u64) -> Result<()> {
+    require!(amount > 0, ErrorCode::InvalidAmount);
+    require!(
+        ctx.accounts.user_lp_token.amount >= amount,
+        ErrorCode::InsufficientBalance
+    );
+    
+    let clock = Clock::get()?;
+    let farm = &mut ctx.accounts.farm;
+    let stake = &mut ctx.accounts.stake;
+    
+    if stake.owner == Pubkey::default() {
+        stake.farm = farm.key();
+        stake.owner = ctx.accounts.owner.key();
+        stake.bump = ctx.bumps.stake;
+    }
+    
+    update_farm_rewards(farm, clock.unix_timestamp)?;
+    settle_farm_stake(farm, stake)?;
+    
+    transfer_tokens(
+        &ctx.accounts.user_lp_token,
+        &ctx.accounts.stake_vault,
+        &ctx.accounts.pool_mint,
+        &ctx.accounts.owner,
+        &ctx.accounts.token_program,
+        amount,
+    )?;
+    
+    stake.amount = stake.amount
+        .checked_add(amount)
+        .ok_or(ErrorCode::MathOverflow)?;
+    
+    farm.total_staked = farm.total_staked
+        .checked_add(amount)
+        .ok_or(ErrorCode::MathOverflow)?;
+    
+    reset_farm_stake_debt(farm, stake)?;
+    
+    emit!(LpStaked {
+        farm: farm.key(),
+        owner: stake.owner,
+        amount,
+        staked: stake.amount,
+        total_staked: farm.total_staked,
+        timestamp: clock.unix_timestamp,
+    });
+    
+    msg!("LP staked: {} (total staked: {})", amount, farm.total_staked);
+    
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct StakeLp<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"farm", farm.pool_mint.as_ref()],
+        bump = farm.bump,
+    )]
+    pub farm: Box<Account<'info, Farm>>,  // ✅ BOX
+    
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = 8 + FarmStake::INIT_SPACE,
+        seeds = [b"farm_stake", farm.key().as_ref(), owner.key().as_ref()],
+        bump,
+    )]
+    pub stake: Box<Account<'info, FarmStake>>,  // ✅ BOX
+    
+    #[account(address = farm.pool_mint)]
+    pub pool_mint: Box<InterfaceAccount<'info, Mint>>,  // ✅ BOX
+    
+    #[account(
+        mut,
+        address = farm.stake_vault,
+    )]
+    pub stake_vault: Box<InterfaceAccount<'info, TokenAccount>>,  // ✅ BOX
+    
+    #[account(
+        mut,
+        token::mint = pool_mint,
+        token::authority = owner,
+    )]
+    pub user_lp_token: Box<InterfaceAccount<'info, TokenAccount>>,  // ✅ BOX
+    
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[event]
+pub struct LpStaked {
+    pub farm: Pubkey,
+    pub owner: Pubkey,
+    pub amount: u64,
+    pub staked: u64,
+    pub total_staked: u64,
+    pub timestamp: i64,
+}
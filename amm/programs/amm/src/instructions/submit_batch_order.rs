@@ -0,0 +1,189 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::state::{Batch, BatchOrder, PoolState, MAX_BATCH_ORDERS};
+use crate::error::ErrorCode;
+use crate::instructions::shared::transfer_tokens;
+
+/// Escrows `amount_in` into the pool's open batch. The first order of a batch
+/// opens its window of `batch_auction_slots`.
+pub fn submit_batch_order_handler(
+    ctx: Context<SubmitBatchOrder>,
+    amount_in: u64,
+    min_amount_out: u64,
+    is_a_to_b: bool,
+) -> Result<()> {
+    require!(amount_in > 0, ErrorCode::InvalidAmount);
+    require!(min_amount_out > 0, ErrorCode::InvalidAmount);
+    
+    let pool_state = &ctx.accounts.pool_state;
+    require!(
+        pool_state.batch_auction_slots > 0,
+        ErrorCode::BatchAuctionDisabled
+    );
+    
+    let clock = Clock::get()?;
+    let batch = &mut ctx.accounts.batch;
+    
+    if batch.pool == Pubkey::default() {
+        batch.pool = pool_state.key();
+        batch.batch_id = pool_state.current_batch_id;
+        batch.creator = ctx.accounts.user.key();
+        batch.start_slot = clock.slot;
+        batch.end_slot = clock.slot
+            .checked_add(pool_state.batch_auction_slots)
+            .ok_or(ErrorCode::MathOverflow)?;
+        batch.bump = ctx.bumps.batch;
+        batch.escrow_a_bump = ctx.bumps.batch_escrow_a;
+        batch.escrow_b_bump = ctx.bumps.batch_escrow_b;
+    }
+    
+    require!(clock.slot <= batch.end_slot, ErrorCode::BatchClosed);
+    require!(
+        (batch.order_count as usize) < MAX_BATCH_ORDERS,
+        ErrorCode::BatchClosed
+    );
+    
+    let (user_in, user_out, escrow_in, mint_in) = if is_a_to_b {
+        (
+            &ctx.accounts.user_token_a,
+            &ctx.accounts.user_token_b,
+            &ctx.accounts.batch_escrow_a,
+            &ctx.accounts.token_mint_a,
+        )
+    } else {
+        (
+            &ctx.accounts.user_token_b,
+            &ctx.accounts.user_token_a,
+            &ctx.accounts.batch_escrow_b,
+            &ctx.accounts.token_mint_b,
+        )
+    };
+    
+    require!(
+        user_in.amount >= amount_in,
+        ErrorCode::InsufficientBalance
+    );
+    
+    transfer_tokens(
+        user_in,
+        escrow_in,
+        mint_in,
+        &ctx.accounts.user,
+        &ctx.accounts.token_program,
+        amount_in,
+    )?;
+    
+    let order_index = batch.order_count;
+    batch.orders[order_index as usize] = BatchOrder {
+        owner: ctx.accounts.user.key(),
+        token_in_account: user_in.key(),
+        token_out_account: user_out.key(),
+        amount_in,
+        min_amount_out,
+        is_a_to_b,
+        filled: false,
+        claimable_amount: 0,
+    };
+    batch.order_count += 1;
+    
+    emit!(BatchOrderSubmitted {
+        pool: batch.pool,
+        batch_id: batch.batch_id,
+        order_index,
+        owner: ctx.accounts.user.key(),
+        amount_in,
+        min_amount_out,
+        is_a_to_b,
+        end_slot: batch.end_slot,
+        timestamp: clock.unix_timestamp,
+    });
+    
+    msg!(
+        "Batch order {} submitted to batch {} (settles after slot {})",
+        order_index,
+        batch.batch_id,
+        batch.end_slot
+    );
+    
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SubmitBatchOrder<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+    
+    #[account(
+        seeds = [b"pool_state", pool_state.token_mint_a.as_ref(), pool_state.token_mint_b.as_ref()],
+        bump = pool_state.bump,
+    )]
+    pub pool_state: Box<Account<'info, PoolState>>,  // ✅ BOX
+    
+    #[account(address = pool_state.token_mint_a)]
+    pub token_mint_a: Box<InterfaceAccount<'info, Mint>>,  // ✅ BOX
+    
+    #[account(address = pool_state.token_mint_b)]
+    pub token_mint_b: Box<InterfaceAccount<'info, Mint>>,  // ✅ BOX
+    
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + Batch::INIT_SPACE,
+        seeds = [b"batch", pool_state.key().as_ref(), &pool_state.current_batch_id.to_le_bytes()],
+        bump,
+    )]
+    pub batch: Box<Account<'info, Batch>>,  // ✅ BOX
+    
+    #[account(
+        init_if_needed,
+        payer = user,
+        seeds = [b"batch_escrow_a", batch.key().as_ref()],
+        bump,
+        token::mint = token_mint_a,
+        token::authority = batch,
+        token::token_program = token_program,
+    )]
+    pub batch_escrow_a: Box<InterfaceAccount<'info, TokenAccount>>,  // ✅ BOX
+    
+    #[account(
+        init_if_needed,
+        payer = user,
+        seeds = [b"batch_escrow_b", batch.key().as_ref()],
+        bump,
+        token::mint = token_mint_b,
+        token::authority = batch,
+        token::token_program = token_program,
+    )]
+    pub batch_escrow_b: Box<InterfaceAccount<'info, TokenAccount>>,  // ✅ BOX
+    
+    #[account(
+        mut,
+        token::mint = token_mint_a,
+        token::authority = user,
+    )]
+    pub user_token_a: Box<InterfaceAccount<'info, TokenAccount>>,  // ✅ BOX
+    
+    #[account(
+        mut,
+        token::mint = token_mint_b,
+        token::authority = user,
+    )]
+    pub user_token_b: Box<InterfaceAccount<'info, TokenAccount>>,  // ✅ BOX
+    
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[event]
+pub struct BatchOrderSubmitted {
+    pub pool: Pubkey,
+    pub batch_id: u64,
+    pub order_index: u8,
+    pub owner: Pubkey,
+    pub amount_in: u64,
+    pub min_amount_out: u64,
+    pub is_a_to_b: bool,
+    pub end_slot: u64,
+    pub timestamp: i64,
+}
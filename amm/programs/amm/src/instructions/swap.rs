@@ -1,48 +1,65 @@
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::sysvar;
 use anchor_spl::{
     token_interface::{Mint, TokenAccount, TokenInterface},
 };
 
-use crate::state::PoolState;
+use crate::state::{AmmConfig, FeeDiscount, PoolState, TraderStats};
 use crate::error::ErrorCode;
-use crate::instructions::shared::{transfer_tokens, transfer_tokens_signed, calculate_swap_output};
-
-const MINIMUM_OUTPUT: u64 = 1;
-const MAX_PRICE_IMPACT_BPS: u64 = 1000; // 10%
+use crate::instructions::shared::{
+    transfer_tokens, transfer_tokens_signed, compute_swap, spot_price, check_deadline,
+    accrue_fee_growth, update_volatility, holder_discount_bps, volume_discount_bps, rolling_volume,
+    record_trader_volume, refresh_slot_reference, slot_price_move_bps, check_sandwich, update_price_oracle,
+    SwapComputation,
+    BPS_DENOMINATOR, SECONDS_PER_DAY,
+};
 
 pub fn swap_handler(
     ctx: Context<Swap>,
     amount_in: u64,
     min_amount_out: u64,
     is_a_to_b: bool, 
-) -> Result<()> {
-    require!(amount_in > 0, ErrorCode::InvalidAmount);
+    deadline: Option<i64>,
+) -> Result<SwapResult> {
+    process_swap(ctx.accounts, amount_in, min_amount_out, is_a_to_b, deadline, None)
+}
+
+/// Executes a swap against the pool. When `referrer` is set, `referral_fee_bps`
+/// of the fee is paid to it in the input token instead of staying in the pool.
+pub fn process_swap<'info>(
+    accounts: &mut Swap<'info>,
+    amount_in: u64,
+    min_amount_out: u64,
+    is_a_to_b: bool,
+    deadline: Option<i64>,
+    referrer: Option<&InterfaceAccount<'info, TokenAccount>>,
+) -> Result<SwapResult> {
+    check_deadline(deadline)?;
     require!(min_amount_out > 0, ErrorCode::InvalidAmount);
     
-    let pool_state = &mut ctx.accounts.pool_state;
+    let clock = Clock::get()?;
+    let day = clock.unix_timestamp.div_euclid(SECONDS_PER_DAY);
+    let fee_discount_bps = accounts.fee_discount_bps(day)?;
+    let pool_key = accounts.pool_state.key();
     
-    let (user_in, user_out, vault_in, vault_out, mint_in, mint_out, reserve_in, reserve_out) = 
+    let (user_in, user_out, vault_in, vault_out, mint_in, mint_out) = 
         if is_a_to_b {
             (
-                &ctx.accounts.user_token_a,
-                &ctx.accounts.user_token_b,
-                &ctx.accounts.vault_a,
-                &ctx.accounts.vault_b,
-                &ctx.accounts.token_mint_a,
-                &ctx.accounts.token_mint_b,
-                pool_state.reserve_a,
-                pool_state.reserve_b,
+                &accounts.user_token_a,
+                &accounts.user_token_b,
+                &accounts.vault_a,
+                &accounts.vault_b,
+                &accounts.token_mint_a,
+                &accounts.token_mint_b,
             )
         } else {
             (
-                &ctx.accounts.user_token_b,
-                &ctx.accounts.user_token_a,
-                &ctx.accounts.vault_b,
-                &ctx.accounts.vault_a,
-                &ctx.accounts.token_mint_b,
-                &ctx.accounts.token_mint_a,
-                pool_state.reserve_b,
-                pool_state.reserve_a,
+                &accounts.user_token_b,
+                &accounts.user_token_a,
+                &accounts.vault_b,
+                &accounts.vault_a,
+                &accounts.token_mint_b,
+                &accounts.token_mint_a,
             )
         };
     
@@ -51,35 +68,31 @@ pub fn swap_handler(
         ErrorCode::InsufficientBalance
     );
     
-    require!(
-        reserve_in > 0 && reserve_out > 0, 
-        ErrorCode::EmptyReserves
-    );
-    
-    let k_before = (reserve_in as u128)
-        .checked_mul(reserve_out as u128)
-        .ok_or(ErrorCode::MathOverflow)?;
-    
-    let price_before = (reserve_out as u128)
-        .checked_mul(1_000_000)
-        .ok_or(ErrorCode::MathOverflow)?
-        .checked_div(reserve_in as u128)
-        .ok_or(ErrorCode::DivisionByZero)? as u64;
-    
-    let fee_amount = (amount_in as u128)
-        .checked_mul(pool_state.fee_numerator as u128)
-        .ok_or(ErrorCode::MathOverflow)?
-        .checked_div(pool_state.fee_denominator as u128)
-        .ok_or(ErrorCode::DivisionByZero)? as u64;
-    
-    let amount_in_after_fee = amount_in
-        .checked_sub(fee_amount)
-        .ok_or(ErrorCode::MathOverflow)?;
+    if let Some(referrer) = referrer {
+        require_keys_eq!(referrer.mint, mint_in.key(), ErrorCode::InvalidReferrer);
+        require_keys_neq!(referrer.owner, accounts.user.key(), ErrorCode::InvalidReferrer);
+    }
     
-    let amount_out = calculate_swap_output(
-        amount_in_after_fee,
-        reserve_in,
-        reserve_out,
+    // Reserves are updated and checked before any tokens move, so a failing swap
+    // leaves no transfers behind for `batch_swap` to skip over.
+    let AppliedSwap {
+        fee_amount,
+        amount_out,
+        price_before,
+        price_after,
+        price_impact_bps,
+        fee_numerator,
+        referral_amount,
+        amount_to_pool,
+    } = apply_swap(
+        &mut accounts.pool_state,
+        &pool_key,
+        amount_in,
+        is_a_to_b,
+        fee_discount_bps,
+        referrer.is_some(),
+        accounts.instructions_sysvar.as_deref(),
+        &clock,
     )?;
     
     require!(
@@ -87,37 +100,40 @@ pub fn swap_handler(
         ErrorCode::SlippageExceeded
     );
     
-    require!(
-        amount_out >= MINIMUM_OUTPUT,
-        ErrorCode::OutputTooSmall
-    );
-    
-    let price_impact_bps = (amount_out as u128)
-        .checked_mul(10000)
-        .ok_or(ErrorCode::MathOverflow)?
-        .checked_div(reserve_out as u128)
-        .ok_or(ErrorCode::DivisionByZero)? as u64;
-    
-    require!(
-        price_impact_bps <= MAX_PRICE_IMPACT_BPS,
-        ErrorCode::PriceImpactTooHigh
-    );
-    
     require!(
         vault_out.amount >= amount_out,
         ErrorCode::InsufficientPoolLiquidity
     );
     
+    let pool_state = &accounts.pool_state;
+    
+    if let Some(trader_stats) = accounts.trader_stats.as_mut() {
+        let volume_a = if is_a_to_b { amount_in } else { amount_out };
+        record_trader_volume(trader_stats, day, volume_a)?;
+    }
+    
     transfer_tokens(
         user_in,
         vault_in,
         mint_in,
-        &ctx.accounts.user,
-        &ctx.accounts.token_program,
-        amount_in,
+        &accounts.user,
+        &accounts.token_program,
+        amount_to_pool,
     )?;
     
-    let pool_key = pool_state.key();
+    if let Some(referrer) = referrer {
+        if referral_amount > 0 {
+            transfer_tokens(
+                user_in,
+                referrer,
+                mint_in,
+                &accounts.user,
+                &accounts.token_program,
+                referral_amount,
+            )?;
+        }
+    }
+    
     let authority_bump = pool_state.authority_bump;
     let authority_seeds = &[
         b"authority",
@@ -130,15 +146,113 @@ pub fn swap_handler(
         vault_out,
         user_out,
         mint_out,
-        &ctx.accounts.pool_authority,
-        &ctx.accounts.token_program,
+        &accounts.pool_authority,
+        &accounts.token_program,
         amount_out,
         signer_seeds,
     )?;
     
+    emit!(SwapExecuted {
+        pool: pool_key,
+        user: accounts.user.key(),
+        token_in: if is_a_to_b { pool_state.token_mint_a } else { pool_state.token_mint_b },
+        token_out: if is_a_to_b { pool_state.token_mint_b } else { pool_state.token_mint_a },
+        amount_in,
+        amount_out,
+        fee_amount,
+        fee_numerator,
+        fee_denominator: pool_state.fee_denominator,
+        fee_discount_bps,
+        referrer: referrer.map(|referrer| referrer.owner),
+        referral_amount,
+        reserve_a: pool_state.reserve_a,
+        reserve_b: pool_state.reserve_b,
+        price_before,
+        price_after,
+        price_impact: price_impact_bps,
+        timestamp: clock.unix_timestamp,
+    });
+    
+    msg!(
+        "Swap: {}→{} (fee: {}, impact: {}bps)",
+        amount_in, amount_out, fee_amount, price_impact_bps
+    );
+    
+    Ok(SwapResult {
+        amount_in,
+        amount_out,
+        fee_amount,
+        referral_amount,
+    })
+}
+
+/// Checks a swap against `pool_state` and applies it there: reserves, fee growth,
+/// price oracle, slot reference and volatility. No tokens move. `swap` runs it on
+/// the pool and `quote_swap` on a copy, so both fail under the same conditions.
+#[allow(clippy::too_many_arguments)]
+pub fn apply_swap(
+    pool_state: &mut PoolState,
+    pool: &Pubkey,
+    amount_in: u64,
+    is_a_to_b: bool,
+    fee_discount_bps: u64,
+    with_referral: bool,
+    instructions_sysvar: Option<&AccountInfo>,
+    clock: &Clock,
+) -> Result<AppliedSwap> {
+    require!(
+        pool_state.batch_auction_slots == 0,
+        ErrorCode::BatchAuctionOnly
+    );
+    require!(amount_in > 0, ErrorCode::InvalidAmount);
+    
+    if pool_state.sandwich_protection {
+        let instructions_sysvar = instructions_sysvar.ok_or(ErrorCode::MissingInstructionsSysvar)?;
+        check_sandwich(pool_state, pool, instructions_sysvar, is_a_to_b)?;
+    }
+    
+    let SwapComputation {
+        fee_amount,
+        amount_out,
+        price_before,
+        price_impact_bps,
+        fee_numerator,
+        ..
+    } = compute_swap(
+        pool_state,
+        amount_in,
+        is_a_to_b,
+        clock.unix_timestamp,
+        fee_discount_bps,
+    )?;
+    
+    if pool_state.max_slot_price_move_bps > 0 {
+        refresh_slot_reference(pool_state, clock.slot)?;
+    }
+    
+    update_price_oracle(pool_state, clock.unix_timestamp)?;
+    
+    let k_before = (pool_state.reserve_a as u128)
+        .checked_mul(pool_state.reserve_b as u128)
+        .ok_or(ErrorCode::MathOverflow)?;
+    
+    let referral_amount = if with_referral {
+        (fee_amount as u128)
+            .checked_mul(pool_state.referral_fee_bps as u128)
+            .ok_or(ErrorCode::MathOverflow)?
+            .checked_div(BPS_DENOMINATOR as u128)
+            .ok_or(ErrorCode::DivisionByZero)? as u64
+    } else {
+        0
+    };
+    
+    let amount_to_pool = amount_in
+        .checked_sub(referral_amount)
+        .ok_or(ErrorCode::MathOverflow)?;
+    
     if is_a_to_b {
         pool_state.reserve_a = pool_state.reserve_a
-            .checked_add(amount_in)
+            .checked_add(amount_to_pool)
             .ok_or(ErrorCode::MathOverflow)?;
         
         pool_state.reserve_b = pool_state.reserve_b
@@ -146,7 +260,7 @@ pub fn swap_handler(
             .ok_or(ErrorCode::MathOverflow)?;
     } else {
         pool_state.reserve_b = pool_state.reserve_b
-            .checked_add(amount_in)
+            .checked_add(amount_to_pool)
             .ok_or(ErrorCode::MathOverflow)?;
         
         pool_state.reserve_a = pool_state.reserve_a
@@ -154,6 +268,11 @@ pub fn swap_handler(
             .ok_or(ErrorCode::MathOverflow)?;
     }
     
+    let lp_fee_amount = fee_amount
+        .checked_sub(referral_amount)
+        .ok_or(ErrorCode::MathOverflow)?;
+    accrue_fee_growth(pool_state, lp_fee_amount, is_a_to_b)?;
+    
     let k_after = (pool_state.reserve_a as u128)
         .checked_mul(pool_state.reserve_b as u128)
         .ok_or(ErrorCode::MathOverflow)?;
@@ -163,41 +282,87 @@ pub fn swap_handler(
         ErrorCode::InvalidConstantProduct
     );
     
+    if pool_state.max_slot_price_move_bps > 0 {
+        let price_move_bps = slot_price_move_bps(pool_state, pool_state.reserve_a, pool_state.reserve_b)?;
+        
+        if price_move_bps > pool_state.max_slot_price_move_bps {
+            // Only visible in the logs of the failed transaction; indexers reading
+            // successful transactions alone should watch for `CircuitBreakerTripped`.
+            emit!(CircuitBreakerTriggered {
+                pool: *pool,
+                slot: clock.slot,
+                reference_price: pool_state.slot_reference_price,
+                price_move_bps,
+                max_slot_price_move_bps: pool_state.max_slot_price_move_bps,
+                timestamp: clock.unix_timestamp,
+            });
+            
+            return err!(ErrorCode::CircuitBreakerTripped);
+        }
+    }
+    
     let (reserve_in_after, reserve_out_after) = if is_a_to_b {
         (pool_state.reserve_a, pool_state.reserve_b)
     } else {
         (pool_state.reserve_b, pool_state.reserve_a)
     };
     
-    let price_after = (reserve_out_after as u128)
-        .checked_mul(1_000_000)
-        .ok_or(ErrorCode::MathOverflow)?
-        .checked_div(reserve_in_after as u128)
-        .ok_or(ErrorCode::DivisionByZero)? as u64;
+    let price_after = spot_price(reserve_in_after, reserve_out_after)?;
+    update_volatility(pool_state, price_before, price_after, clock.unix_timestamp)?;
     
-    let clock = Clock::get()?;
-    emit!(SwapExecuted {
-        pool: pool_state.key(),
-        user: ctx.accounts.user.key(),
-        token_in: if is_a_to_b { pool_state.token_mint_a } else { pool_state.token_mint_b },
-        token_out: if is_a_to_b { pool_state.token_mint_b } else { pool_state.token_mint_a },
-        amount_in,
-        amount_out,
+    Ok(AppliedSwap {
         fee_amount,
-        reserve_a: pool_state.reserve_a,
-        reserve_b: pool_state.reserve_b,
+        amount_out,
         price_before,
         price_after,
-        price_impact: price_impact_bps,
-        timestamp: clock.unix_timestamp,
-    });
+        price_impact_bps,
+        fee_numerator,
+        referral_amount,
+        amount_to_pool,
+    })
+}
+
+/// Swaps input a keeper (`accounts.user`) has just received from an order escrow
+/// and forwards the output to the order's `owner_token_out`. Trader stats are
+/// not recorded and no fee discount applies.
+pub fn process_keeper_swap<'info>(
+    accounts: &mut Swap<'info>,
+    amount_in: u64,
+    min_amount_out: u64,
+    is_a_to_b: bool,
+    owner_token_out: &InterfaceAccount<'info, TokenAccount>,
+) -> Result<SwapResult> {
+    if is_a_to_b {
+        accounts.user_token_a.reload()?;
+    } else {
+        accounts.user_token_b.reload()?;
+    }
     
-    msg!(
-        "Swap: {}→{} (fee: {}, impact: {}bps)",
-        amount_in, amount_out, fee_amount, price_impact_bps
-    );
+    // The trade is the order owner's, so it neither counts towards the keeper's
+    // stats nor earns any of the keeper's fee discounts.
+    accounts.fee_discount = None;
+    accounts.config = None;
+    accounts.discount_token_account = None;
+    accounts.trader_stats = None;
+    
+    let result = process_swap(accounts, amount_in, min_amount_out, is_a_to_b, None, None)?;
+    
+    let (keeper_out, mint_out) = if is_a_to_b {
+        (&accounts.user_token_b, &accounts.token_mint_b)
+    } else {
+        (&accounts.user_token_a, &accounts.token_mint_a)
+    };
+    
+    transfer_tokens(
+        keeper_out,
+        owner_token_out,
+        mint_out,
+        &accounts.user,
+        &accounts.token_program,
+        result.amount_out,
+    )?;
     
-    Ok(())
+    Ok(result)
 }
 
 #[derive(Accounts)]
@@ -253,6 +418,81 @@ pub struct Swap<'info> {
     pub user_token_b: Box<InterfaceAccount<'info, TokenAccount>>,  // ✅ BOX
     
     pub token_program: Interface<'info, TokenInterface>,
+    
+    /// Optional fee discount granted to `user`.
+    #[account(
+        seeds = [b"fee_discount", user.key().as_ref()],
+        bump = fee_discount.bump,
+        constraint = fee_discount.trader == user.key() @ ErrorCode::InvalidFeeDiscount,
+    )]
+    pub fee_discount: Option<Box<Account<'info, FeeDiscount>>>,  // ✅ BOX
+    
+    /// Program config, required with `discount_token_account`.
+    #[account(
+        seeds = [b"amm_config"],
+        bump = config.bump,
+    )]
+    pub config: Option<Box<Account<'info, AmmConfig>>>,  // ✅ BOX
+    
+    /// Optional balance of the config's discount mint held by `user`.
+    #[account(token::authority = user)]
+    pub discount_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,  // ✅ BOX
+    
+    /// Optional swap volume of `user` in this pool.
+    #[account(
+        mut,
+        seeds = [b"trader_stats", pool_state.key().as_ref(), user.key().as_ref()],
+        bump = trader_stats.bump,
+    )]
+    pub trader_stats: Option<Box<Account<'info, TraderStats>>>,  // ✅ BOX
+    
+    /// CHECK: instructions sysvar, required when the pool has sandwich protection on
+    #[account(address = sysvar::instructions::ID)]
+    pub instructions_sysvar: Option<UncheckedAccount<'info>>,
+}
+
+impl<'info> Swap<'info> {
+    /// Best fee discount available to `user` on `day` from the optional discount accounts.
+    pub fn fee_discount_bps(&self, day: i64) -> Result<u64> {
+        fee_discount_bps(
+            &self.pool_state,
+            self.fee_discount.as_deref().map(|fee_discount| &**fee_discount),
+            self.config.as_deref().map(|config| &**config),
+            self.discount_token_account.as_deref().map(|account| &**account),
+            self.trader_stats.as_deref().map(|trader_stats| &**trader_stats),
+            day,
+        )
+    }
+}
+
+/// Best fee discount on `day` from a trader's discount accounts, whichever are given.
+pub fn fee_discount_bps(
+    pool_state: &PoolState,
+    fee_discount: Option<&FeeDiscount>,
+    config: Option<&AmmConfig>,
+    discount_token_account: Option<&TokenAccount>,
+    trader_stats: Option<&TraderStats>,
+    day: i64,
+) -> Result<u64> {
+    let mut discount_bps = fee_discount.map_or(0, |fee_discount| fee_discount.discount_bps);
+    
+    if let Some(discount_token_account) = discount_token_account {
+        let config = config.ok_or(ErrorCode::InvalidFeeDiscount)?;
+        require_keys_eq!(
+            discount_token_account.mint,
+            config.discount_mint,
+            ErrorCode::InvalidFeeDiscount
+        );
+        
+        discount_bps = discount_bps.max(holder_discount_bps(config, discount_token_account.amount));
+    }
+    
+    if let Some(trader_stats) = trader_stats {
+        let volume = rolling_volume(trader_stats, day);
+        discount_bps = discount_bps.max(volume_discount_bps(pool_state, volume));
+    }
+    
+    Ok(discount_bps)
 }
 
 #[event]
@@ -264,10 +504,52 @@ pub struct SwapExecuted {
     pub amount_in: u64,
     pub amount_out: u64,
     pub fee_amount: u64,
+    /// Base fee rate of this swap, including any dynamic fee. Size tiers may add to
+    /// `fee_amount` on top of it.
+    pub fee_numerator: u64,
+    pub fee_denominator: u64,
+    pub fee_discount_bps: u64,
+    pub referrer: Option<Pubkey>,
+    pub referral_amount: u64,
     pub reserve_a: u64,
     pub reserve_b: u64,
     pub price_before: u64,
     pub price_after: u64,
     pub price_impact: u64,
     pub timestamp: i64,
+}
+
+/// Emitted when the slot circuit breaker trips. A swap that trips it fails, so the
+/// event is only in that failed transaction's logs. `settle_batch` refunds the
+/// batch instead and emits it from a successful transaction.
+#[event]
+pub struct CircuitBreakerTriggered {
+    pub pool: Pubkey,
+    pub slot: u64,
+    pub reference_price: u64,
+    pub price_move_bps: u64,
+    pub max_slot_price_move_bps: u64,
+    pub timestamp: i64,
+}
+
+/// Executed amounts, returned to the caller through return data.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct SwapResult {
+    pub amount_in: u64,
+    pub amount_out: u64,
+    pub fee_amount: u64,
+    pub referral_amount: u64,
+}
+
+/// Outcome of `apply_swap`.
+pub struct AppliedSwap {
+    pub fee_amount: u64,
+    pub amount_out: u64,
+    pub price_before: u64,
+    pub price_after: u64,
+    pub price_impact_bps: u64,
+    pub fee_numerator: u64,
+    pub referral_amount: u64,
+    /// `amount_in` less the referral payout.
+    pub amount_to_pool: u64,
 }
\ No newline at end of file
@@ -0,0 +1,192 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::TokenAccount;
+
+use crate::state::{RfqQuote, RfqQuoteFill};
+use crate::error::ErrorCode;
+use crate::instructions::swap::*;
+use crate::instructions::shared::{transfer_tokens, transfer_tokens_signed, verify_ed25519_signature};
+
+/// Fills up to `quote.amount_in` of the swap against an allowlisted market maker at
+/// the quoted price and routes the rest through the pool. `min_amount_out` bounds
+/// the combined output. The maker signs the Borsh-encoded quote with an ed25519
+/// program instruction placed right before this one, and approves the
+/// `rfq_authority` PDA as delegate of its output token account.
+pub fn swap_rfq_handler(
+    ctx: Context<SwapRfq>,
+    amount_in: u64,
+    min_amount_out: u64,
+    is_a_to_b: bool,
+    quote: RfqQuote,
+) -> Result<SwapResult> {
+    require!(amount_in > 0, ErrorCode::InvalidAmount);
+    require!(min_amount_out > 0, ErrorCode::InvalidAmount);
+    
+    let clock = Clock::get()?;
+    let pool_state = &ctx.accounts.swap.pool_state;
+    
+    require!(quote.expiry >= clock.unix_timestamp, ErrorCode::QuoteExpired);
+    require!(
+        quote.pool == pool_state.key()
+            && quote.taker == ctx.accounts.swap.user.key()
+            && quote.is_a_to_b == is_a_to_b
+            && quote.amount_in > 0,
+        ErrorCode::InvalidQuote
+    );
+    
+    let rfq_makers = &pool_state.rfq_makers[..pool_state.rfq_maker_count as usize];
+    require!(rfq_makers.contains(&quote.maker), ErrorCode::RfqMakerNotAllowed);
+    
+    let instructions_sysvar = ctx.accounts.swap.instructions_sysvar
+        .as_ref()
+        .ok_or(ErrorCode::MissingInstructionsSysvar)?;
+    let mut message = Vec::new();
+    quote.serialize(&mut message).map_err(|_| ErrorCode::InvalidQuote)?;
+    verify_ed25519_signature(instructions_sysvar, &quote.maker, &message)?;
+    
+    let rfq_amount_in = amount_in.min(quote.amount_in);
+    let rfq_amount_out = (quote.amount_out as u128)
+        .checked_mul(rfq_amount_in as u128)
+        .ok_or(ErrorCode::MathOverflow)?
+        .checked_div(quote.amount_in as u128)
+        .ok_or(ErrorCode::DivisionByZero)? as u64;
+    let pool_amount_in = amount_in
+        .checked_sub(rfq_amount_in)
+        .ok_or(ErrorCode::MathOverflow)?;
+    
+    let quote_fill = &mut ctx.accounts.quote_fill;
+    quote_fill.maker = quote.maker;
+    quote_fill.nonce = quote.nonce;
+    quote_fill.bump = ctx.bumps.quote_fill;
+    quote_fill.taker = quote.taker;
+    quote_fill.expiry = quote.expiry;
+    
+    let swap = &mut ctx.accounts.swap;
+    let (user_in, user_out, mint_in, mint_out) = if is_a_to_b {
+        (&swap.user_token_a, &swap.user_token_b, &swap.token_mint_a, &swap.token_mint_b)
+    } else {
+        (&swap.user_token_b, &swap.user_token_a, &swap.token_mint_b, &swap.token_mint_a)
+    };
+    
+    require_keys_eq!(ctx.accounts.maker_token_in.mint, mint_in.key(), ErrorCode::InvalidTokenMint);
+    require_keys_eq!(ctx.accounts.maker_token_out.mint, mint_out.key(), ErrorCode::InvalidTokenMint);
+    require!(user_in.amount >= amount_in, ErrorCode::InsufficientBalance);
+    
+    transfer_tokens(
+        user_in,
+        &ctx.accounts.maker_token_in,
+        mint_in,
+        &swap.user,
+        &swap.token_program,
+        rfq_amount_in,
+    )?;
+    
+    if rfq_amount_out > 0 {
+        let rfq_authority_seeds = &[b"rfq_authority".as_ref(), &[ctx.bumps.rfq_authority]];
+        
+        transfer_tokens_signed(
+            &ctx.accounts.maker_token_out,
+            user_out,
+            mint_out,
+            &ctx.accounts.rfq_authority,
+            &swap.token_program,
+            rfq_amount_out,
+            &[&rfq_authority_seeds[..]],
+        )?;
+    }
+    
+    let (pool_amount_out, fee_amount) = if pool_amount_in > 0 {
+        if is_a_to_b {
+            swap.user_token_a.reload()?;
+        } else {
+            swap.user_token_b.reload()?;
+        }
+        
+        let result = process_swap(swap, pool_amount_in, 1, is_a_to_b, None, None)?;
+        (result.amount_out, result.fee_amount)
+    } else {
+        (0, 0)
+    };
+    
+    let amount_out = rfq_amount_out
+        .checked_add(pool_amount_out)
+        .ok_or(ErrorCode::MathOverflow)?;
+    
+    require!(
+        amount_out >= min_amount_out,
+        ErrorCode::SlippageExceeded
+    );
+    
+    emit!(RfqSwapExecuted {
+        pool: swap.pool_state.key(),
+        user: swap.user.key(),
+        maker: quote.maker,
+        nonce: quote.nonce,
+        is_a_to_b,
+        rfq_amount_in,
+        rfq_amount_out,
+        pool_amount_in,
+        pool_amount_out,
+        timestamp: clock.unix_timestamp,
+    });
+    
+    msg!(
+        "RFQ swap: {}→{} from maker, {}→{} from pool",
+        rfq_amount_in, rfq_amount_out, pool_amount_in, pool_amount_out
+    );
+    
+    Ok(SwapResult {
+        amount_in,
+        amount_out,
+        fee_amount,
+        referral_amount: 0,
+    })
+}
+
+#[derive(Accounts)]
+#[instruction(amount_in: u64, min_amount_out: u64, is_a_to_b: bool, quote: RfqQuote)]
+pub struct SwapRfq<'info> {
+    /// Swap accounts with the quote's taker as `user`. `instructions_sysvar` is required.
+    pub swap: Swap<'info>,
+    
+    /// CHECK: quote signer, verified through the ed25519 instruction
+    #[account(address = quote.maker)]
+    pub maker: UncheckedAccount<'info>,
+    
+    #[account(mut, token::authority = maker)]
+    pub maker_token_in: Box<InterfaceAccount<'info, TokenAccount>>,  // ✅ BOX
+    
+    #[account(mut, token::authority = maker)]
+    pub maker_token_out: Box<InterfaceAccount<'info, TokenAccount>>,  // ✅ BOX
+    
+    /// CHECK: PDA the maker approves as delegate of `maker_token_out`
+    #[account(
+        seeds = [b"rfq_authority"],
+        bump,
+    )]
+    pub rfq_authority: AccountInfo<'info>,
+    
+    #[account(
+        init,
+        payer = swap.user,
+        space = 8 + RfqQuoteFill::INIT_SPACE,
+        seeds = [b"rfq_quote", quote.maker.as_ref(), &quote.nonce.to_le_bytes()],
+        bump,
+    )]
+    pub quote_fill: Box<Account<'info, RfqQuoteFill>>,  // ✅ BOX
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[event]
+pub struct RfqSwapExecuted {
+    pub pool: Pubkey,
+    pub user: Pubkey,
+    pub maker: Pubkey,
+    pub nonce: u64,
+    pub is_a_to_b: bool,
+    pub rfq_amount_in: u64,
+    pub rfq_amount_out: u64,
+    pub pool_amount_in: u64,
+    pub pool_amount_out: u64,
+    pub timestamp: i64,
+}
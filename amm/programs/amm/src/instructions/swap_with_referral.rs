@@ -0,0 +1,32 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::TokenAccount;
+
+use crate::instructions::swap::*;
+
+pub fn swap_with_referral_handler(
+    ctx: Context<SwapWithReferral>,
+    amount_in: u64,
+    min_amount_out: u64,
+    is_a_to_b: bool,
+    deadline: Option<i64>,
+) -> Result<SwapResult> {
+    let accounts = ctx.accounts;
+    
+    process_swap(
+        &mut accounts.swap,
+        amount_in,
+        min_amount_out,
+        is_a_to_b,
+        deadline,
+        Some(&accounts.referrer_token_account),
+    )
+}
+
+#[derive(Accounts)]
+pub struct SwapWithReferral<'info> {
+    pub swap: Swap<'info>,
+    
+    /// Referrer's token account for the input token, receives the referral fee
+    #[account(mut)]
+    pub referrer_token_account: Box<InterfaceAccount<'info, TokenAccount>>,  // ✅ BOX
+}
@@ -0,0 +1,96 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::state::{DcaOrder, PoolState};
+use crate::error::ErrorCode;
+use crate::instructions::shared::transfer_tokens;
+
+/// Adds `amount` of input to a DCA order's escrow. The schedule is unchanged.
+pub fn top_up_dca_order_handler(ctx: Context<TopUpDcaOrder>, amount: u64) -> Result<()> {
+    require!(amount > 0, ErrorCode::InvalidAmount);
+    require!(
+        ctx.accounts.user_token_in.amount >= amount,
+        ErrorCode::InsufficientBalance
+    );
+    
+    transfer_tokens(
+        &ctx.accounts.user_token_in,
+        &ctx.accounts.dca_escrow,
+        &ctx.accounts.input_mint,
+        &ctx.accounts.owner,
+        &ctx.accounts.token_program,
+        amount,
+    )?;
+    
+    let dca_order = &mut ctx.accounts.dca_order;
+    dca_order.remaining_amount = dca_order.remaining_amount
+        .checked_add(amount)
+        .ok_or(ErrorCode::MathOverflow)?;
+    
+    let clock = Clock::get()?;
+    emit!(DcaOrderToppedUp {
+        pool: dca_order.pool,
+        owner: dca_order.owner,
+        dca_order: dca_order.key(),
+        amount,
+        remaining_amount: dca_order.remaining_amount,
+        timestamp: clock.unix_timestamp,
+    });
+    
+    msg!("DCA order topped up: {} ({} left)", amount, dca_order.remaining_amount);
+    
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct TopUpDcaOrder<'info> {
+    pub owner: Signer<'info>,
+    
+    #[account(
+        seeds = [b"pool_state", pool_state.token_mint_a.as_ref(), pool_state.token_mint_b.as_ref()],
+        bump = pool_state.bump,
+    )]
+    pub pool_state: Box<Account<'info, PoolState>>,  // ✅ BOX
+    
+    #[account(address = dca_escrow.mint)]
+    pub input_mint: Box<InterfaceAccount<'info, Mint>>,  // ✅ BOX
+    
+    #[account(
+        mut,
+        seeds = [
+            b"dca_order",
+            pool_state.key().as_ref(),
+            owner.key().as_ref(),
+            &[dca_order.is_a_to_b as u8],
+        ],
+        bump = dca_order.bump,
+        has_one = owner @ ErrorCode::Unauthorized,
+    )]
+    pub dca_order: Box<Account<'info, DcaOrder>>,  // ✅ BOX
+    
+    #[account(
+        mut,
+        seeds = [b"dca_escrow", dca_order.key().as_ref()],
+        bump = dca_order.escrow_bump,
+    )]
+    pub dca_escrow: Box<InterfaceAccount<'info, TokenAccount>>,  // ✅ BOX
+    
+    #[account(
+        mut,
+        token::mint = input_mint,
+        token::authority = owner,
+    )]
+    pub user_token_in: Box<InterfaceAccount<'info, TokenAccount>>,  // ✅ BOX
+    
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[event]
+pub struct DcaOrderToppedUp {
+    pub pool: Pubkey,
+    pub owner: Pubkey,
+    pub dca_order: Pubkey,
+    pub amount: u64,
+    pub remaining_amount: u64,
+    pub timestamp: i64,
+}
@@ -0,0 +1,42 @@
+use anchor_lang::prelude::*;
+
+use crate::state::{PoolState, TraderStats, TRADER_STATS_WINDOW_DAYS};
+
+/// Creates the account that records `user`'s swap volume in a pool. Swaps that
+/// pass it accrue volume and get the pool's volume discount tier.
+pub fn open_trader_stats_handler(ctx: Context<OpenTraderStats>) -> Result<()> {
+    let trader_stats = &mut ctx.accounts.trader_stats;
+    trader_stats.pool = ctx.accounts.pool_state.key();
+    trader_stats.owner = ctx.accounts.user.key();
+    trader_stats.daily_volume = [0; TRADER_STATS_WINDOW_DAYS];
+    trader_stats.last_day = 0;
+    trader_stats.total_volume = 0;
+    trader_stats.bump = ctx.bumps.trader_stats;
+    
+    msg!("Trader stats opened for {}", trader_stats.owner);
+    
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct OpenTraderStats<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+    
+    #[account(
+        seeds = [b"pool_state", pool_state.token_mint_a.as_ref(), pool_state.token_mint_b.as_ref()],
+        bump = pool_state.bump,
+    )]
+    pub pool_state: Box<Account<'info, PoolState>>,  // ✅ BOX
+    
+    #[account(
+        init,
+        payer = user,
+        space = 8 + TraderStats::INIT_SPACE,
+        seeds = [b"trader_stats", pool_state.key().as_ref(), user.key().as_ref()],
+        bump,
+    )]
+    pub trader_stats: Box<Account<'info, TraderStats>>,  // ✅ BOX
+    
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,161 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::state::{LiquidityLock, PoolState};
+use crate::error::ErrorCode;
+use crate::instructions::shared::{transfer_tokens_signed, close_token_account_signed};
+
+pub fn unlock_liquidity_handler(ctx: Context<UnlockLiquidity>) -> Result<()> {
+    let clock = Clock::get()?;
+    let lock = &ctx.accounts.lock;
+    
+    let releasable = vested_amount(lock, clock.unix_timestamp)?
+        .checked_sub(lock.withdrawn)
+        .ok_or(ErrorCode::MathOverflow)?;
+    
+    require!(releasable > 0, ErrorCode::LiquidityStillLocked);
+    
+    let withdrawn = lock.withdrawn
+        .checked_add(releasable)
+        .ok_or(ErrorCode::MathOverflow)?;
+    
+    let remaining = lock.amount
+        .checked_sub(withdrawn)
+        .ok_or(ErrorCode::MathOverflow)?;
+    
+    // The final release sweeps the whole escrow so LP tokens sent to it
+    // directly can't keep it from closing
+    let release_amount = if remaining == 0 {
+        ctx.accounts.lock_escrow.amount
+    } else {
+        releasable
+    };
+    
+    let pool_key = ctx.accounts.pool_state.key();
+    let owner_key = ctx.accounts.owner.key();
+    let lock_id_bytes = lock.lock_id.to_le_bytes();
+    let lock_seeds = &[
+        b"lock",
+        pool_key.as_ref(),
+        owner_key.as_ref(),
+        lock_id_bytes.as_ref(),
+        &[lock.bump],
+    ];
+    let signer_seeds = &[&lock_seeds[..]];
+    
+    transfer_tokens_signed(
+        &ctx.accounts.lock_escrow,
+        &ctx.accounts.user_lp_token,
+        &ctx.accounts.pool_mint,
+        &ctx.accounts.lock.to_account_info(),
+        &ctx.accounts.token_program,
+        release_amount,
+        signer_seeds,
+    )?;
+    
+    let lock = &mut ctx.accounts.lock;
+    lock.withdrawn = withdrawn;
+    
+    emit!(LiquidityUnlocked {
+        pool: lock.pool,
+        owner: lock.owner,
+        lock: lock.key(),
+        lock_id: lock.lock_id,
+        lp_amount: release_amount,
+        remaining,
+        timestamp: clock.unix_timestamp,
+    });
+    
+    msg!(
+        "Liquidity unlocked: {} LP tokens, {} still locked",
+        release_amount,
+        remaining
+    );
+    
+    if remaining == 0 {
+        close_token_account_signed(
+            &ctx.accounts.lock_escrow,
+            &ctx.accounts.owner.to_account_info(),
+            &ctx.accounts.lock.to_account_info(),
+            &ctx.accounts.token_program,
+            signer_seeds,
+        )?;
+        
+        ctx.accounts.lock.close(ctx.accounts.owner.to_account_info())?;
+    }
+    
+    Ok(())
+}
+
+/// LP amount released by `now`: everything after `unlock_time`, and with linear
+/// vesting a pro-rata share of the time elapsed since `locked_at` before it.
+fn vested_amount(lock: &LiquidityLock, now: i64) -> Result<u64> {
+    if now >= lock.unlock_time {
+        return Ok(lock.amount);
+    }
+    
+    if !lock.linear_vesting || now <= lock.locked_at {
+        return Ok(0);
+    }
+    
+    let elapsed = (now - lock.locked_at) as u128;
+    let duration = (lock.unlock_time - lock.locked_at) as u128;
+    
+    let vested = (lock.amount as u128)
+        .checked_mul(elapsed)
+        .ok_or(ErrorCode::MathOverflow)?
+        .checked_div(duration)
+        .ok_or(ErrorCode::DivisionByZero)? as u64;
+    
+    Ok(vested)
+}
+
+#[derive(Accounts)]
+pub struct UnlockLiquidity<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    
+    #[account(
+        seeds = [b"pool_state", pool_state.token_mint_a.as_ref(), pool_state.token_mint_b.as_ref()],
+        bump = pool_state.bump,
+    )]
+    pub pool_state: Box<Account<'info, PoolState>>,  // ✅ BOX
+    
+    #[account(address = pool_state.pool_mint)]
+    pub pool_mint: Box<InterfaceAccount<'info, Mint>>,  // ✅ BOX
+    
+    #[account(
+        mut,
+        seeds = [b"lock", pool_state.key().as_ref(), owner.key().as_ref(), &lock.lock_id.to_le_bytes()],
+        bump = lock.bump,
+        has_one = owner @ ErrorCode::Unauthorized,
+    )]
+    pub lock: Box<Account<'info, LiquidityLock>>,  // ✅ BOX
+    
+    #[account(
+        mut,
+        seeds = [b"lock_escrow", lock.key().as_ref()],
+        bump = lock.escrow_bump,
+    )]
+    pub lock_escrow: Box<InterfaceAccount<'info, TokenAccount>>,  // ✅ BOX
+    
+    #[account(
+        mut,
+        token::mint = pool_mint,
+        token::authority = owner,
+    )]
+    pub user_lp_token: Box<InterfaceAccount<'info, TokenAccount>>,  // ✅ BOX
+    
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[event]
+pub struct LiquidityUnlocked {
+    pub pool: Pubkey,
+    pub owner: Pubkey,
+    pub lock: Pubkey,
+    pub lock_id: u64,
+    pub lp_amount: u64,
+    pub remaining: u64,
+    pub timestamp: i64,
+}
@@ -0,0 +1,111 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::state::{Farm, FarmStake};
+use crate::error::ErrorCode;
+use crate::instructions::shared::{
+    transfer_tokens_signed, update_farm_rewards, settle_farm_stake, reset_farm_stake_debt,
+};
+
+pub fn unstake_lp_handler(ctx: Context<UnstakeLp>, amount: u64) -> Result<()> {
+    require!(amount > 0, ErrorCode::InvalidAmount);
+    require!(
+        ctx.accounts.stake.amount >= amount,
+        ErrorCode::InsufficientBalance
+    );
+    
+    let clock = Clock::get()?;
+    let farm = &mut ctx.accounts.farm;
+    let stake = &mut ctx.accounts.stake;
+    
+    update_farm_rewards(farm, clock.unix_timestamp)?;
+    settle_farm_stake(farm, stake)?;
+    
+    let pool_mint_key = farm.pool_mint;
+    let farm_seeds = &[
+        b"farm",
+        pool_mint_key.as_ref(),
+        &[farm.bump],
+    ];
+    let signer_seeds = &[&farm_seeds[..]];
+    
+    transfer_tokens_signed(
+        &ctx.accounts.stake_vault,
+        &ctx.accounts.user_lp_token,
+        &ctx.accounts.pool_mint,
+        &farm.to_account_info(),
+        &ctx.accounts.token_program,
+        amount,
+        signer_seeds,
+    )?;
+    
+    stake.amount = stake.amount
+        .checked_sub(amount)
+        .ok_or(ErrorCode::MathOverflow)?;
+    
+    farm.total_staked = farm.total_staked
+        .checked_sub(amount)
+        .ok_or(ErrorCode::MathOverflow)?;
+    
+    reset_farm_stake_debt(farm, stake)?;
+    
+    emit!(LpUnstaked {
+        farm: farm.key(),
+        owner: stake.owner,
+        amount,
+        staked: stake.amount,
+        total_staked: farm.total_staked,
+        timestamp: clock.unix_timestamp,
+    });
+    
+    msg!("LP unstaked: {} (total staked: {})", amount, farm.total_staked);
+    
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct UnstakeLp<'info> {
+    pub owner: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"farm", farm.pool_mint.as_ref()],
+        bump = farm.bump,
+    )]
+    pub farm: Box<Account<'info, Farm>>,  // ✅ BOX
+    
+    #[account(
+        mut,
+        seeds = [b"farm_stake", farm.key().as_ref(), owner.key().as_ref()],
+        bump = stake.bump,
+    )]
+    pub stake: Box<Account<'info, FarmStake>>,  // ✅ BOX
+    
+    #[account(address = farm.pool_mint)]
+    pub pool_mint: Box<InterfaceAccount<'info, Mint>>,  // ✅ BOX
+    
+    #[account(
+        mut,
+        address = farm.stake_vault,
+    )]
+    pub stake_vault: Box<InterfaceAccount<'info, TokenAccount>>,  // ✅ BOX
+    
+    #[account(
+        mut,
+        token::mint = pool_mint,
+        token::authority = owner,
+    )]
+    pub user_lp_token: Box<InterfaceAccount<'info, TokenAccount>>,  // ✅ BOX
+    
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[event]
+pub struct LpUnstaked {
+    pub farm: Pubkey,
+    pub owner: Pubkey,
+    pub amount: u64,
+    pub staked: u64,
+    pub total_staked: u64,
+    pub timestamp: i64,
+}
@@ -0,0 +1,58 @@
+use anchor_lang::prelude::*;
+
+use crate::state::{AmmConfig, HolderDiscountTier, MAX_HOLDER_DISCOUNT_TIERS};
+use crate::error::ErrorCode;
+use crate::instructions::shared::validate_holder_discount_tiers;
+
+/// Sets the governance token and the balance tiers that earn its holders a swap
+/// fee discount. An empty tier list disables the discount.
+pub fn set_holder_discount_handler(
+    ctx: Context<UpdateConfig>,
+    discount_mint: Pubkey,
+    tiers: Vec<HolderDiscountTier>,
+) -> Result<()> {
+    validate_holder_discount_tiers(&tiers)?;
+    
+    let config = &mut ctx.accounts.config;
+    config.discount_mint = discount_mint;
+    config.holder_discount_tier_count = tiers.len() as u8;
+    config.holder_discount_tiers = [HolderDiscountTier::default(); MAX_HOLDER_DISCOUNT_TIERS];
+    config.holder_discount_tiers[..tiers.len()].copy_from_slice(&tiers);
+    
+    let clock = Clock::get()?;
+    emit!(HolderDiscountUpdated {
+        admin: ctx.accounts.admin.key(),
+        discount_mint,
+        tiers,
+        timestamp: clock.unix_timestamp,
+    });
+    
+    msg!(
+        "Holder discount updated: mint={}, tiers={}",
+        discount_mint,
+        config.holder_discount_tier_count
+    );
+    
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct UpdateConfig<'info> {
+    pub admin: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"amm_config"],
+        bump = config.bump,
+        has_one = admin @ ErrorCode::Unauthorized,
+    )]
+    pub config: Box<Account<'info, AmmConfig>>,  // ✅ BOX
+}
+
+#[event]
+pub struct HolderDiscountUpdated {
+    pub admin: Pubkey,
+    pub discount_mint: Pubkey,
+    pub tiers: Vec<HolderDiscountTier>,
+    pub timestamp: i64,
+}
@@ -0,0 +1,390 @@
+use anchor_lang::prelude::*;
+
+use crate::state::{
+    AmmConfig, PoolState, VolumeDiscountTier, MAX_RFQ_MAKERS, MAX_SANDWICH_EXEMPT_PROGRAMS,
+    MAX_VOLUME_DISCOUNT_TIERS,
+};
+use crate::error::ErrorCode;
+use crate::instructions::shared::{validate_volume_discount_tiers, BPS_DENOMINATOR};
+
+pub fn set_max_price_impact_handler(
+    ctx: Context<UpdatePoolConfig>,
+    max_price_impact_bps: u64,
+) -> Result<()> {
+    require!(
+        max_price_impact_bps <= BPS_DENOMINATOR,
+        ErrorCode::InvalidPriceImpactLimit
+    );
+    
+    let pool_state = &mut ctx.accounts.pool_state;
+    let previous_max_price_impact_bps = pool_state.max_price_impact_bps;
+    pool_state.max_price_impact_bps = max_price_impact_bps;
+    
+    let clock = Clock::get()?;
+    emit!(MaxPriceImpactUpdated {
+        pool: pool_state.key(),
+        admin: ctx.accounts.admin.key(),
+        previous_max_price_impact_bps,
+        max_price_impact_bps,
+        timestamp: clock.unix_timestamp,
+    });
+    
+    msg!(
+        "Max price impact updated: {} -> {} bps",
+        previous_max_price_impact_bps,
+        max_price_impact_bps
+    );
+    
+    Ok(())
+}
+
+/// Sets the per-slot circuit breaker bound. Zero disables the breaker.
+pub fn set_max_slot_price_move_handler(
+    ctx: Context<UpdatePoolConfig>,
+    max_slot_price_move_bps: u64,
+) -> Result<()> {
+    let pool_state = &mut ctx.accounts.pool_state;
+    let previous_max_slot_price_move_bps = pool_state.max_slot_price_move_bps;
+    pool_state.max_slot_price_move_bps = max_slot_price_move_bps;
+    
+    // Start a fresh reference on the next swap.
+    pool_state.slot_reference_slot = 0;
+    
+    let clock = Clock::get()?;
+    emit!(MaxSlotPriceMoveUpdated {
+        pool: pool_state.key(),
+        admin: ctx.accounts.admin.key(),
+        previous_max_slot_price_move_bps,
+        max_slot_price_move_bps,
+        timestamp: clock.unix_timestamp,
+    });
+    
+    msg!(
+        "Max slot price move updated: {} -> {} bps",
+        previous_max_slot_price_move_bps,
+        max_slot_price_move_bps
+    );
+    
+    Ok(())
+}
+
+/// Turns same-transaction sandwich protection on or off and sets the programs
+/// exempt from it.
+pub fn set_sandwich_protection_handler(
+    ctx: Context<UpdatePoolConfig>,
+    enabled: bool,
+    exempt_programs: Vec<Pubkey>,
+) -> Result<()> {
+    require!(
+        exempt_programs.len() <= MAX_SANDWICH_EXEMPT_PROGRAMS,
+        ErrorCode::TooManyExemptPrograms
+    );
+    
+    let pool_state = &mut ctx.accounts.pool_state;
+    pool_state.sandwich_protection = enabled;
+    pool_state.sandwich_exempt_program_count = exempt_programs.len() as u8;
+    pool_state.sandwich_exempt_programs = [Pubkey::default(); MAX_SANDWICH_EXEMPT_PROGRAMS];
+    pool_state.sandwich_exempt_programs[..exempt_programs.len()].copy_from_slice(&exempt_programs);
+    
+    let clock = Clock::get()?;
+    emit!(SandwichProtectionUpdated {
+        pool: pool_state.key(),
+        admin: ctx.accounts.admin.key(),
+        enabled,
+        exempt_programs,
+        timestamp: clock.unix_timestamp,
+    });
+    
+    msg!(
+        "Sandwich protection {} ({} exempt programs)",
+        if enabled { "enabled" } else { "disabled" },
+        pool_state.sandwich_exempt_program_count
+    );
+    
+    Ok(())
+}
+
+/// Replaces the market makers whose signed quotes `swap_rfq` accepts on this pool.
+pub fn set_rfq_makers_handler(
+    ctx: Context<UpdatePoolConfig>,
+    makers: Vec<Pubkey>,
+) -> Result<()> {
+    require!(makers.len() <= MAX_RFQ_MAKERS, ErrorCode::TooManyRfqMakers);
+    
+    let pool_state = &mut ctx.accounts.pool_state;
+    pool_state.rfq_maker_count = makers.len() as u8;
+    pool_state.rfq_makers = [Pubkey::default(); MAX_RFQ_MAKERS];
+    pool_state.rfq_makers[..makers.len()].copy_from_slice(&makers);
+    
+    let clock = Clock::get()?;
+    emit!(RfqMakersUpdated {
+        pool: pool_state.key(),
+        admin: ctx.accounts.admin.key(),
+        makers,
+        timestamp: clock.unix_timestamp,
+    });
+    
+    msg!("RFQ makers updated ({} allowlisted)", pool_state.rfq_maker_count);
+    
+    Ok(())
+}
+
+/// Sets the rolling 30-day volume tiers that earn traders of this pool a swap fee
+/// discount. Tiers are per pool because volume is counted in the pool's token A.
+/// An empty tier list disables the discount.
+pub fn set_volume_discount_handler(
+    ctx: Context<UpdatePoolConfig>,
+    tiers: Vec<VolumeDiscountTier>,
+) -> Result<()> {
+    validate_volume_discount_tiers(&tiers)?;
+    
+    let pool_state = &mut ctx.accounts.pool_state;
+    pool_state.volume_discount_tier_count = tiers.len() as u8;
+    pool_state.volume_discount_tiers = [VolumeDiscountTier::default(); MAX_VOLUME_DISCOUNT_TIERS];
+    pool_state.volume_discount_tiers[..tiers.len()].copy_from_slice(&tiers);
+    
+    let clock = Clock::get()?;
+    emit!(VolumeDiscountUpdated {
+        pool: pool_state.key(),
+        admin: ctx.accounts.admin.key(),
+        tiers,
+        timestamp: clock.unix_timestamp,
+    });
+    
+    msg!("Volume discount updated: tiers={}", pool_state.volume_discount_tier_count);
+    
+    Ok(())
+}
+
+/// Sets the batch auction window in slots. Zero switches the pool back to direct swaps;
+/// an open batch can still be settled.
+pub fn set_batch_auction_handler(
+    ctx: Context<UpdatePoolConfig>,
+    batch_auction_slots: u64,
+) -> Result<()> {
+    let pool_state = &mut ctx.accounts.pool_state;
+    let previous_batch_auction_slots = pool_state.batch_auction_slots;
+    pool_state.batch_auction_slots = batch_auction_slots;
+    
+    let clock = Clock::get()?;
+    emit!(BatchAuctionUpdated {
+        pool: pool_state.key(),
+        admin: ctx.accounts.admin.key(),
+        previous_batch_auction_slots,
+        batch_auction_slots,
+        timestamp: clock.unix_timestamp,
+    });
+    
+    msg!(
+        "Batch auction window updated: {} -> {} slots",
+        previous_batch_auction_slots,
+        batch_auction_slots
+    );
+    
+    Ok(())
+}
+
+/// Sets the swap fee of each direction. The denominator is fixed at creation.
+pub fn set_fees_handler(
+    ctx: Context<UpdatePoolConfig>,
+    fee_numerator_a_to_b: u64,
+    fee_numerator_b_to_a: u64,
+) -> Result<()> {
+    let pool_state = &mut ctx.accounts.pool_state;
+    
+    require!(
+        fee_numerator_a_to_b <= pool_state.fee_denominator / 100,
+        ErrorCode::FeeTooHigh
+    );
+    require!(
+        fee_numerator_b_to_a <= pool_state.fee_denominator / 100,
+        ErrorCode::FeeTooHigh
+    );
+    
+    if pool_state.max_fee_numerator > 0 {
+        require!(
+            pool_state.max_fee_numerator >= fee_numerator_a_to_b
+                && pool_state.max_fee_numerator >= fee_numerator_b_to_a,
+            ErrorCode::InvalidDynamicFee
+        );
+    }
+    
+    let previous_fee_numerator_a_to_b = pool_state.fee_numerator_a_to_b;
+    let previous_fee_numerator_b_to_a = pool_state.fee_numerator_b_to_a;
+    pool_state.fee_numerator_a_to_b = fee_numerator_a_to_b;
+    pool_state.fee_numerator_b_to_a = fee_numerator_b_to_a;
+    
+    let clock = Clock::get()?;
+    emit!(FeesUpdated {
+        pool: pool_state.key(),
+        admin: ctx.accounts.admin.key(),
+        previous_fee_numerator_a_to_b,
+        previous_fee_numerator_b_to_a,
+        fee_numerator_a_to_b,
+        fee_numerator_b_to_a,
+        fee_denominator: pool_state.fee_denominator,
+        timestamp: clock.unix_timestamp,
+    });
+    
+    msg!(
+        "Fees updated: {}/{} (A→B), {}/{} (B→A)",
+        fee_numerator_a_to_b,
+        pool_state.fee_denominator,
+        fee_numerator_b_to_a,
+        pool_state.fee_denominator
+    );
+    
+    Ok(())
+}
+
+/// Enables the dynamic fee, or disables it when `max_fee_numerator` is zero.
+pub fn set_dynamic_fee_handler(
+    ctx: Context<UpdatePoolConfig>,
+    max_fee_numerator: u64,
+    volatility_cap_bps: u64,
+    volatility_decay_period: i64,
+) -> Result<()> {
+    let pool_state = &mut ctx.accounts.pool_state;
+    
+    if max_fee_numerator > 0 {
+        require!(
+            max_fee_numerator >= pool_state.fee_numerator_a_to_b
+                && max_fee_numerator >= pool_state.fee_numerator_b_to_a,
+            ErrorCode::InvalidDynamicFee
+        );
+        require!(
+            max_fee_numerator <= pool_state.fee_denominator / 100,
+            ErrorCode::FeeTooHigh
+        );
+        require!(volatility_cap_bps > 0, ErrorCode::InvalidDynamicFee);
+        require!(volatility_decay_period > 0, ErrorCode::InvalidDynamicFee);
+    }
+    
+    pool_state.max_fee_numerator = max_fee_numerator;
+    pool_state.volatility_cap_bps = volatility_cap_bps;
+    pool_state.volatility_decay_period = volatility_decay_period;
+    pool_state.volatility_accumulator = 0;
+    
+    let clock = Clock::get()?;
+    pool_state.volatility_updated_at = clock.unix_timestamp;
+    
+    emit!(DynamicFeeUpdated {
+        pool: pool_state.key(),
+        admin: ctx.accounts.admin.key(),
+        fee_numerator_a_to_b: pool_state.fee_numerator_a_to_b,
+        fee_numerator_b_to_a: pool_state.fee_numerator_b_to_a,
+        max_fee_numerator,
+        fee_denominator: pool_state.fee_denominator,
+        volatility_cap_bps,
+        volatility_decay_period,
+        timestamp: clock.unix_timestamp,
+    });
+    
+    msg!(
+        "Dynamic fee updated: max {}/{} (cap: {}bps, decay: {}s)",
+        max_fee_numerator,
+        pool_state.fee_denominator,
+        volatility_cap_bps,
+        volatility_decay_period
+    );
+    
+    Ok(())
+}
+
+/// Pool settings are changed by the program config admin; pool creation is
+/// permissionless, so the creator gets no say.
+#[derive(Accounts)]
+pub struct UpdatePoolConfig<'info> {
+    pub admin: Signer<'info>,
+    
+    #[account(
+        seeds = [b"amm_config"],
+        bump = config.bump,
+        has_one = admin @ ErrorCode::Unauthorized,
+    )]
+    pub config: Box<Account<'info, AmmConfig>>,  // ✅ BOX
+    
+    #[account(
+        mut,
+        seeds = [b"pool_state", pool_state.token_mint_a.as_ref(), pool_state.token_mint_b.as_ref()],
+        bump = pool_state.bump,
+    )]
+    pub pool_state: Box<Account<'info, PoolState>>,  // ✅ BOX
+}
+
+#[event]
+pub struct MaxPriceImpactUpdated {
+    pub pool: Pubkey,
+    pub admin: Pubkey,
+    pub previous_max_price_impact_bps: u64,
+    pub max_price_impact_bps: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct DynamicFeeUpdated {
+    pub pool: Pubkey,
+    pub admin: Pubkey,
+    pub fee_numerator_a_to_b: u64,
+    pub fee_numerator_b_to_a: u64,
+    pub max_fee_numerator: u64,
+    pub fee_denominator: u64,
+    pub volatility_cap_bps: u64,
+    pub volatility_decay_period: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct FeesUpdated {
+    pub pool: Pubkey,
+    pub admin: Pubkey,
+    pub previous_fee_numerator_a_to_b: u64,
+    pub previous_fee_numerator_b_to_a: u64,
+    pub fee_numerator_a_to_b: u64,
+    pub fee_numerator_b_to_a: u64,
+    pub fee_denominator: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct MaxSlotPriceMoveUpdated {
+    pub pool: Pubkey,
+    pub admin: Pubkey,
+    pub previous_max_slot_price_move_bps: u64,
+    pub max_slot_price_move_bps: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct SandwichProtectionUpdated {
+    pub pool: Pubkey,
+    pub admin: Pubkey,
+    pub enabled: bool,
+    pub exempt_programs: Vec<Pubkey>,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct BatchAuctionUpdated {
+    pub pool: Pubkey,
+    pub admin: Pubkey,
+    pub previous_batch_auction_slots: u64,
+    pub batch_auction_slots: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RfqMakersUpdated {
+    pub pool: Pubkey,
+    pub admin: Pubkey,
+    pub makers: Vec<Pubkey>,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct VolumeDiscountUpdated {
+    pub pool: Pubkey,
+    pub admin: Pubkey,
+    pub tiers: Vec<VolumeDiscountTier>,
+    pub timestamp: i64,
+}
@@ -5,6 +5,7 @@ pub mod state;
 pub mod instructions;
 
 use instructions::*;
+use state::{FeeTier, HolderDiscountTier, RfqQuote, TriggerKind, VolumeDiscountTier};
 
 declare_id!("FiG4LoB7kGhAsufQGeZkBs72qgN6D4wFQpwVByGGo65F");
 
@@ -12,12 +13,27 @@ declare_id!("FiG4LoB7kGhAsufQGeZkBs72qgN6D4wFQpwVByGGo65F");
 pub mod amm {
     use super::*;
     
+    #[allow(clippy::too_many_arguments)]
     pub fn initialize_pool(
         ctx: Context<InitializePool>,
-        fee_numerator: u64,
+        fee_numerator_a_to_b: u64,
+        fee_numerator_b_to_a: u64,
         fee_denominator: u64,
+        referral_fee_bps: u64,
+        max_price_impact_bps: u64,
+        lp_metadata_uri: String,
+        fee_tiers: Vec<FeeTier>,
     ) -> Result<()> {
-        instructions::initialize_pool::initialize_pool_handler(ctx, fee_numerator, fee_denominator)
+        instructions::initialize_pool::initialize_pool_handler(
+            ctx,
+            fee_numerator_a_to_b,
+            fee_numerator_b_to_a,
+            fee_denominator,
+            referral_fee_bps,
+            max_price_impact_bps,
+            lp_metadata_uri,
+            fee_tiers,
+        )
     }
     
   
@@ -28,7 +44,27 @@ pub mod amm {
         amount_a_min: u64,
         amount_b_min: u64,
         min_lp_tokens: u64,
-    ) -> Result<()> {
+    ) -> Result<AddLiquidityResult> {
+        instructions::add_liquidity::add_liquidity_handler(
+            ctx,
+            amount_a_desired,
+            amount_b_desired,
+            amount_a_min,
+            amount_b_min,
+            min_lp_tokens,
+            None,
+        )
+    }
+    
+    pub fn add_liquidity_with_deadline(
+        ctx: Context<AddLiquidity>,
+        amount_a_desired: u64,
+        amount_b_desired: u64,
+        amount_a_min: u64,
+        amount_b_min: u64,
+        min_lp_tokens: u64,
+        deadline: i64,
+    ) -> Result<AddLiquidityResult> {
         instructions::add_liquidity::add_liquidity_handler(
             ctx,
             amount_a_desired,
@@ -36,6 +72,7 @@ pub mod amm {
             amount_a_min,
             amount_b_min,
             min_lp_tokens,
+            Some(deadline),
         )
     }
     
@@ -44,12 +81,29 @@ pub mod amm {
         lp_tokens: u64,
         min_amount_a: u64,
         min_amount_b: u64,
-    ) -> Result<()> {
+    ) -> Result<RemoveLiquidityResult> {
         instructions::remove_liquidity::remove_liquidity_handler(
             ctx,
             lp_tokens,
             min_amount_a,
             min_amount_b,
+            None,
+        )
+    }
+    
+    pub fn remove_liquidity_with_deadline(
+        ctx: Context<RemoveLiquidity>,
+        lp_tokens: u64,
+        min_amount_a: u64,
+        min_amount_b: u64,
+        deadline: i64,
+    ) -> Result<RemoveLiquidityResult> {
+        instructions::remove_liquidity::remove_liquidity_handler(
+            ctx,
+            lp_tokens,
+            min_amount_a,
+            min_amount_b,
+            Some(deadline),
         )
     }
     
@@ -58,7 +112,413 @@ pub mod amm {
         amount_in: u64,
         min_amount_out: u64,
         is_a_to_b: bool,
+    ) -> Result<SwapResult> {
+        instructions::swap::swap_handler(ctx, amount_in, min_amount_out, is_a_to_b, None)
+    }
+    
+    pub fn swap_with_deadline(
+        ctx: Context<Swap>,
+        amount_in: u64,
+        min_amount_out: u64,
+        is_a_to_b: bool,
+        deadline: i64,
+    ) -> Result<SwapResult> {
+        instructions::swap::swap_handler(
+            ctx,
+            amount_in,
+            min_amount_out,
+            is_a_to_b,
+            Some(deadline),
+        )
+    }
+    
+    pub fn swap_with_referral(
+        ctx: Context<SwapWithReferral>,
+        amount_in: u64,
+        min_amount_out: u64,
+        is_a_to_b: bool,
+        deadline: Option<i64>,
+    ) -> Result<SwapResult> {
+        instructions::swap_with_referral::swap_with_referral_handler(
+            ctx,
+            amount_in,
+            min_amount_out,
+            is_a_to_b,
+            deadline,
+        )
+    }
+    
+    pub fn set_max_price_impact(
+        ctx: Context<UpdatePoolConfig>,
+        max_price_impact_bps: u64,
+    ) -> Result<()> {
+        instructions::update_pool_config::set_max_price_impact_handler(ctx, max_price_impact_bps)
+    }
+    
+    pub fn set_max_slot_price_move(
+        ctx: Context<UpdatePoolConfig>,
+        max_slot_price_move_bps: u64,
+    ) -> Result<()> {
+        instructions::update_pool_config::set_max_slot_price_move_handler(ctx, max_slot_price_move_bps)
+    }
+    
+    pub fn set_sandwich_protection(
+        ctx: Context<UpdatePoolConfig>,
+        enabled: bool,
+        exempt_programs: Vec<Pubkey>,
+    ) -> Result<()> {
+        instructions::update_pool_config::set_sandwich_protection_handler(
+            ctx,
+            enabled,
+            exempt_programs,
+        )
+    }
+    
+    pub fn set_batch_auction(
+        ctx: Context<UpdatePoolConfig>,
+        batch_auction_slots: u64,
+    ) -> Result<()> {
+        instructions::update_pool_config::set_batch_auction_handler(ctx, batch_auction_slots)
+    }
+    
+    pub fn set_rfq_makers(ctx: Context<UpdatePoolConfig>, makers: Vec<Pubkey>) -> Result<()> {
+        instructions::update_pool_config::set_rfq_makers_handler(ctx, makers)
+    }
+    
+    pub fn set_fees(
+        ctx: Context<UpdatePoolConfig>,
+        fee_numerator_a_to_b: u64,
+        fee_numerator_b_to_a: u64,
+    ) -> Result<()> {
+        instructions::update_pool_config::set_fees_handler(
+            ctx,
+            fee_numerator_a_to_b,
+            fee_numerator_b_to_a,
+        )
+    }
+    
+    pub fn set_dynamic_fee(
+        ctx: Context<UpdatePoolConfig>,
+        max_fee_numerator: u64,
+        volatility_cap_bps: u64,
+        volatility_decay_period: i64,
+    ) -> Result<()> {
+        instructions::update_pool_config::set_dynamic_fee_handler(
+            ctx,
+            max_fee_numerator,
+            volatility_cap_bps,
+            volatility_decay_period,
+        )
+    }
+    
+    pub fn quote_swap(
+        ctx: Context<QuoteSwap>,
+        amount_in: u64,
+        is_a_to_b: bool,
+    ) -> Result<SwapQuote> {
+        instructions::quote::quote_swap_handler(ctx, amount_in, is_a_to_b)
+    }
+    
+    pub fn quote_add_liquidity(
+        ctx: Context<Quote>,
+        amount_a_desired: u64,
+        amount_b_desired: u64,
+    ) -> Result<AddLiquidityQuote> {
+        instructions::quote::quote_add_liquidity_handler(ctx, amount_a_desired, amount_b_desired)
+    }
+    
+    pub fn quote_remove_liquidity(
+        ctx: Context<Quote>,
+        lp_tokens: u64,
+    ) -> Result<RemoveLiquidityQuote> {
+        instructions::quote::quote_remove_liquidity_handler(ctx, lp_tokens)
+    }
+    
+    pub fn open_position(
+        ctx: Context<OpenPosition>,
+        amount_a_desired: u64,
+        amount_b_desired: u64,
+        amount_a_min: u64,
+        amount_b_min: u64,
+        min_lp_shares: u64,
+    ) -> Result<()> {
+        instructions::position::open_position_handler(
+            ctx,
+            amount_a_desired,
+            amount_b_desired,
+            amount_a_min,
+            amount_b_min,
+            min_lp_shares,
+        )
+    }
+    
+    pub fn increase_position(
+        ctx: Context<ModifyPosition>,
+        amount_a_desired: u64,
+        amount_b_desired: u64,
+        amount_a_min: u64,
+        amount_b_min: u64,
+        min_lp_shares: u64,
+    ) -> Result<()> {
+        instructions::position::increase_position_handler(
+            ctx,
+            amount_a_desired,
+            amount_b_desired,
+            amount_a_min,
+            amount_b_min,
+            min_lp_shares,
+        )
+    }
+    
+    pub fn decrease_position(
+        ctx: Context<ModifyPosition>,
+        lp_shares: u64,
+        min_amount_a: u64,
+        min_amount_b: u64,
+    ) -> Result<()> {
+        instructions::position::decrease_position_handler(ctx, lp_shares, min_amount_a, min_amount_b)
+    }
+    
+    pub fn close_position(
+        ctx: Context<ModifyPosition>,
+        min_amount_a: u64,
+        min_amount_b: u64,
+    ) -> Result<()> {
+        instructions::position::close_position_handler(ctx, min_amount_a, min_amount_b)
+    }
+    
+    pub fn lock_liquidity(
+        ctx: Context<LockLiquidity>,
+        lock_id: u64,
+        lp_amount: u64,
+        unlock_time: i64,
+        linear_vesting: bool,
+    ) -> Result<()> {
+        instructions::lock_liquidity::lock_liquidity_handler(
+            ctx,
+            lock_id,
+            lp_amount,
+            unlock_time,
+            linear_vesting,
+        )
+    }
+    
+    pub fn unlock_liquidity(ctx: Context<UnlockLiquidity>) -> Result<()> {
+        instructions::unlock_liquidity::unlock_liquidity_handler(ctx)
+    }
+    
+    pub fn create_farm(
+        ctx: Context<CreateFarm>,
+        reward_per_second: u64,
+        start_time: i64,
+        end_time: i64,
+    ) -> Result<()> {
+        instructions::create_farm::create_farm_handler(ctx, reward_per_second, start_time, end_time)
+    }
+    
+    pub fn add_farm_reward(
+        ctx: Context<AddFarmReward>,
+        reward_per_second: u64,
+        start_time: i64,
+        end_time: i64,
+    ) -> Result<()> {
+        instructions::add_farm_reward::add_farm_reward_handler(
+            ctx,
+            reward_per_second,
+            start_time,
+            end_time,
+        )
+    }
+    
+    pub fn stake_lp(ctx: Context<StakeLp>, amount: u64) -> Result<()> {
+        instructions::stake_lp::stake_lp_handler(ctx, amount)
+    }
+    
+    pub fn unstake_lp(ctx: Context<UnstakeLp>, amount: u64) -> Result<()> {
+        instructions::unstake_lp::unstake_lp_handler(ctx, amount)
+    }
+    
+    pub fn claim_rewards(ctx: Context<ClaimRewards>, reward_index: u8) -> Result<()> {
+        instructions::claim_rewards::claim_rewards_handler(ctx, reward_index)
+    }
+    
+    pub fn initialize_config(ctx: Context<InitializeConfig>, admin: Pubkey) -> Result<()> {
+        instructions::initialize_config::initialize_config_handler(ctx, admin)
+    }
+    
+    pub fn set_fee_discount(
+        ctx: Context<SetFeeDiscount>,
+        trader: Pubkey,
+        discount_bps: u64,
+    ) -> Result<()> {
+        instructions::fee_discount::set_fee_discount_handler(ctx, trader, discount_bps)
+    }
+    
+    pub fn remove_fee_discount(ctx: Context<RemoveFeeDiscount>) -> Result<()> {
+        instructions::fee_discount::remove_fee_discount_handler(ctx)
+    }
+    
+    pub fn set_holder_discount(
+        ctx: Context<UpdateConfig>,
+        discount_mint: Pubkey,
+        tiers: Vec<HolderDiscountTier>,
+    ) -> Result<()> {
+        instructions::update_config::set_holder_discount_handler(ctx, discount_mint, tiers)
+    }
+    
+    pub fn set_volume_discount(
+        ctx: Context<UpdatePoolConfig>,
+        tiers: Vec<VolumeDiscountTier>,
+    ) -> Result<()> {
+        instructions::update_pool_config::set_volume_discount_handler(ctx, tiers)
+    }
+    
+    pub fn open_trader_stats(ctx: Context<OpenTraderStats>) -> Result<()> {
+        instructions::trader_stats::open_trader_stats_handler(ctx)
+    }
+    
+    pub fn commit_swap(ctx: Context<CommitSwap>, hash: [u8; 32], deposit: u64) -> Result<()> {
+        instructions::commit_swap::commit_swap_handler(ctx, hash, deposit)
+    }
+    
+    pub fn reveal_swap(
+        ctx: Context<RevealSwap>,
+        amount_in: u64,
+        min_amount_out: u64,
+        salt: [u8; 32],
+    ) -> Result<SwapResult> {
+        instructions::reveal_swap::reveal_swap_handler(ctx, amount_in, min_amount_out, salt)
+    }
+    
+    pub fn refund_swap_commit(ctx: Context<RefundSwapCommit>) -> Result<()> {
+        instructions::refund_swap_commit::refund_swap_commit_handler(ctx)
+    }
+    
+    pub fn submit_batch_order(
+        ctx: Context<SubmitBatchOrder>,
+        amount_in: u64,
+        min_amount_out: u64,
+        is_a_to_b: bool,
+    ) -> Result<()> {
+        instructions::submit_batch_order::submit_batch_order_handler(
+            ctx,
+            amount_in,
+            min_amount_out,
+            is_a_to_b,
+        )
+    }
+    
+    pub fn settle_batch<'info>(ctx: Context<'_, '_, 'info, 'info, SettleBatch<'info>>) -> Result<()> {
+        instructions::settle_batch::settle_batch_handler(ctx)
+    }
+    
+    pub fn claim_batch_order(ctx: Context<ClaimBatchOrder>, order_index: u8) -> Result<()> {
+        instructions::claim_batch_order::claim_batch_order_handler(ctx, order_index)
+    }
+    
+    pub fn place_limit_order(
+        ctx: Context<PlaceLimitOrder>,
+        order_id: u64,
+        amount_in: u64,
+        target_price: u64,
+        is_a_to_b: bool,
+        expiry: i64,
+    ) -> Result<()> {
+        instructions::place_limit_order::place_limit_order_handler(
+            ctx,
+            order_id,
+            amount_in,
+            target_price,
+            is_a_to_b,
+            expiry,
+        )
+    }
+    
+    pub fn execute_limit_order(ctx: Context<ExecuteLimitOrder>) -> Result<()> {
+        instructions::execute_limit_order::execute_limit_order_handler(ctx)
+    }
+    
+    pub fn cancel_limit_order(ctx: Context<CancelLimitOrder>) -> Result<()> {
+        instructions::cancel_limit_order::cancel_limit_order_handler(ctx)
+    }
+    
+    pub fn open_dca_order(
+        ctx: Context<OpenDcaOrder>,
+        total_amount: u64,
+        amount_per_interval: u64,
+        interval: i64,
+        min_amount_out: u64,
+        is_a_to_b: bool,
     ) -> Result<()> {
-        instructions::swap::swap_handler(ctx, amount_in, min_amount_out, is_a_to_b)
+        instructions::open_dca_order::open_dca_order_handler(
+            ctx,
+            total_amount,
+            amount_per_interval,
+            interval,
+            min_amount_out,
+            is_a_to_b,
+        )
+    }
+    
+    pub fn execute_dca_order(ctx: Context<ExecuteDcaOrder>) -> Result<()> {
+        instructions::execute_dca_order::execute_dca_order_handler(ctx)
+    }
+    
+    pub fn top_up_dca_order(ctx: Context<TopUpDcaOrder>, amount: u64) -> Result<()> {
+        instructions::top_up_dca_order::top_up_dca_order_handler(ctx, amount)
+    }
+    
+    pub fn cancel_dca_order(ctx: Context<CancelDcaOrder>) -> Result<()> {
+        instructions::cancel_dca_order::cancel_dca_order_handler(ctx)
+    }
+    
+    pub fn place_trigger_order(
+        ctx: Context<PlaceTriggerOrder>,
+        amount_in: u64,
+        min_amount_out: u64,
+        is_a_to_b: bool,
+        kind: TriggerKind,
+        trigger_price: u64,
+        twap_window: i64,
+    ) -> Result<()> {
+        instructions::place_trigger_order::place_trigger_order_handler(
+            ctx,
+            amount_in,
+            min_amount_out,
+            is_a_to_b,
+            kind,
+            trigger_price,
+            twap_window,
+        )
+    }
+    
+    pub fn execute_trigger_order(ctx: Context<ExecuteTriggerOrder>) -> Result<()> {
+        instructions::execute_trigger_order::execute_trigger_order_handler(ctx)
+    }
+    
+    pub fn cancel_trigger_order(ctx: Context<CancelTriggerOrder>) -> Result<()> {
+        instructions::cancel_trigger_order::cancel_trigger_order_handler(ctx)
+    }
+    
+    pub fn swap_rfq(
+        ctx: Context<SwapRfq>,
+        amount_in: u64,
+        min_amount_out: u64,
+        is_a_to_b: bool,
+        quote: RfqQuote,
+    ) -> Result<SwapResult> {
+        instructions::swap_rfq::swap_rfq_handler(ctx, amount_in, min_amount_out, is_a_to_b, quote)
+    }
+    
+    pub fn close_rfq_quote_fill(ctx: Context<CloseRfqQuoteFill>) -> Result<()> {
+        instructions::close_rfq_quote_fill::close_rfq_quote_fill_handler(ctx)
+    }
+    
+    pub fn batch_swap<'info>(
+        ctx: Context<'_, '_, 'info, 'info, BatchSwap<'info>>,
+        entries: Vec<BatchSwapEntry>,
+        skip_failed: bool,
+    ) -> Result<Vec<BatchSwapEntryResult>> {
+        instructions::batch_swap::batch_swap_handler(ctx, entries, skip_failed)
     }
 }
\ No newline at end of file
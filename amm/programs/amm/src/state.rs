@@ -1,5 +1,30 @@
 use anchor_lang::prelude::*;
 
+pub const MAX_FARM_REWARDS: usize = 4;
+pub const MAX_FEE_TIERS: usize = 4;
+pub const MAX_HOLDER_DISCOUNT_TIERS: usize = 4;
+pub const MAX_VOLUME_DISCOUNT_TIERS: usize = 4;
+pub const TRADER_STATS_WINDOW_DAYS: usize = 30;
+pub const MAX_SANDWICH_EXEMPT_PROGRAMS: usize = 4;
+pub const MAX_BATCH_ORDERS: usize = 8;
+pub const MAX_PRICE_OBSERVATIONS: usize = 16;
+pub const MAX_RFQ_MAKERS: usize = 4;
+
+/// Marginal fee charged on the part of a swap beyond `threshold_bps` of `reserve_in`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, InitSpace)]
+pub struct FeeTier {
+    pub threshold_bps: u64,
+    pub fee_numerator: u64,
+}
+
+/// Cumulative prices of a pool at `timestamp`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, InitSpace)]
+pub struct PriceObservation {
+    pub timestamp: i64,
+    pub price_a_cumulative: u128,
+    pub price_b_cumulative: u128,
+}
+
 #[account]
 #[derive(InitSpace)]
 pub struct PoolState {
@@ -11,14 +36,398 @@ pub struct PoolState {
 
     pub pool_mint: Pubkey,
 
-    pub fee_numerator: u64,
+    /// Swap fee rates by direction, over `fee_denominator`.
+    pub fee_numerator_a_to_b: u64,
+    pub fee_numerator_b_to_a: u64,
     pub fee_denominator: u64,
+    /// Share of each swap fee, in bps, paid out to the referrer when one is given.
+    pub referral_fee_bps: u64,
+    /// Maximum price impact of a single swap against the spot price, in bps. Zero disables the check.
+    pub max_price_impact_bps: u64,
+    /// Maximum move of the A/B price within one slot, in bps. Zero disables the breaker.
+    pub max_slot_price_move_bps: u64,
+    /// A/B spot price before the first swap of `slot_reference_slot`.
+    pub slot_reference_price: u64,
+    pub slot_reference_slot: u64,
+
+    /// Rejects swaps sharing a transaction with an opposite-direction swap on this pool.
+    pub sandwich_protection: bool,
+    /// Programs whose CPI swaps skip the sandwich check, e.g. routers.
+    pub sandwich_exempt_program_count: u8,
+    pub sandwich_exempt_programs: [Pubkey; MAX_SANDWICH_EXEMPT_PROGRAMS],
+
+    /// Market makers whose signed quotes `swap_rfq` accepts.
+    pub rfq_maker_count: u8,
+    pub rfq_makers: [Pubkey; MAX_RFQ_MAKERS],
+
+    /// Slots each swap batch collects orders for. Zero disables batch auctions;
+    /// otherwise swaps only go through batches.
+    pub batch_auction_slots: u64,
+    /// Id of the batch currently collecting orders.
+    pub current_batch_id: u64,
+
+    /// Ceiling of the dynamic fee, over `fee_denominator`. Zero disables the dynamic fee
+    /// and every swap pays the fee of its direction.
+    pub max_fee_numerator: u64,
+    /// Accumulated volatility, in bps, at which the dynamic fee reaches `max_fee_numerator`.
+    pub volatility_cap_bps: u64,
+    /// Seconds for the volatility accumulator to decay linearly back to zero.
+    pub volatility_decay_period: i64,
+    /// Sum of recent swap price moves, in bps, as of `volatility_updated_at`.
+    pub volatility_accumulator: u64,
+    pub volatility_updated_at: i64,
+
+    /// Trade-size fee schedule, ordered by threshold. Unused entries are zeroed.
+    pub fee_tier_count: u8,
+    pub fee_tiers: [FeeTier; MAX_FEE_TIERS],
+
+    /// Fee discounts for `TraderStats` volume in this pool, ordered by `min_volume`
+    /// in token A base units. Set by the config admin.
+    pub volume_discount_tier_count: u8,
+    pub volume_discount_tiers: [VolumeDiscountTier; MAX_VOLUME_DISCOUNT_TIERS],
 
     pub reserve_a: u64,
     pub reserve_b: u64,
     pub total_supply: u64,
+    /// LP amount withheld from the first deposit, scaled to the LP mint decimals.
+    pub minimum_liquidity: u64,
+
+    /// Cumulative LP fee per LP share, Q64.64, in token A and token B.
+    pub fee_growth_global_a: u128,
+    pub fee_growth_global_b: u128,
+
+    /// Time integrals of the B-per-A and A-per-B spot prices, scaled by `PRICE_SCALE`.
+    /// They wrap on overflow; only differences between two readings are meaningful.
+    pub price_a_cumulative: u128,
+    pub price_b_cumulative: u128,
+    pub price_cumulative_updated_at: i64,
+    /// Ring buffer of past cumulative prices, at most one per `PRICE_OBSERVATION_PERIOD`.
+    pub price_observation_index: u8,
+    pub price_observations: [PriceObservation; MAX_PRICE_OBSERVATIONS],
 
     pub bump: u8,
     pub authority_bump: u8,
     pub pool_mint_bump: u8,
 }
+
+/// Non-transferable LP position. Shares count towards the pool's `total_supply`
+/// but are held here instead of being minted as LP tokens.
+#[account]
+#[derive(InitSpace)]
+pub struct Position {
+    pub pool: Pubkey,
+    pub owner: Pubkey,
+
+    pub lp_shares: u64,
+
+    /// Totals deposited into and withdrawn from the pool through this position.
+    pub deposited_a: u64,
+    pub deposited_b: u64,
+    pub withdrawn_a: u64,
+    pub withdrawn_b: u64,
+
+    /// Pool reserves at the last deposit.
+    pub entry_reserve_a: u64,
+    pub entry_reserve_b: u64,
+
+    /// Pool fee growth at the last update, and the fees earned up to it.
+    pub fee_growth_checkpoint_a: u128,
+    pub fee_growth_checkpoint_b: u128,
+    pub fees_earned_a: u64,
+    pub fees_earned_b: u64,
+
+    pub opened_at: i64,
+    pub bump: u8,
+}
+
+/// LP tokens held in escrow until `unlock_time`, optionally vesting linearly
+/// from `locked_at`.
+#[account]
+#[derive(InitSpace)]
+pub struct LiquidityLock {
+    pub pool: Pubkey,
+    pub owner: Pubkey,
+    pub lock_id: u64,
+
+    pub amount: u64,
+    pub withdrawn: u64,
+
+    pub locked_at: i64,
+    pub unlock_time: i64,
+    pub linear_vesting: bool,
+
+    pub bump: u8,
+    pub escrow_bump: u8,
+}
+
+/// One reward stream of a farm, emitted at `reward_per_second` between
+/// `start_time` and `end_time`. `end_time` moves back by any period during
+/// which nothing was staked.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, InitSpace)]
+pub struct FarmReward {
+    pub reward_mint: Pubkey,
+    pub reward_vault: Pubkey,
+
+    pub reward_per_second: u64,
+    pub start_time: i64,
+    pub end_time: i64,
+
+    /// Rewards accumulated per staked LP token, scaled by `REWARD_PER_SHARE_PRECISION`.
+    pub reward_per_share: u128,
+    pub last_update_time: i64,
+}
+
+/// Liquidity-mining farm for a pool's LP token, keyed by `pool_mint`.
+#[account]
+#[derive(InitSpace)]
+pub struct Farm {
+    pub pool: Pubkey,
+    pub pool_mint: Pubkey,
+    pub authority: Pubkey,
+    pub stake_vault: Pubkey,
+
+    pub total_staked: u64,
+
+    pub reward_count: u8,
+    pub rewards: [FarmReward; MAX_FARM_REWARDS],
+
+    pub bump: u8,
+    pub stake_vault_bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct FarmStake {
+    pub farm: Pubkey,
+    pub owner: Pubkey,
+
+    pub amount: u64,
+
+    /// Per reward: `amount * reward_per_share` at the last settlement, and the
+    /// rewards settled but not yet claimed.
+    pub reward_debt: [u128; MAX_FARM_REWARDS],
+    pub pending_rewards: [u64; MAX_FARM_REWARDS],
+
+    pub bump: u8,
+}
+
+/// Swap fee discount for holding at least `min_balance` of the discount mint.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, InitSpace)]
+pub struct HolderDiscountTier {
+    pub min_balance: u64,
+    pub discount_bps: u64,
+}
+
+/// Swap fee discount for trading at least `min_volume` over the rolling window.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, InitSpace)]
+pub struct VolumeDiscountTier {
+    pub min_volume: u64,
+    pub discount_bps: u64,
+}
+
+/// Program-wide configuration, managed by `admin`.
+#[account]
+#[derive(InitSpace)]
+pub struct AmmConfig {
+    pub admin: Pubkey,
+
+    /// Token whose holders get a swap fee discount. Default pubkey disables it.
+    pub discount_mint: Pubkey,
+    /// Holder discount tiers, ordered by `min_balance`.
+    pub holder_discount_tier_count: u8,
+    pub holder_discount_tiers: [HolderDiscountTier; MAX_HOLDER_DISCOUNT_TIERS],
+
+    pub bump: u8,
+}
+
+/// Swap fee discount granted by the config admin to a single trader.
+#[account]
+#[derive(InitSpace)]
+pub struct FeeDiscount {
+    pub trader: Pubkey,
+    pub discount_bps: u64,
+    pub bump: u8,
+}
+
+/// Swap volume of a trader in one pool, in token A base units, bucketed by day
+/// over the last `TRADER_STATS_WINDOW_DAYS` days.
+#[account]
+#[derive(InitSpace)]
+pub struct TraderStats {
+    pub pool: Pubkey,
+    pub owner: Pubkey,
+
+    /// Ring buffer indexed by day number modulo the window.
+    pub daily_volume: [u64; TRADER_STATS_WINDOW_DAYS],
+    /// Day number, unix time / 86400, of the most recent recorded swap.
+    pub last_day: i64,
+    pub total_volume: u64,
+
+    pub bump: u8,
+}
+
+/// Input of a hidden swap held in escrow until it is revealed or refunded.
+#[account]
+#[derive(InitSpace)]
+pub struct SwapCommit {
+    pub pool: Pubkey,
+    pub owner: Pubkey,
+
+    /// Hash of the swap parameters, see `swap_commitment_hash`.
+    pub hash: [u8; 32],
+    pub deposit: u64,
+    /// Direction implied by the deposited mint.
+    pub is_a_to_b: bool,
+    pub committed_slot: u64,
+
+    pub bump: u8,
+    pub escrow_bump: u8,
+}
+
+/// Swap order waiting in a batch. Output and refunds go to the recorded accounts.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, InitSpace)]
+pub struct BatchOrder {
+    pub owner: Pubkey,
+    pub token_in_account: Pubkey,
+    pub token_out_account: Pubkey,
+    pub amount_in: u64,
+    pub min_amount_out: u64,
+    pub is_a_to_b: bool,
+    /// Whether the order cleared at settlement.
+    pub filled: bool,
+    /// Output, or refund when not filled, that settlement could not pay to the
+    /// recorded account. It waits in the batch escrow of its mint for the owner.
+    pub claimable_amount: u64,
+}
+
+/// Swap orders collected over `start_slot..=end_slot`, settled together at one price.
+#[account]
+#[derive(InitSpace)]
+pub struct Batch {
+    pub pool: Pubkey,
+    pub batch_id: u64,
+    /// Payer of the batch and escrow rent, refunded once the batch is closed.
+    pub creator: Pubkey,
+
+    pub start_slot: u64,
+    pub end_slot: u64,
+
+    pub order_count: u8,
+    pub orders: [BatchOrder; MAX_BATCH_ORDERS],
+    /// Set by settlement. A settled batch stays open until every claim is paid.
+    pub settled: bool,
+
+    pub bump: u8,
+    pub escrow_a_bump: u8,
+    pub escrow_b_bump: u8,
+}
+
+/// Limit order whose input is held in escrow until a keeper fills it or the owner cancels.
+#[account]
+#[derive(InitSpace)]
+pub struct Order {
+    pub pool: Pubkey,
+    pub owner: Pubkey,
+    pub order_id: u64,
+    /// Receives the output of the fill.
+    pub token_out_account: Pubkey,
+
+    pub amount_in: u64,
+    /// Minimum output per unit of input, scaled by `PRICE_SCALE`.
+    pub target_price: u64,
+    pub is_a_to_b: bool,
+    /// Unix timestamp after which the order can no longer be filled.
+    pub expiry: i64,
+
+    pub bump: u8,
+    pub escrow_bump: u8,
+}
+
+/// Recurring swap of `amount_per_interval` from escrow, run by a permissionless crank.
+#[account]
+#[derive(InitSpace)]
+pub struct DcaOrder {
+    pub pool: Pubkey,
+    pub owner: Pubkey,
+    /// Receives the output of every fill.
+    pub token_out_account: Pubkey,
+    pub is_a_to_b: bool,
+
+    /// Input still held in escrow.
+    pub remaining_amount: u64,
+    pub amount_per_interval: u64,
+    /// Seconds between fills.
+    pub interval: i64,
+    /// Minimum output of a full `amount_per_interval` fill, scaled down for a smaller last fill.
+    pub min_amount_out: u64,
+    pub next_fill_at: i64,
+
+    pub fill_count: u64,
+    pub total_amount_in: u64,
+    pub total_amount_out: u64,
+
+    pub bump: u8,
+    pub escrow_bump: u8,
+}
+
+/// Price condition under which a trigger order fires.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum TriggerKind {
+    /// Fires once the output-per-input price falls to the trigger price or below.
+    StopLoss,
+    /// Fires once the output-per-input price rises to the trigger price or above.
+    TakeProfit,
+}
+
+/// Market swap held in escrow until the pool price crosses `trigger_price`.
+#[account]
+#[derive(InitSpace)]
+pub struct TriggerOrder {
+    pub pool: Pubkey,
+    pub owner: Pubkey,
+    /// Receives the output of the fill.
+    pub token_out_account: Pubkey,
+
+    pub amount_in: u64,
+    pub min_amount_out: u64,
+    pub is_a_to_b: bool,
+
+    pub kind: TriggerKind,
+    /// Output per unit of input, scaled by `PRICE_SCALE`.
+    pub trigger_price: u64,
+    /// Seconds of TWAP compared against `trigger_price`. Zero compares the spot price.
+    pub twap_window: i64,
+
+    pub bump: u8,
+    pub escrow_bump: u8,
+}
+
+/// Firm price from a market maker, signed with ed25519 over its Borsh encoding.
+/// Fills up to `amount_in` at `amount_out / amount_in`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct RfqQuote {
+    pub pool: Pubkey,
+    pub maker: Pubkey,
+    /// Only this account can take the quote.
+    pub taker: Pubkey,
+    pub is_a_to_b: bool,
+    pub amount_in: u64,
+    pub amount_out: u64,
+    /// Unix timestamp after which the quote can no longer be filled.
+    pub expiry: i64,
+    /// Chosen by the maker; each (maker, nonce) can be filled once.
+    pub nonce: u64,
+}
+
+/// Marks an RFQ quote as filled so it cannot be replayed. The taker can close it
+/// once the quote has expired, since an expired quote can no longer be filled.
+#[account]
+#[derive(InitSpace)]
+pub struct RfqQuoteFill {
+    pub maker: Pubkey,
+    pub nonce: u64,
+    pub bump: u8,
+    /// Paid the rent for this account and receives it back on close.
+    pub taker: Pubkey,
+    pub expiry: i64,
+}
@@ -4,20 +4,36 @@ import { Amm } from "../target/types/amm";
 import {
   ASSOCIATED_TOKEN_PROGRAM_ID,
   TOKEN_PROGRAM_ID,
+  approve,
+  closeAccount,
+  createAccount,
   createMint,
   getOrCreateAssociatedTokenAccount,
   getAssociatedTokenAddress,
   mintTo,
   getAccount,
+  getMint,
 } from "@solana/spl-token";
-import { Keypair, LAMPORTS_PER_SOL, PublicKey, SystemProgram } from "@solana/web3.js";
+import {
+  Ed25519Program,
+  Keypair,
+  LAMPORTS_PER_SOL,
+  PublicKey,
+  SystemProgram,
+  SYSVAR_INSTRUCTIONS_PUBKEY,
+  SYSVAR_RENT_PUBKEY,
+  Transaction,
+  TransactionInstruction,
+} from "@solana/web3.js";
 import { assert } from "chai";
+import { createHash } from "crypto";
 
 describe("AMM", () => {
   const provider = anchor.AnchorProvider.env();
   anchor.setProvider(provider);
   const program = anchor.workspace.Amm as Program<Amm>;
   const connection = provider.connection;
+  const [configPDA] = PublicKey.findProgramAddressSync([Buffer.from("amm_config")], program.programId);
 
   let admin: Keypair;
   let user1: Keypair;
@@ -30,6 +46,7 @@ describe("AMM", () => {
   let vaultAPDA: PublicKey;
   let vaultBPDA: PublicKey;
   let poolMintPDA: PublicKey;
+  let lpMetadataPDA: PublicKey;
   let user1TokenA: PublicKey;
   let user1TokenB: PublicKey;
   let user1LpToken: PublicKey;
@@ -42,6 +59,11 @@ describe("AMM", () => {
 
   const FEE_NUMERATOR = new BN(3);
   const FEE_DENOMINATOR = new BN(1000);
+  const REFERRAL_FEE_BPS = new BN(2000);
+  const MAX_PRICE_IMPACT_BPS = new BN(1000);
+  const LP_METADATA_URI = "https://example.com/amm-lp.json";
+  const TOKEN_METADATA_PROGRAM_ID = new PublicKey("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
+  const BPF_LOADER_UPGRADEABLE_ID = new PublicKey("BPFLoaderUpgradeab1e11111111111111111111111");
 
   async function confirmTx(signature: string) {
     const latestBlockhash = await connection.getLatestBlockhash();
@@ -51,6 +73,74 @@ describe("AMM", () => {
     });
   }
 
+  const metadataPDA = (mint: PublicKey) =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from("metadata"), TOKEN_METADATA_PROGRAM_ID.toBuffer(), mint.toBuffer()],
+      TOKEN_METADATA_PROGRAM_ID
+    )[0];
+
+  // Metaplex metadata layout: key, update authority, mint, then padded name, symbol and uri.
+  async function fetchMetadata(mint: PublicKey) {
+    const info = await connection.getAccountInfo(metadataPDA(mint));
+    assert.isNotNull(info, "Missing metadata account");
+    const data = info!.data;
+    let offset = 1;
+    const readPubkey = () => {
+      const key = new PublicKey(data.subarray(offset, offset + 32));
+      offset += 32;
+      return key;
+    };
+    const readString = () => {
+      const length = data.readUInt32LE(offset);
+      const value = data.subarray(offset + 4, offset + 4 + length).toString("utf8");
+      offset += 4 + length;
+      return value.replace(/\0/g, "");
+    };
+    const updateAuthority = readPubkey();
+    const metadataMint = readPubkey();
+    return { updateAuthority, mint: metadataMint, name: readString(), symbol: readString(), uri: readString() };
+  }
+
+  // CreateMetadataAccountV3 with the mint authority as payer and update authority.
+  async function createTokenMetadata(mint: PublicKey, authority: Keypair, symbol: string) {
+    const borshString = (value: string) => {
+      const length = Buffer.alloc(4);
+      length.writeUInt32LE(Buffer.byteLength(value));
+      return Buffer.concat([length, Buffer.from(value)]);
+    };
+    const data = Buffer.concat([
+      Buffer.from([33]),
+      borshString(`${symbol} Token`),
+      borshString(symbol),
+      borshString(""),
+      Buffer.from([0, 0]), // seller_fee_basis_points
+      Buffer.from([0, 0, 0]), // creators, collection, uses
+      Buffer.from([1]), // is_mutable
+      Buffer.from([0]), // collection_details
+    ]);
+    const instruction = new TransactionInstruction({
+      programId: TOKEN_METADATA_PROGRAM_ID,
+      keys: [
+        { pubkey: metadataPDA(mint), isSigner: false, isWritable: true },
+        { pubkey: mint, isSigner: false, isWritable: false },
+        { pubkey: authority.publicKey, isSigner: true, isWritable: false },
+        { pubkey: authority.publicKey, isSigner: true, isWritable: true },
+        { pubkey: authority.publicKey, isSigner: true, isWritable: false },
+        { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
+        { pubkey: SYSVAR_RENT_PUBKEY, isSigner: false, isWritable: false },
+      ],
+      data,
+    });
+    await provider.sendAndConfirm(new Transaction().add(instruction), [authority]);
+  }
+
+  function decodeReturnData(logs: string[], typeName: string): any {
+    const prefix = `Program return: ${program.programId.toBase58()} `;
+    const log = logs.filter((line) => line.startsWith(prefix)).pop();
+    assert.isDefined(log, "Missing return data");
+    return program.coder.types.decode(typeName, Buffer.from(log.slice(prefix.length), "base64"));
+  }
+
   it("Setup: Create test accounts and fund them", async () => {
     console.log("\n🚀 Setting up test environment...");
 
@@ -140,6 +230,11 @@ describe("AMM", () => {
       program.programId
     );
 
+    [lpMetadataPDA] = PublicKey.findProgramAddressSync(
+      [Buffer.from("metadata"), TOKEN_METADATA_PROGRAM_ID.toBuffer(), poolMintPDA.toBuffer()],
+      TOKEN_METADATA_PROGRAM_ID
+    );
+
     console.log("   PDAs derived");
     console.log("   Pool State:", poolStatePDA.toBase58());
     console.log("   Pool Authority:", poolAuthorityPDA.toBase58());
@@ -197,7 +292,7 @@ describe("AMM", () => {
 
     try {
       await program.methods
-        .initializePool(highFee, FEE_DENOMINATOR)
+        .initializePool(highFee, FEE_NUMERATOR, FEE_DENOMINATOR, REFERRAL_FEE_BPS, MAX_PRICE_IMPACT_BPS, LP_METADATA_URI, [])
         .accounts({
           payer: admin.publicKey,
           tokenMintA,
@@ -210,6 +305,11 @@ describe("AMM", () => {
           systemProgram: SystemProgram.programId,
           tokenProgram: TOKEN_PROGRAM_ID,
           associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          tokenMintAMetadata: null,
+          tokenMintBMetadata: null,
+          lpMetadata: lpMetadataPDA,
+          tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+          rent: SYSVAR_RENT_PUBKEY,
         })
         .signers([admin])
         .rpc();
@@ -230,7 +330,7 @@ describe("AMM", () => {
 
     try {
       await program.methods
-        .initializePool(FEE_NUMERATOR, new BN(0))
+        .initializePool(FEE_NUMERATOR, FEE_NUMERATOR, new BN(0), REFERRAL_FEE_BPS, MAX_PRICE_IMPACT_BPS, LP_METADATA_URI, [])
         .accounts({
           payer: admin.publicKey,
           tokenMintA,
@@ -243,6 +343,11 @@ describe("AMM", () => {
           systemProgram: SystemProgram.programId,
           tokenProgram: TOKEN_PROGRAM_ID,
           associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          tokenMintAMetadata: null,
+          tokenMintBMetadata: null,
+          lpMetadata: lpMetadataPDA,
+          tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+          rent: SYSVAR_RENT_PUBKEY,
         })
         .signers([admin])
         .rpc();
@@ -258,6 +363,49 @@ describe("AMM", () => {
     }
   });
 
+  it("Should reject pool initialization with decreasing fee tiers", async () => {
+    console.log("\n Testing fee tier validation...");
+
+    const feeTiers = [
+      { thresholdBps: new BN(100), feeNumerator: new BN(8) },
+      { thresholdBps: new BN(500), feeNumerator: new BN(5) },
+    ];
+
+    try {
+      await program.methods
+        .initializePool(FEE_NUMERATOR, FEE_NUMERATOR, FEE_DENOMINATOR, REFERRAL_FEE_BPS, MAX_PRICE_IMPACT_BPS, LP_METADATA_URI, feeTiers)
+        .accounts({
+          payer: admin.publicKey,
+          tokenMintA,
+          tokenMintB,
+          poolState: poolStatePDA,
+          poolAuthority: poolAuthorityPDA,
+          poolMint: poolMintPDA,
+          vaultA: vaultAPDA,
+          vaultB: vaultBPDA,
+          systemProgram: SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          tokenMintAMetadata: null,
+          tokenMintBMetadata: null,
+          lpMetadata: lpMetadataPDA,
+          tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+          rent: SYSVAR_RENT_PUBKEY,
+        })
+        .signers([admin])
+        .rpc();
+
+      assert.fail("Should have thrown error");
+    } catch (err: any) {
+      const errMsg = err.toString();
+      assert.isTrue(
+        errMsg.includes("InvalidFeeTiers") || errMsg.includes("6037"),
+        "Should fail with InvalidFeeTiers error"
+      );
+      console.log(" Correctly rejected decreasing fee tiers");
+    }
+  });
+
   it("Should reject pool with unordered mints", async () => {
     console.log("\n Testing mint ordering validation...");
 
@@ -276,6 +424,11 @@ describe("AMM", () => {
       program.programId
     );
 
+    const [wrongLpMetadata] = PublicKey.findProgramAddressSync(
+      [Buffer.from("metadata"), TOKEN_METADATA_PROGRAM_ID.toBuffer(), wrongPoolMint.toBuffer()],
+      TOKEN_METADATA_PROGRAM_ID
+    );
+
     const wrongVaultA = await getAssociatedTokenAddress(
       tokenMintB,
       wrongAuthority,
@@ -294,7 +447,7 @@ describe("AMM", () => {
 
     try {
       await program.methods
-        .initializePool(FEE_NUMERATOR, FEE_DENOMINATOR)
+        .initializePool(FEE_NUMERATOR, FEE_NUMERATOR, FEE_DENOMINATOR, REFERRAL_FEE_BPS, MAX_PRICE_IMPACT_BPS, LP_METADATA_URI, [])
         .accounts({
           payer: admin.publicKey,
           tokenMintA: tokenMintB,
@@ -307,6 +460,11 @@ describe("AMM", () => {
           systemProgram: SystemProgram.programId,
           tokenProgram: TOKEN_PROGRAM_ID,
           associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          tokenMintAMetadata: null,
+          tokenMintBMetadata: null,
+          lpMetadata: wrongLpMetadata,
+          tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+          rent: SYSVAR_RENT_PUBKEY,
         })
         .signers([admin])
         .rpc();
@@ -326,7 +484,7 @@ describe("AMM", () => {
     console.log("\n Initializing pool...");
 
     const tx = await program.methods
-      .initializePool(FEE_NUMERATOR, FEE_DENOMINATOR)
+      .initializePool(FEE_NUMERATOR, FEE_NUMERATOR, FEE_DENOMINATOR, REFERRAL_FEE_BPS, MAX_PRICE_IMPACT_BPS, LP_METADATA_URI, [])
       .accounts({
         payer: admin.publicKey,
         tokenMintA,
@@ -339,6 +497,11 @@ describe("AMM", () => {
         systemProgram: SystemProgram.programId,
         tokenProgram: TOKEN_PROGRAM_ID,
         associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        tokenMintAMetadata: null,
+        tokenMintBMetadata: null,
+        lpMetadata: lpMetadataPDA,
+        tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+        rent: SYSVAR_RENT_PUBKEY,
       })
       .signers([admin])
       .rpc();
@@ -348,23 +511,157 @@ describe("AMM", () => {
 
     const poolState = await program.account.poolState.fetch(poolStatePDA);
 
-    assert.equal(poolState.feeNumerator.toNumber(), 3);
+    assert.equal(poolState.feeNumeratorAToB.toNumber(), 3);
+    assert.equal(poolState.feeNumeratorBToA.toNumber(), 3);
     assert.equal(poolState.feeDenominator.toNumber(), 1000);
+    assert.equal(poolState.referralFeeBps.toNumber(), 2000);
+    assert.equal(poolState.maxPriceImpactBps.toNumber(), 1000);
     assert.equal(poolState.reserveA.toNumber(), 0);
     assert.equal(poolState.reserveB.toNumber(), 0);
     assert.equal(poolState.totalSupply.toNumber(), 0);
     assert.equal(poolState.tokenMintA.toBase58(), tokenMintA.toBase58());
     assert.equal(poolState.tokenMintB.toBase58(), tokenMintB.toBase58());
 
+    const poolMint = await getMint(connection, poolMintPDA);
+    assert.equal(poolMint.decimals, 9);
+    assert.equal(poolState.minimumLiquidity.toNumber(), 1000);
+
+    const lpMetadata = await connection.getAccountInfo(lpMetadataPDA);
+    assert.isNotNull(lpMetadata);
+    assert.equal(lpMetadata!.owner.toBase58(), TOKEN_METADATA_PROGRAM_ID.toBase58());
+
+    // Neither test mint has metadata, so both are labelled by their address prefix.
+    const metadata = await fetchMetadata(poolMintPDA);
+    assert.equal(
+      metadata.name,
+      `AMM-LP ${tokenMintA.toBase58().slice(0, 4)}/${tokenMintB.toBase58().slice(0, 4)}`
+    );
+    assert.equal(metadata.symbol, "AMM-LP");
+    assert.equal(metadata.uri, LP_METADATA_URI);
+    assert.ok(metadata.mint.equals(poolMintPDA));
+    assert.ok(metadata.updateAuthority.equals(poolAuthorityPDA));
+
     console.log(" Pool initialized with 0.3% fee");
   });
 
+  it("Should lock the minimum liquidity floor for a 6-decimal pair", async () => {
+    console.log("\n Testing minimum liquidity for 6-decimal mints...");
+
+    const mint1 = await createMint(connection, admin, admin.publicKey, null, 6, undefined, undefined, TOKEN_PROGRAM_ID);
+    const mint2 = await createMint(connection, admin, admin.publicKey, null, 6, undefined, undefined, TOKEN_PROGRAM_ID);
+    const [stableMintA, stableMintB] =
+      mint1.toBuffer().compare(mint2.toBuffer()) < 0 ? [mint1, mint2] : [mint2, mint1];
+
+    const [stablePool] = PublicKey.findProgramAddressSync(
+      [Buffer.from("pool_state"), stableMintA.toBuffer(), stableMintB.toBuffer()],
+      program.programId
+    );
+    const [stableAuthority] = PublicKey.findProgramAddressSync(
+      [Buffer.from("authority"), stablePool.toBuffer()],
+      program.programId
+    );
+    const [stablePoolMint] = PublicKey.findProgramAddressSync(
+      [Buffer.from("pool_mint"), stablePool.toBuffer()],
+      program.programId
+    );
+    const [stableLpMetadata] = PublicKey.findProgramAddressSync(
+      [Buffer.from("metadata"), TOKEN_METADATA_PROGRAM_ID.toBuffer(), stablePoolMint.toBuffer()],
+      TOKEN_METADATA_PROGRAM_ID
+    );
+
+    const tx = await program.methods
+      .initializePool(FEE_NUMERATOR, FEE_NUMERATOR, FEE_DENOMINATOR, REFERRAL_FEE_BPS, MAX_PRICE_IMPACT_BPS, LP_METADATA_URI, [])
+      .accounts({
+        payer: admin.publicKey,
+        tokenMintA: stableMintA,
+        tokenMintB: stableMintB,
+        poolState: stablePool,
+        poolAuthority: stableAuthority,
+        poolMint: stablePoolMint,
+        vaultA: await getAssociatedTokenAddress(stableMintA, stableAuthority, true, TOKEN_PROGRAM_ID, ASSOCIATED_TOKEN_PROGRAM_ID),
+        vaultB: await getAssociatedTokenAddress(stableMintB, stableAuthority, true, TOKEN_PROGRAM_ID, ASSOCIATED_TOKEN_PROGRAM_ID),
+        systemProgram: SystemProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        tokenMintAMetadata: null,
+        tokenMintBMetadata: null,
+        lpMetadata: stableLpMetadata,
+        tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+        rent: SYSVAR_RENT_PUBKEY,
+      })
+      .signers([admin])
+      .rpc();
+
+    await confirmTx(tx);
+
+    const poolState = await program.account.poolState.fetch(stablePool);
+    const poolMint = await getMint(connection, stablePoolMint);
+    assert.equal(poolMint.decimals, 6);
+    assert.equal(poolState.minimumLiquidity.toNumber(), 1000);
+    console.log(" 6-decimal LP mint locks", poolState.minimumLiquidity.toNumber(), "base units");
+  });
+
+  it("Should name the LP token after the pair's metadata symbols", async () => {
+    console.log("\n Testing LP token metadata...");
+
+    const mint1 = await createMint(connection, admin, admin.publicKey, null, 9, undefined, undefined, TOKEN_PROGRAM_ID);
+    const mint2 = await createMint(connection, admin, admin.publicKey, null, 9, undefined, undefined, TOKEN_PROGRAM_ID);
+    const [namedMintA, namedMintB] =
+      mint1.toBuffer().compare(mint2.toBuffer()) < 0 ? [mint1, mint2] : [mint2, mint1];
+    await createTokenMetadata(namedMintA, admin, "USDC");
+    await createTokenMetadata(namedMintB, admin, "SOL");
+
+    const [namedPool] = PublicKey.findProgramAddressSync(
+      [Buffer.from("pool_state"), namedMintA.toBuffer(), namedMintB.toBuffer()],
+      program.programId
+    );
+    const [namedAuthority] = PublicKey.findProgramAddressSync(
+      [Buffer.from("authority"), namedPool.toBuffer()],
+      program.programId
+    );
+    const [namedPoolMint] = PublicKey.findProgramAddressSync(
+      [Buffer.from("pool_mint"), namedPool.toBuffer()],
+      program.programId
+    );
+
+    const tx = await program.methods
+      .initializePool(FEE_NUMERATOR, FEE_NUMERATOR, FEE_DENOMINATOR, REFERRAL_FEE_BPS, MAX_PRICE_IMPACT_BPS, LP_METADATA_URI, [])
+      .accounts({
+        payer: admin.publicKey,
+        tokenMintA: namedMintA,
+        tokenMintB: namedMintB,
+        poolState: namedPool,
+        poolAuthority: namedAuthority,
+        poolMint: namedPoolMint,
+        vaultA: await getAssociatedTokenAddress(namedMintA, namedAuthority, true, TOKEN_PROGRAM_ID, ASSOCIATED_TOKEN_PROGRAM_ID),
+        vaultB: await getAssociatedTokenAddress(namedMintB, namedAuthority, true, TOKEN_PROGRAM_ID, ASSOCIATED_TOKEN_PROGRAM_ID),
+        systemProgram: SystemProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        tokenMintAMetadata: metadataPDA(namedMintA),
+        tokenMintBMetadata: metadataPDA(namedMintB),
+        lpMetadata: metadataPDA(namedPoolMint),
+        tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+        rent: SYSVAR_RENT_PUBKEY,
+      })
+      .signers([admin])
+      .rpc();
+    await confirmTx(tx);
+
+    const metadata = await fetchMetadata(namedPoolMint);
+    assert.equal(metadata.name, "AMM-LP USDC/SOL");
+    assert.equal(metadata.symbol, "AMM-LP");
+    assert.equal(metadata.uri, LP_METADATA_URI);
+    assert.ok(metadata.updateAuthority.equals(namedAuthority));
+    console.log(" LP token named", metadata.name);
+  });
+
   it("Should reject duplicate pool initialization", async () => {
     console.log("\n Testing duplicate pool initialization...");
 
     try {
       await program.methods
-        .initializePool(FEE_NUMERATOR, FEE_DENOMINATOR)
+        .initializePool(FEE_NUMERATOR, FEE_NUMERATOR, FEE_DENOMINATOR, REFERRAL_FEE_BPS, MAX_PRICE_IMPACT_BPS, LP_METADATA_URI, [])
         .accounts({
           payer: admin.publicKey,
           tokenMintA,
@@ -377,6 +674,11 @@ describe("AMM", () => {
           systemProgram: SystemProgram.programId,
           tokenProgram: TOKEN_PROGRAM_ID,
           associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          tokenMintAMetadata: null,
+          tokenMintBMetadata: null,
+          lpMetadata: lpMetadataPDA,
+          tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+          rent: SYSVAR_RENT_PUBKEY,
         })
         .signers([admin])
         .rpc();
@@ -582,6 +884,11 @@ describe("AMM", () => {
         userTokenA: user2TokenA,
         userTokenB: user2TokenB,
         tokenProgram: TOKEN_PROGRAM_ID,
+        config: null,
+        discountTokenAccount: null,
+        traderStats: null,
+        instructionsSysvar: null,
+        feeDiscount: null,
       })
       .signers([user2])
       .rpc();
@@ -615,6 +922,11 @@ describe("AMM", () => {
         userTokenA: user2TokenA,
         userTokenB: user2TokenB,
         tokenProgram: TOKEN_PROGRAM_ID,
+        config: null,
+        discountTokenAccount: null,
+        traderStats: null,
+        instructionsSysvar: null,
+        feeDiscount: null,
       })
       .signers([user2])
       .rpc();
@@ -652,6 +964,11 @@ describe("AMM", () => {
           userTokenA: user2TokenA,
           userTokenB: user2TokenB,
           tokenProgram: TOKEN_PROGRAM_ID,
+          config: null,
+          discountTokenAccount: null,
+          traderStats: null,
+          instructionsSysvar: null,
+          feeDiscount: null,
         })
         .signers([user2])
         .rpc();
@@ -667,6 +984,248 @@ describe("AMM", () => {
     }
   });
 
+  it("Should initialize the program config", async () => {
+    console.log("\n Testing config initialization...");
+
+    const [programDataPDA] = PublicKey.findProgramAddressSync(
+      [program.programId.toBuffer()],
+      BPF_LOADER_UPGRADEABLE_ID
+    );
+
+    const tx = await program.methods
+      .initializeConfig(admin.publicKey)
+      .accounts({
+        upgradeAuthority: provider.wallet.publicKey,
+        config: configPDA,
+        program: program.programId,
+        programData: programDataPDA,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+    await confirmTx(tx);
+
+    const config = await program.account.ammConfig.fetch(configPDA);
+    assert.ok(config.admin.equals(admin.publicKey));
+  });
+
+  it("Should reject price impact limit update from non-admin", async () => {
+    console.log("\n Testing price impact limit authority check...");
+
+    try {
+      await program.methods
+        .setMaxPriceImpact(new BN(0))
+        .accounts({
+          admin: user2.publicKey,
+          config: configPDA,
+          poolState: poolStatePDA,
+        })
+        .signers([user2])
+        .rpc();
+
+      assert.fail("Should have thrown error");
+    } catch (err: any) {
+      const errMsg = err.toString();
+      assert.isTrue(
+        errMsg.includes("Unauthorized") || errMsg.includes("6022"),
+        "Should fail with Unauthorized error"
+      );
+      console.log(" Correctly rejected non-admin update");
+    }
+  });
+
+  it("Should update price impact limit as admin", async () => {
+    console.log("\n Testing price impact limit update...");
+
+    const tx = await program.methods
+      .setMaxPriceImpact(new BN(2500))
+      .accounts({
+        admin: admin.publicKey,
+        config: configPDA,
+        poolState: poolStatePDA,
+      })
+      .signers([admin])
+      .rpc();
+
+    await confirmTx(tx);
+
+    let poolState = await program.account.poolState.fetch(poolStatePDA);
+    assert.equal(poolState.maxPriceImpactBps.toNumber(), 2500);
+
+    await program.methods
+      .setMaxPriceImpact(MAX_PRICE_IMPACT_BPS)
+      .accounts({
+        admin: admin.publicKey,
+        config: configPDA,
+        poolState: poolStatePDA,
+      })
+      .signers([admin])
+      .rpc();
+
+    poolState = await program.account.poolState.fetch(poolStatePDA);
+    assert.equal(poolState.maxPriceImpactBps.toNumber(), 1000);
+    console.log(" Price impact limit updated and restored");
+  });
+
+  it("Should charge a different fee per swap direction", async () => {
+    console.log("\n Testing direction-dependent fees...");
+
+    const configAccounts = { admin: admin.publicKey, config: configPDA, poolState: poolStatePDA };
+
+    const tx = await program.methods
+      .setFees(FEE_NUMERATOR, new BN(10))
+      .accounts(configAccounts)
+      .signers([admin])
+      .rpc();
+    await confirmTx(tx);
+
+    const amountIn = new BN(1_000_000);
+    const buyQuote = await program.methods
+      .quoteSwap(amountIn, true)
+      .accounts({
+        poolState: poolStatePDA,
+        feeDiscount: null,
+        config: null,
+        discountTokenAccount: null,
+        traderStats: null,
+        instructionsSysvar: null,
+      })
+      .view();
+    const sellQuote = await program.methods
+      .quoteSwap(amountIn, false)
+      .accounts({
+        poolState: poolStatePDA,
+        feeDiscount: null,
+        config: null,
+        discountTokenAccount: null,
+        traderStats: null,
+        instructionsSysvar: null,
+      })
+      .view();
+
+    assert.equal(buyQuote.feeAmount.toNumber(), 3_000);
+    assert.equal(sellQuote.feeAmount.toNumber(), 10_000);
+
+    const resetTx = await program.methods
+      .setFees(FEE_NUMERATOR, FEE_NUMERATOR)
+      .accounts(configAccounts)
+      .signers([admin])
+      .rpc();
+    await confirmTx(resetTx);
+    console.log(" A→B fee:", buyQuote.feeAmount.toNumber(), "B→A fee:", sellQuote.feeAmount.toNumber());
+  });
+
+  it("Should trip the circuit breaker on a large in-slot price move", async () => {
+    console.log("\n Testing per-slot circuit breaker...");
+
+    const configAccounts = { admin: admin.publicKey, config: configPDA, poolState: poolStatePDA };
+
+    const enableTx = await program.methods
+      .setMaxSlotPriceMove(new BN(1))
+      .accounts(configAccounts)
+      .signers([admin])
+      .rpc();
+    await confirmTx(enableTx);
+
+    try {
+      await program.methods
+        .swap(new BN(30_000_000), new BN(1), true)
+        .accounts({
+          user: user2.publicKey,
+          poolState: poolStatePDA,
+          poolAuthority: poolAuthorityPDA,
+          tokenMintA,
+          tokenMintB,
+          vaultA: vaultAPDA,
+          vaultB: vaultBPDA,
+          userTokenA: user2TokenA,
+          userTokenB: user2TokenB,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          config: null,
+          discountTokenAccount: null,
+          traderStats: null,
+          instructionsSysvar: null,
+          feeDiscount: null,
+        })
+        .signers([user2])
+        .rpc();
+
+      assert.fail("Should have thrown error");
+    } catch (err: any) {
+      const errMsg = err.toString();
+      assert.isTrue(
+        errMsg.includes("CircuitBreakerTripped") || errMsg.includes("6039"),
+        "Should fail with CircuitBreakerTripped error"
+      );
+      console.log(" Circuit breaker tripped");
+    }
+
+    const disableTx = await program.methods
+      .setMaxSlotPriceMove(new BN(0))
+      .accounts(configAccounts)
+      .signers([admin])
+      .rpc();
+    await confirmTx(disableTx);
+  });
+
+  it("Should reject opposite swaps in one transaction with sandwich protection", async () => {
+    console.log("\n Testing sandwich protection...");
+
+    const configAccounts = { admin: admin.publicKey, config: configPDA, poolState: poolStatePDA };
+
+    const enableTx = await program.methods
+      .setSandwichProtection(true, [])
+      .accounts(configAccounts)
+      .signers([admin])
+      .rpc();
+    await confirmTx(enableTx);
+
+    const swapAccounts = {
+      user: user2.publicKey,
+      poolState: poolStatePDA,
+      poolAuthority: poolAuthorityPDA,
+      tokenMintA,
+      tokenMintB,
+      vaultA: vaultAPDA,
+      vaultB: vaultBPDA,
+      userTokenA: user2TokenA,
+      userTokenB: user2TokenB,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      config: null,
+      discountTokenAccount: null,
+      traderStats: null,
+      feeDiscount: null,
+      instructionsSysvar: SYSVAR_INSTRUCTIONS_PUBKEY,
+    };
+
+    const buy = await program.methods
+      .swap(new BN(1_000_000), new BN(1), true)
+      .accounts(swapAccounts)
+      .instruction();
+    const sell = await program.methods
+      .swap(new BN(1_000_000), new BN(1), false)
+      .accounts(swapAccounts)
+      .instruction();
+
+    try {
+      await provider.sendAndConfirm(new Transaction().add(buy, sell), [user2]);
+      assert.fail("Should have thrown error");
+    } catch (err: any) {
+      const errMsg = err.toString() + JSON.stringify(err.logs ?? []);
+      assert.isTrue(
+        errMsg.includes("SandwichDetected") || errMsg.includes("0x1799"),
+        "Should fail with SandwichDetected error"
+      );
+      console.log(" Opposite swaps rejected");
+    }
+
+    const disableTx = await program.methods
+      .setSandwichProtection(false, [])
+      .accounts(configAccounts)
+      .signers([admin])
+      .rpc();
+    await confirmTx(disableTx);
+  });
+
   it("Should reject swap with excessive slippage", async () => {
     console.log("\n Testing slippage protection...");
 
@@ -684,6 +1243,11 @@ describe("AMM", () => {
           userTokenA: user2TokenA,
           userTokenB: user2TokenB,
           tokenProgram: TOKEN_PROGRAM_ID,
+          config: null,
+          discountTokenAccount: null,
+          traderStats: null,
+          instructionsSysvar: null,
+          feeDiscount: null,
         })
         .signers([user2])
         .rpc();
@@ -716,6 +1280,11 @@ describe("AMM", () => {
           userTokenA: user2TokenA,
           userTokenB: user2TokenB,
           tokenProgram: TOKEN_PROGRAM_ID,
+          config: null,
+          discountTokenAccount: null,
+          traderStats: null,
+          instructionsSysvar: null,
+          feeDiscount: null,
         })
         .signers([user2])
         .rpc();
@@ -751,6 +1320,11 @@ describe("AMM", () => {
           userTokenA: user2TokenA,
           userTokenB: user2TokenB,
           tokenProgram: TOKEN_PROGRAM_ID,
+          config: null,
+          discountTokenAccount: null,
+          traderStats: null,
+          instructionsSysvar: null,
+          feeDiscount: null,
         })
         .signers([user2])
         .rpc();
@@ -764,36 +1338,34 @@ describe("AMM", () => {
     console.log(" K growth:", ((kAfter - kBefore) / kBefore * 100).toFixed(3), "%");
   });
 
-  it("Should handle second liquidity provider", async () => {
-    console.log("\n Testing second LP...");
-
-    const lpAcc = await getOrCreateAssociatedTokenAccount(
-      connection, user2, poolMintPDA, user2.publicKey, false, undefined, undefined, TOKEN_PROGRAM_ID
-    );
-    user2LpToken = lpAcc.address;
+  it("Should pay referrer a share of the swap fee", async () => {
+    console.log("\n Testing referral fee payout...");
 
     const poolBefore = await program.account.poolState.fetch(poolStatePDA);
-    const lpSupplyBefore = poolBefore.totalSupply.toNumber();
-
-    const amountA = new BN(250_000_000);
+    const referrerBefore = await getAccount(connection, user3TokenA);
+    const amountIn = new BN(100_000_000);
 
     const tx = await program.methods
-      .addLiquidity(amountA, new BN(500_000_000), new BN(0), new BN(0), new BN(1))
+      .swapWithReferral(amountIn, new BN(1), true, null)
       .accounts({
-        user: user2.publicKey,
-        poolState: poolStatePDA,
-        poolAuthority: poolAuthorityPDA,
-        tokenMintA,
-        tokenMintB,
-        vaultA: vaultAPDA,
-        vaultB: vaultBPDA,
-        poolMint: poolMintPDA,
-        userTokenA: user2TokenA,
-        userTokenB: user2TokenB,
-        userLpToken: user2LpToken,
-        tokenProgram: TOKEN_PROGRAM_ID,
-        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
-        systemProgram: SystemProgram.programId,
+        swap: {
+          user: user2.publicKey,
+          poolState: poolStatePDA,
+          poolAuthority: poolAuthorityPDA,
+          tokenMintA,
+          tokenMintB,
+          vaultA: vaultAPDA,
+          vaultB: vaultBPDA,
+          userTokenA: user2TokenA,
+          userTokenB: user2TokenB,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          config: null,
+          discountTokenAccount: null,
+          traderStats: null,
+          instructionsSysvar: null,
+          feeDiscount: null,
+        },
+        referrerTokenAccount: user3TokenA,
       })
       .signers([user2])
       .rpc();
@@ -801,22 +1373,1730 @@ describe("AMM", () => {
     await confirmTx(tx);
 
     const poolAfter = await program.account.poolState.fetch(poolStatePDA);
-    const user2LpBalance = await getAccount(connection, user2LpToken);
+    const referrerAfter = await getAccount(connection, user3TokenA);
+    const referralPaid = Number(referrerAfter.amount) - Number(referrerBefore.amount);
 
-    assert.isTrue(poolAfter.totalSupply.toNumber() > lpSupplyBefore);
-    assert.isTrue(Number(user2LpBalance.amount) > 0);
+    const feeAmount = (100_000_000 * 3) / 1000;
+    const expectedReferral = (feeAmount * 2000) / 10000;
 
-    console.log(" Second LP added liquidity");
-    console.log(" User2 LP tokens:", Number(user2LpBalance.amount) / 1e9);
+    assert.equal(referralPaid, expectedReferral);
+    assert.equal(
+      poolAfter.reserveA.toNumber() - poolBefore.reserveA.toNumber(),
+      100_000_000 - expectedReferral
+    );
+    console.log(" Referrer received:", referralPaid / 1e9, "Token A");
   });
 
-  it("Should handle third liquidity provider", async () => {
-    console.log("\n Testing third LP...");
+  it("Should reject self-referral", async () => {
+    console.log("\n Testing self-referral rejection...");
 
-    const lpAcc = await getOrCreateAssociatedTokenAccount(
-      connection, user3, poolMintPDA, user3.publicKey, false, undefined, undefined, TOKEN_PROGRAM_ID
-    );
-    user3LpToken = lpAcc.address;
+    try {
+      await program.methods
+        .swapWithReferral(new BN(10_000_000), new BN(1), true, null)
+        .accounts({
+          swap: {
+            user: user2.publicKey,
+            poolState: poolStatePDA,
+            poolAuthority: poolAuthorityPDA,
+            tokenMintA,
+            tokenMintB,
+            vaultA: vaultAPDA,
+            vaultB: vaultBPDA,
+            userTokenA: user2TokenA,
+            userTokenB: user2TokenB,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            config: null,
+            discountTokenAccount: null,
+            traderStats: null,
+            instructionsSysvar: null,
+            feeDiscount: null,
+          },
+          referrerTokenAccount: user2TokenA,
+        })
+        .signers([user2])
+        .rpc();
+
+      assert.fail("Should have thrown error");
+    } catch (err: any) {
+      const errMsg = err.toString();
+      assert.isTrue(
+        errMsg.includes("InvalidReferrer") || errMsg.includes("6027"),
+        "Should fail with InvalidReferrer error"
+      );
+      console.log(" Correctly rejected self-referral");
+    }
+  });
+
+  it("Should reject referral swap past its deadline", async () => {
+    console.log("\n Testing referral swap deadline...");
+
+    const expired = new BN(Math.floor(Date.now() / 1000) - 3600);
+
+    try {
+      await program.methods
+        .swapWithReferral(new BN(10_000_000), new BN(1), true, expired)
+        .accounts({
+          swap: {
+            user: user2.publicKey,
+            poolState: poolStatePDA,
+            poolAuthority: poolAuthorityPDA,
+            tokenMintA,
+            tokenMintB,
+            vaultA: vaultAPDA,
+            vaultB: vaultBPDA,
+            userTokenA: user2TokenA,
+            userTokenB: user2TokenB,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            config: null,
+            discountTokenAccount: null,
+            traderStats: null,
+            instructionsSysvar: null,
+            feeDiscount: null,
+          },
+          referrerTokenAccount: user3TokenA,
+        })
+        .signers([user2])
+        .rpc();
+
+      assert.fail("Should have thrown error");
+    } catch (err: any) {
+      const errMsg = err.toString();
+      assert.isTrue(
+        errMsg.includes("DeadlineExceeded") || errMsg.includes("6028"),
+        "Should fail with DeadlineExceeded error"
+      );
+      console.log(" Correctly rejected expired referral swap");
+    }
+  });
+
+  it("Should reject swap past its deadline", async () => {
+    console.log("\n Testing swap deadline...");
+
+    const expired = new BN(Math.floor(Date.now() / 1000) - 3600);
+
+    try {
+      await program.methods
+        .swapWithDeadline(new BN(10_000_000), new BN(1), true, expired)
+        .accounts({
+          user: user2.publicKey,
+          poolState: poolStatePDA,
+          poolAuthority: poolAuthorityPDA,
+          tokenMintA,
+          tokenMintB,
+          vaultA: vaultAPDA,
+          vaultB: vaultBPDA,
+          userTokenA: user2TokenA,
+          userTokenB: user2TokenB,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          config: null,
+          discountTokenAccount: null,
+          traderStats: null,
+          instructionsSysvar: null,
+          feeDiscount: null,
+        })
+        .signers([user2])
+        .rpc();
+
+      assert.fail("Should have thrown error");
+    } catch (err: any) {
+      const errMsg = err.toString();
+      assert.isTrue(
+        errMsg.includes("DeadlineExceeded") || errMsg.includes("6028"),
+        "Should fail with DeadlineExceeded error"
+      );
+      console.log(" Correctly rejected expired swap");
+    }
+  });
+
+  it("Should swap before its deadline", async () => {
+    console.log("\n Testing swap within deadline...");
+
+    const balanceBefore = await getAccount(connection, user2TokenB);
+    const deadline = new BN(Math.floor(Date.now() / 1000) + 3600);
+
+    const tx = await program.methods
+      .swapWithDeadline(new BN(10_000_000), new BN(1), true, deadline)
+      .accounts({
+        user: user2.publicKey,
+        poolState: poolStatePDA,
+        poolAuthority: poolAuthorityPDA,
+        tokenMintA,
+        tokenMintB,
+        vaultA: vaultAPDA,
+        vaultB: vaultBPDA,
+        userTokenA: user2TokenA,
+        userTokenB: user2TokenB,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        config: null,
+        discountTokenAccount: null,
+        traderStats: null,
+        instructionsSysvar: null,
+        feeDiscount: null,
+      })
+      .signers([user2])
+      .rpc();
+
+    await confirmTx(tx);
+
+    const balanceAfter = await getAccount(connection, user2TokenB);
+    assert.isTrue(Number(balanceAfter.amount) > Number(balanceBefore.amount));
+    console.log(" Swap executed before deadline");
+  });
+
+  it("Should quote a swap that matches the executed amount", async () => {
+    console.log("\n Testing swap quote...");
+
+    const amountIn = new BN(30_000_000);
+    const quote = await program.methods
+      .quoteSwap(amountIn, true)
+      .accounts({
+        poolState: poolStatePDA,
+        feeDiscount: null,
+        config: null,
+        discountTokenAccount: null,
+        traderStats: null,
+        instructionsSysvar: null,
+      })
+      .view();
+
+    const balanceBefore = await getAccount(connection, user2TokenB);
+
+    const tx = await program.methods
+      .swap(amountIn, quote.amountOut, true)
+      .accounts({
+        user: user2.publicKey,
+        poolState: poolStatePDA,
+        poolAuthority: poolAuthorityPDA,
+        tokenMintA,
+        tokenMintB,
+        vaultA: vaultAPDA,
+        vaultB: vaultBPDA,
+        userTokenA: user2TokenA,
+        userTokenB: user2TokenB,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        config: null,
+        discountTokenAccount: null,
+        traderStats: null,
+        instructionsSysvar: null,
+        feeDiscount: null,
+      })
+      .signers([user2])
+      .rpc();
+
+    await confirmTx(tx);
+
+    const balanceAfter = await getAccount(connection, user2TokenB);
+    const received = Number(balanceAfter.amount) - Number(balanceBefore.amount);
+    const poolAfter = await program.account.poolState.fetch(poolStatePDA);
+
+    assert.equal(received, quote.amountOut.toNumber());
+    assert.equal(poolAfter.reserveA.toNumber(), quote.reserveAAfter.toNumber());
+    assert.equal(poolAfter.reserveB.toNumber(), quote.reserveBAfter.toNumber());
+    console.log(" Quote matched:", received / 1e9, "Token B");
+  });
+
+  it("Should return the executed amounts from swap and liquidity instructions", async () => {
+    console.log("\n Testing return data...");
+
+    const swap = () =>
+      program.methods
+        .swap(new BN(5_000_000), new BN(1), true)
+        .accounts({
+          user: user2.publicKey,
+          poolState: poolStatePDA,
+          poolAuthority: poolAuthorityPDA,
+          tokenMintA,
+          tokenMintB,
+          vaultA: vaultAPDA,
+          vaultB: vaultBPDA,
+          userTokenA: user2TokenA,
+          userTokenB: user2TokenB,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          config: null,
+          discountTokenAccount: null,
+          traderStats: null,
+          instructionsSysvar: null,
+          feeDiscount: null,
+        })
+        .signers([user2]);
+
+    const swapResult = decodeReturnData((await swap().simulate()).raw, "SwapResult");
+    const user2ABefore = await getAccount(connection, user2TokenA);
+    const user2BBefore = await getAccount(connection, user2TokenB);
+    await confirmTx(await swap().rpc());
+    const user2AAfter = await getAccount(connection, user2TokenA);
+    const user2BAfter = await getAccount(connection, user2TokenB);
+
+    assert.equal(Number(user2ABefore.amount) - Number(user2AAfter.amount), swapResult.amountIn.toNumber());
+    assert.equal(Number(user2BAfter.amount) - Number(user2BBefore.amount), swapResult.amountOut.toNumber());
+
+    const liquidityAccounts = {
+      user: user1.publicKey,
+      poolState: poolStatePDA,
+      poolAuthority: poolAuthorityPDA,
+      tokenMintA,
+      tokenMintB,
+      vaultA: vaultAPDA,
+      vaultB: vaultBPDA,
+      poolMint: poolMintPDA,
+      userTokenA: user1TokenA,
+      userTokenB: user1TokenB,
+      userLpToken: user1LpToken,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      systemProgram: SystemProgram.programId,
+    };
+    const balances = async () => [
+      Number((await getAccount(connection, user1TokenA)).amount),
+      Number((await getAccount(connection, user1TokenB)).amount),
+      Number((await getAccount(connection, user1LpToken)).amount),
+    ];
+
+    const addLiquidity = () =>
+      program.methods
+        .addLiquidity(new BN(100_000_000), new BN(300_000_000), new BN(0), new BN(0), new BN(1))
+        .accounts(liquidityAccounts)
+        .signers([user1]);
+
+    const addResult = decodeReturnData((await addLiquidity().simulate()).raw, "AddLiquidityResult");
+    const [addABefore, addBBefore, addLpBefore] = await balances();
+    await confirmTx(await addLiquidity().rpc());
+    const [addAAfter, addBAfter, addLpAfter] = await balances();
+
+    assert.equal(addABefore - addAAfter, addResult.amountA.toNumber());
+    assert.equal(addBBefore - addBAfter, addResult.amountB.toNumber());
+    assert.equal(addLpAfter - addLpBefore, addResult.lpTokensMinted.toNumber());
+
+    const removeLiquidity = () =>
+      program.methods
+        .removeLiquidity(addResult.lpTokensMinted, new BN(1), new BN(1))
+        .accounts(liquidityAccounts)
+        .signers([user1]);
+
+    const removeResult = decodeReturnData((await removeLiquidity().simulate()).raw, "RemoveLiquidityResult");
+    const [removeABefore, removeBBefore, removeLpBefore] = await balances();
+    await confirmTx(await removeLiquidity().rpc());
+    const [removeAAfter, removeBAfter, removeLpAfter] = await balances();
+
+    assert.equal(removeAAfter - removeABefore, removeResult.amountA.toNumber());
+    assert.equal(removeBAfter - removeBBefore, removeResult.amountB.toNumber());
+    assert.equal(removeLpBefore - removeLpAfter, removeResult.lpTokensBurned.toNumber());
+    console.log(" Return data matches balance changes");
+  });
+
+  it("Should waive the fee for a trader with a full fee discount", async () => {
+    console.log("\n Testing trader fee discount...");
+
+    const [feeDiscountPDA] = PublicKey.findProgramAddressSync(
+      [Buffer.from("fee_discount"), user2.publicKey.toBuffer()],
+      program.programId
+    );
+
+    const discountTx = await program.methods
+      .setFeeDiscount(user2.publicKey, new BN(10_000))
+      .accounts({
+        admin: admin.publicKey,
+        config: configPDA,
+        feeDiscount: feeDiscountPDA,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([admin])
+      .rpc();
+    await confirmTx(discountTx);
+
+    const amountIn = new BN(10_000_000);
+    const quote = await program.methods
+      .quoteSwap(amountIn, true)
+      .accounts({
+        poolState: poolStatePDA,
+        feeDiscount: null,
+        config: null,
+        discountTokenAccount: null,
+        traderStats: null,
+        instructionsSysvar: null,
+      })
+      .view();
+
+    const discountedQuote = await program.methods
+      .quoteSwap(amountIn, true)
+      .accounts({
+        poolState: poolStatePDA,
+        feeDiscount: feeDiscountPDA,
+        config: null,
+        discountTokenAccount: null,
+        traderStats: null,
+        instructionsSysvar: null,
+      })
+      .view();
+    assert.equal(discountedQuote.feeAmount.toNumber(), 0);
+
+    const balanceBefore = await getAccount(connection, user2TokenB);
+
+    const tx = await program.methods
+      .swap(amountIn, quote.amountOut, true)
+      .accounts({
+        user: user2.publicKey,
+        poolState: poolStatePDA,
+        poolAuthority: poolAuthorityPDA,
+        tokenMintA,
+        tokenMintB,
+        vaultA: vaultAPDA,
+        vaultB: vaultBPDA,
+        userTokenA: user2TokenA,
+        userTokenB: user2TokenB,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        config: null,
+        discountTokenAccount: null,
+        traderStats: null,
+        instructionsSysvar: null,
+        feeDiscount: feeDiscountPDA,
+      })
+      .signers([user2])
+      .rpc();
+    await confirmTx(tx);
+
+    const balanceAfter = await getAccount(connection, user2TokenB);
+    const received = Number(balanceAfter.amount) - Number(balanceBefore.amount);
+    assert.isAbove(received, quote.amountOut.toNumber());
+    assert.equal(received, discountedQuote.amountOut.toNumber());
+
+    const removeTx = await program.methods
+      .removeFeeDiscount()
+      .accounts({
+        admin: admin.publicKey,
+        config: configPDA,
+        feeDiscount: feeDiscountPDA,
+      })
+      .signers([admin])
+      .rpc();
+    await confirmTx(removeTx);
+    console.log(" Discounted swap received:", received / 1e9, "Token B");
+  });
+
+  it("Should discount the fee for governance token holders", async () => {
+    console.log("\n Testing holder fee discount...");
+
+    const governanceMint = await createMint(
+      connection,
+      admin,
+      admin.publicKey,
+      null,
+      6,
+      undefined,
+      undefined,
+      TOKEN_PROGRAM_ID
+    );
+    const user2Governance = await getOrCreateAssociatedTokenAccount(connection, user2, governanceMint, user2.publicKey);
+    await mintTo(connection, admin, governanceMint, user2Governance.address, admin, 5_000_000);
+
+    const tiers = [
+      { minBalance: new BN(1_000_000), discountBps: new BN(2_500) },
+      { minBalance: new BN(10_000_000), discountBps: new BN(5_000) },
+    ];
+
+    const configTx = await program.methods
+      .setHolderDiscount(governanceMint, tiers)
+      .accounts({ admin: admin.publicKey, config: configPDA })
+      .signers([admin])
+      .rpc();
+    await confirmTx(configTx);
+
+    const amountIn = new BN(10_000_000);
+    const quote = await program.methods
+      .quoteSwap(amountIn, true)
+      .accounts({
+        poolState: poolStatePDA,
+        feeDiscount: null,
+        config: null,
+        discountTokenAccount: null,
+        traderStats: null,
+        instructionsSysvar: null,
+      })
+      .view();
+
+    const balanceBefore = await getAccount(connection, user2TokenB);
+
+    const tx = await program.methods
+      .swap(amountIn, quote.amountOut, true)
+      .accounts({
+        user: user2.publicKey,
+        poolState: poolStatePDA,
+        poolAuthority: poolAuthorityPDA,
+        tokenMintA,
+        tokenMintB,
+        vaultA: vaultAPDA,
+        vaultB: vaultBPDA,
+        userTokenA: user2TokenA,
+        userTokenB: user2TokenB,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        config: configPDA,
+        discountTokenAccount: user2Governance.address,
+        traderStats: null,
+        instructionsSysvar: null,
+        feeDiscount: null,
+      })
+      .signers([user2])
+      .rpc();
+    await confirmTx(tx);
+
+    const balanceAfter = await getAccount(connection, user2TokenB);
+    const received = Number(balanceAfter.amount) - Number(balanceBefore.amount);
+    assert.isAbove(received, quote.amountOut.toNumber());
+    console.log(" Holder swap received:", received / 1e9, "Token B");
+  });
+
+  it("Should track trader volume and apply volume discounts", async () => {
+    console.log("\n Testing volume-tiered fees...");
+
+    const [traderStatsPDA] = PublicKey.findProgramAddressSync(
+      [Buffer.from("trader_stats"), poolStatePDA.toBuffer(), user2.publicKey.toBuffer()],
+      program.programId
+    );
+
+    const openTx = await program.methods
+      .openTraderStats()
+      .accounts({
+        user: user2.publicKey,
+        poolState: poolStatePDA,
+        traderStats: traderStatsPDA,
+        instructionsSysvar: null,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([user2])
+      .rpc();
+    await confirmTx(openTx);
+
+    const tiers = [{ minVolume: new BN(10_000_000), discountBps: new BN(5_000) }];
+    try {
+      await program.methods
+        .setVolumeDiscount(tiers)
+        .accounts({ admin: user2.publicKey, config: configPDA, poolState: poolStatePDA })
+        .signers([user2])
+        .rpc();
+      assert.fail("Should have thrown error");
+    } catch (err: any) {
+      const errMsg = err.toString();
+      assert.isTrue(
+        errMsg.includes("Unauthorized") || errMsg.includes("6022"),
+        "Should fail with Unauthorized error"
+      );
+    }
+
+    const tiersTx = await program.methods
+      .setVolumeDiscount(tiers)
+      .accounts({ admin: admin.publicKey, config: configPDA, poolState: poolStatePDA })
+      .signers([admin])
+      .rpc();
+    await confirmTx(tiersTx);
+
+    const swapAccounts = {
+      user: user2.publicKey,
+      poolState: poolStatePDA,
+      poolAuthority: poolAuthorityPDA,
+      tokenMintA,
+      tokenMintB,
+      vaultA: vaultAPDA,
+      vaultB: vaultBPDA,
+      userTokenA: user2TokenA,
+      userTokenB: user2TokenB,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      config: null,
+      discountTokenAccount: null,
+      traderStats: traderStatsPDA,
+      instructionsSysvar: null,
+      feeDiscount: null,
+    };
+
+    const amountIn = new BN(10_000_000);
+    const firstTx = await program.methods
+      .swap(amountIn, new BN(1), true)
+      .accounts(swapAccounts)
+      .signers([user2])
+      .rpc();
+    await confirmTx(firstTx);
+
+    const stats = await program.account.traderStats.fetch(traderStatsPDA);
+    assert.equal(stats.totalVolume.toNumber(), 10_000_000);
+
+    const quote = await program.methods
+      .quoteSwap(amountIn, true)
+      .accounts({
+        poolState: poolStatePDA,
+        feeDiscount: null,
+        config: null,
+        discountTokenAccount: null,
+        traderStats: null,
+        instructionsSysvar: null,
+      })
+      .view();
+    const balanceBefore = await getAccount(connection, user2TokenB);
+
+    const secondTx = await program.methods
+      .swap(amountIn, quote.amountOut, true)
+      .accounts(swapAccounts)
+      .signers([user2])
+      .rpc();
+    await confirmTx(secondTx);
+
+    const balanceAfter = await getAccount(connection, user2TokenB);
+    const received = Number(balanceAfter.amount) - Number(balanceBefore.amount);
+    assert.isAbove(received, quote.amountOut.toNumber());
+    console.log(" Volume-discounted swap received:", received / 1e9, "Token B");
+  });
+
+  it("Should commit and reveal a swap", async () => {
+    console.log("\n Testing commit-reveal swap...");
+
+    const amountIn = new BN(5_000_000);
+    const minAmountOut = new BN(1);
+    const deposit = new BN(8_000_000);
+    const salt = Keypair.generate().publicKey.toBuffer();
+    const hash = createHash("sha256")
+      .update(amountIn.toArrayLike(Buffer, "le", 8))
+      .update(minAmountOut.toArrayLike(Buffer, "le", 8))
+      .update(salt)
+      .update(user2.publicKey.toBuffer())
+      .digest();
+
+    const [commitPDA] = PublicKey.findProgramAddressSync(
+      [Buffer.from("swap_commit"), poolStatePDA.toBuffer(), user2.publicKey.toBuffer(), hash],
+      program.programId
+    );
+    const [commitEscrowPDA] = PublicKey.findProgramAddressSync(
+      [Buffer.from("commit_escrow"), commitPDA.toBuffer()],
+      program.programId
+    );
+
+    const commitTx = await program.methods
+      .commitSwap(Array.from(hash), deposit)
+      .accounts({
+        user: user2.publicKey,
+        poolState: poolStatePDA,
+        depositMint: tokenMintA,
+        commit: commitPDA,
+        commitEscrow: commitEscrowPDA,
+        userToken: user2TokenA,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([user2])
+      .rpc();
+    await confirmTx(commitTx);
+
+    const escrow = await getAccount(connection, commitEscrowPDA);
+    assert.equal(Number(escrow.amount), deposit.toNumber());
+
+    await new Promise((resolve) => setTimeout(resolve, 2000));
+
+    const tokenABefore = await getAccount(connection, user2TokenA);
+
+    const revealTx = await program.methods
+      .revealSwap(amountIn, minAmountOut, Array.from(salt))
+      .accounts({
+        swap: {
+          user: user2.publicKey,
+          poolState: poolStatePDA,
+          poolAuthority: poolAuthorityPDA,
+          tokenMintA,
+          tokenMintB,
+          vaultA: vaultAPDA,
+          vaultB: vaultBPDA,
+          userTokenA: user2TokenA,
+          userTokenB: user2TokenB,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          config: null,
+          discountTokenAccount: null,
+          traderStats: null,
+          feeDiscount: null,
+          instructionsSysvar: null,
+        },
+        commit: commitPDA,
+        commitEscrow: commitEscrowPDA,
+      })
+      .signers([user2])
+      .rpc();
+    await confirmTx(revealTx);
+
+    const tokenAAfter = await getAccount(connection, user2TokenA);
+    assert.equal(
+      Number(tokenAAfter.amount) - Number(tokenABefore.amount),
+      deposit.toNumber() - amountIn.toNumber()
+    );
+    assert.isNull(await connection.getAccountInfo(commitPDA));
+    console.log(" Revealed swap of", amountIn.toNumber() / 1e9, "Token A");
+  });
+
+  it("Should settle a batch auction at a uniform clearing price", async () => {
+    console.log("\n Testing batch auction...");
+
+    await program.methods
+      .setBatchAuction(new BN(2))
+      .accounts({
+        admin: admin.publicKey,
+        config: configPDA,
+        poolState: poolStatePDA,
+      })
+      .signers([admin])
+      .rpc();
+
+    let poolState = await program.account.poolState.fetch(poolStatePDA);
+    const [batchPDA] = PublicKey.findProgramAddressSync(
+      [Buffer.from("batch"), poolStatePDA.toBuffer(), poolState.currentBatchId.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    );
+    const [batchEscrowAPDA] = PublicKey.findProgramAddressSync(
+      [Buffer.from("batch_escrow_a"), batchPDA.toBuffer()],
+      program.programId
+    );
+    const [batchEscrowBPDA] = PublicKey.findProgramAddressSync(
+      [Buffer.from("batch_escrow_b"), batchPDA.toBuffer()],
+      program.programId
+    );
+
+    const orders = [
+      { user: user2, tokenIn: user2TokenA, tokenOut: user2TokenB, amountIn: new BN(4_000_000), isAToB: true },
+      { user: user3, tokenIn: user3TokenB, tokenOut: user3TokenA, amountIn: new BN(3_000_000), isAToB: false },
+    ];
+
+    for (const order of orders) {
+      const tx = await program.methods
+        .submitBatchOrder(order.amountIn, new BN(1), order.isAToB)
+        .accounts({
+          user: order.user.publicKey,
+          poolState: poolStatePDA,
+          tokenMintA,
+          tokenMintB,
+          batch: batchPDA,
+          batchEscrowA: batchEscrowAPDA,
+          batchEscrowB: batchEscrowBPDA,
+          userTokenA: order.isAToB ? order.tokenIn : order.tokenOut,
+          userTokenB: order.isAToB ? order.tokenOut : order.tokenIn,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([order.user])
+        .rpc();
+      await confirmTx(tx);
+    }
+
+    try {
+      await program.methods
+        .swap(new BN(1_000_000), new BN(1), true)
+        .accounts({
+          user: user1.publicKey,
+          poolState: poolStatePDA,
+          poolAuthority: poolAuthorityPDA,
+          tokenMintA,
+          tokenMintB,
+          vaultA: vaultAPDA,
+          vaultB: vaultBPDA,
+          userTokenA: user1TokenA,
+          userTokenB: user1TokenB,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          config: null,
+          discountTokenAccount: null,
+          traderStats: null,
+          feeDiscount: null,
+          instructionsSysvar: null,
+        })
+        .signers([user1])
+        .rpc();
+      assert.fail("Should have thrown error");
+    } catch (err: any) {
+      const errMsg = err.toString();
+      assert.isTrue(
+        errMsg.includes("BatchAuctionOnly") || errMsg.includes("6048"),
+        "Should fail with BatchAuctionOnly error"
+      );
+    }
+
+    try {
+      await program.methods
+        .quoteSwap(new BN(1_000_000), true)
+        .accounts({
+          poolState: poolStatePDA,
+          feeDiscount: null,
+          config: null,
+          discountTokenAccount: null,
+          traderStats: null,
+          instructionsSysvar: null,
+        })
+        .view();
+      assert.fail("Should have thrown error");
+    } catch (err: any) {
+      const errMsg = err.toString() + JSON.stringify(err.simulationResponse?.logs ?? []);
+      assert.isTrue(
+        errMsg.includes("BatchAuctionOnly") || errMsg.includes("6048"),
+        "Quote should fail with BatchAuctionOnly error"
+      );
+    }
+
+    await new Promise((resolve) => setTimeout(resolve, 2000));
+
+    const user2BBefore = await getAccount(connection, user2TokenB);
+    const user3ABefore = await getAccount(connection, user3TokenA);
+
+    const settleTx = await program.methods
+      .settleBatch()
+      .accounts({
+        settler: user1.publicKey,
+        poolState: poolStatePDA,
+        poolAuthority: poolAuthorityPDA,
+        tokenMintA,
+        tokenMintB,
+        vaultA: vaultAPDA,
+        vaultB: vaultBPDA,
+        batch: batchPDA,
+        batchEscrowA: batchEscrowAPDA,
+        batchEscrowB: batchEscrowBPDA,
+        creator: user2.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .remainingAccounts(
+        orders.flatMap((order) => [
+          { pubkey: order.tokenIn, isSigner: false, isWritable: true },
+          { pubkey: order.tokenOut, isSigner: false, isWritable: true },
+        ])
+      )
+      .signers([user1])
+      .rpc();
+    await confirmTx(settleTx);
+
+    const user2BAfter = await getAccount(connection, user2TokenB);
+    const user3AAfter = await getAccount(connection, user3TokenA);
+    assert.isAbove(Number(user2BAfter.amount), Number(user2BBefore.amount));
+    assert.isAbove(Number(user3AAfter.amount), Number(user3ABefore.amount));
+    assert.isNull(await connection.getAccountInfo(batchPDA));
+
+    await program.methods
+      .setBatchAuction(new BN(0))
+      .accounts({
+        admin: admin.publicKey,
+        config: configPDA,
+        poolState: poolStatePDA,
+      })
+      .signers([admin])
+      .rpc();
+
+    poolState = await program.account.poolState.fetch(poolStatePDA);
+    assert.equal(poolState.batchAuctionSlots.toNumber(), 0);
+    assert.equal(poolState.currentBatchId.toNumber(), 1);
+    console.log(" Batch settled for both directions");
+  });
+
+  it("Should keep an undeliverable batch payout claimable", async () => {
+    console.log("\n Testing batch payout to a closed account...");
+
+    await program.methods
+      .setBatchAuction(new BN(2))
+      .accounts({
+        admin: admin.publicKey,
+        config: configPDA,
+        poolState: poolStatePDA,
+      })
+      .signers([admin])
+      .rpc();
+
+    const poolState = await program.account.poolState.fetch(poolStatePDA);
+    const [batchPDA] = PublicKey.findProgramAddressSync(
+      [Buffer.from("batch"), poolStatePDA.toBuffer(), poolState.currentBatchId.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    );
+    const [batchEscrowAPDA] = PublicKey.findProgramAddressSync(
+      [Buffer.from("batch_escrow_a"), batchPDA.toBuffer()],
+      program.programId
+    );
+    const [batchEscrowBPDA] = PublicKey.findProgramAddressSync(
+      [Buffer.from("batch_escrow_b"), batchPDA.toBuffer()],
+      program.programId
+    );
+
+    const outputAccount = await createAccount(connection, user2, tokenMintB, user2.publicKey, Keypair.generate());
+
+    const submitTx = await program.methods
+      .submitBatchOrder(new BN(2_000_000), new BN(1), true)
+      .accounts({
+        user: user2.publicKey,
+        poolState: poolStatePDA,
+        tokenMintA,
+        tokenMintB,
+        batch: batchPDA,
+        batchEscrowA: batchEscrowAPDA,
+        batchEscrowB: batchEscrowBPDA,
+        userTokenA: user2TokenA,
+        userTokenB: outputAccount,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([user2])
+      .rpc();
+    await confirmTx(submitTx);
+
+    await closeAccount(connection, user2, outputAccount, user2.publicKey, user2);
+    await new Promise((resolve) => setTimeout(resolve, 2000));
+
+    const settleTx = await program.methods
+      .settleBatch()
+      .accounts({
+        settler: user1.publicKey,
+        poolState: poolStatePDA,
+        poolAuthority: poolAuthorityPDA,
+        tokenMintA,
+        tokenMintB,
+        vaultA: vaultAPDA,
+        vaultB: vaultBPDA,
+        batch: batchPDA,
+        batchEscrowA: batchEscrowAPDA,
+        batchEscrowB: batchEscrowBPDA,
+        creator: user2.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .remainingAccounts([
+        { pubkey: user2TokenA, isSigner: false, isWritable: true },
+        { pubkey: outputAccount, isSigner: false, isWritable: true },
+      ])
+      .signers([user1])
+      .rpc();
+    await confirmTx(settleTx);
+
+    const batch = await program.account.batch.fetch(batchPDA);
+    assert.isTrue(batch.settled);
+    assert.isTrue(batch.orders[0].filled);
+    const claimable = batch.orders[0].claimableAmount.toNumber();
+    assert.isAbove(claimable, 0);
+
+    const balanceBefore = await getAccount(connection, user2TokenB);
+    const claimTx = await program.methods
+      .claimBatchOrder(0)
+      .accounts({
+        owner: user2.publicKey,
+        poolState: poolStatePDA,
+        tokenMintA,
+        tokenMintB,
+        vaultA: vaultAPDA,
+        vaultB: vaultBPDA,
+        batch: batchPDA,
+        batchEscrowA: batchEscrowAPDA,
+        batchEscrowB: batchEscrowBPDA,
+        ownerToken: user2TokenB,
+        creator: user2.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([user2])
+      .rpc();
+    await confirmTx(claimTx);
+
+    const balanceAfter = await getAccount(connection, user2TokenB);
+    assert.equal(Number(balanceAfter.amount) - Number(balanceBefore.amount), claimable);
+    assert.isNull(await connection.getAccountInfo(batchPDA));
+
+    await program.methods
+      .setBatchAuction(new BN(0))
+      .accounts({
+        admin: admin.publicKey,
+        config: configPDA,
+        poolState: poolStatePDA,
+      })
+      .signers([admin])
+      .rpc();
+    console.log(" Claimed undeliverable batch output:", claimable);
+  });
+
+  it("Should fill a limit order through a keeper and cancel an unreachable one", async () => {
+    console.log("\n Testing limit orders...");
+
+    const poolState = await program.account.poolState.fetch(poolStatePDA);
+    const spotPrice = poolState.reserveB.mul(new BN(1_000_000)).div(poolState.reserveA);
+    const expiry = new BN(Math.floor(Date.now() / 1000) + 3600);
+    const amountIn = new BN(2_000_000);
+
+    const orderPDA = (orderId: number) =>
+      PublicKey.findProgramAddressSync(
+        [
+          Buffer.from("order"),
+          poolStatePDA.toBuffer(),
+          user2.publicKey.toBuffer(),
+          new BN(orderId).toArrayLike(Buffer, "le", 8),
+        ],
+        program.programId
+      )[0];
+    const orderEscrowPDA = (order: PublicKey) =>
+      PublicKey.findProgramAddressSync([Buffer.from("order_escrow"), order.toBuffer()], program.programId)[0];
+
+    const placeOrder = async (orderId: number, targetPrice: BN) => {
+      const order = orderPDA(orderId);
+      const tx = await program.methods
+        .placeLimitOrder(new BN(orderId), amountIn, targetPrice, true, expiry)
+        .accounts({
+          user: user2.publicKey,
+          poolState: poolStatePDA,
+          inputMint: tokenMintA,
+          order,
+          orderEscrow: orderEscrowPDA(order),
+          userTokenIn: user2TokenA,
+          userTokenOut: user2TokenB,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([user2])
+        .rpc();
+      await confirmTx(tx);
+      return order;
+    };
+
+    const executeOrder = (order: PublicKey) =>
+      program.methods
+        .executeLimitOrder()
+        .accounts({
+          swap: {
+            user: user1.publicKey,
+            poolState: poolStatePDA,
+            poolAuthority: poolAuthorityPDA,
+            tokenMintA,
+            tokenMintB,
+            vaultA: vaultAPDA,
+            vaultB: vaultBPDA,
+            userTokenA: user1TokenA,
+            userTokenB: user1TokenB,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            config: null,
+            discountTokenAccount: null,
+            traderStats: null,
+            feeDiscount: null,
+            instructionsSysvar: null,
+          },
+          order,
+          orderEscrow: orderEscrowPDA(order),
+          owner: user2.publicKey,
+          ownerTokenOut: user2TokenB,
+        })
+        .signers([user1])
+        .rpc();
+
+    const fillableOrder = await placeOrder(1, spotPrice.muln(9).divn(10));
+    const tokenBBefore = await getAccount(connection, user2TokenB);
+    await confirmTx(await executeOrder(fillableOrder));
+
+    const tokenBAfter = await getAccount(connection, user2TokenB);
+    assert.isAbove(Number(tokenBAfter.amount), Number(tokenBBefore.amount));
+    assert.isNull(await connection.getAccountInfo(fillableOrder));
+
+    const unreachableOrder = await placeOrder(2, spotPrice.muln(2));
+    // Same price and expiry, told apart by the order id
+    const duplicateOrder = await placeOrder(3, spotPrice.muln(2));
+    try {
+      await executeOrder(unreachableOrder);
+      assert.fail("Should have thrown error");
+    } catch (err: any) {
+      const errMsg = err.toString();
+      assert.isTrue(
+        errMsg.includes("LimitPriceNotReached") || errMsg.includes("6054"),
+        "Should fail with LimitPriceNotReached error"
+      );
+    }
+
+    const tokenABefore = await getAccount(connection, user2TokenA);
+    for (const order of [unreachableOrder, duplicateOrder]) {
+      const cancelTx = await program.methods
+        .cancelLimitOrder()
+        .accounts({
+          owner: user2.publicKey,
+          poolState: poolStatePDA,
+          inputMint: tokenMintA,
+          order,
+          orderEscrow: orderEscrowPDA(order),
+          userTokenIn: user2TokenA,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([user2])
+        .rpc();
+      await confirmTx(cancelTx);
+    }
+
+    const tokenAAfter = await getAccount(connection, user2TokenA);
+    assert.equal(Number(tokenAAfter.amount) - Number(tokenABefore.amount), 2 * amountIn.toNumber());
+    console.log(" Limit order filled and unreachable order cancelled");
+  });
+
+  it("Should run a DCA order slice by slice", async () => {
+    console.log("\n Testing DCA order...");
+
+    const [dcaOrderPDA] = PublicKey.findProgramAddressSync(
+      [Buffer.from("dca_order"), poolStatePDA.toBuffer(), user3.publicKey.toBuffer(), Buffer.from([1])],
+      program.programId
+    );
+    const [dcaEscrowPDA] = PublicKey.findProgramAddressSync(
+      [Buffer.from("dca_escrow"), dcaOrderPDA.toBuffer()],
+      program.programId
+    );
+
+    const openTx = await program.methods
+      .openDcaOrder(new BN(3_000_000), new BN(1_000_000), new BN(3600), new BN(1), true)
+      .accounts({
+        user: user3.publicKey,
+        poolState: poolStatePDA,
+        inputMint: tokenMintA,
+        dcaOrder: dcaOrderPDA,
+        dcaEscrow: dcaEscrowPDA,
+        userTokenIn: user3TokenA,
+        userTokenOut: user3TokenB,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([user3])
+      .rpc();
+    await confirmTx(openTx);
+
+    const crank = () =>
+      program.methods
+        .executeDcaOrder()
+        .accounts({
+          swap: {
+            user: user1.publicKey,
+            poolState: poolStatePDA,
+            poolAuthority: poolAuthorityPDA,
+            tokenMintA,
+            tokenMintB,
+            vaultA: vaultAPDA,
+            vaultB: vaultBPDA,
+            userTokenA: user1TokenA,
+            userTokenB: user1TokenB,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            config: null,
+            discountTokenAccount: null,
+            traderStats: null,
+            feeDiscount: null,
+            instructionsSysvar: null,
+          },
+          dcaOrder: dcaOrderPDA,
+          dcaEscrow: dcaEscrowPDA,
+          ownerTokenOut: user3TokenB,
+        })
+        .signers([user1])
+        .rpc();
+
+    const tokenBBefore = await getAccount(connection, user3TokenB);
+    await confirmTx(await crank());
+
+    const tokenBAfter = await getAccount(connection, user3TokenB);
+    let dcaOrder = await program.account.dcaOrder.fetch(dcaOrderPDA);
+    assert.equal(dcaOrder.remainingAmount.toNumber(), 2_000_000);
+    assert.equal(dcaOrder.fillCount.toNumber(), 1);
+    assert.equal(
+      Number(tokenBAfter.amount) - Number(tokenBBefore.amount),
+      dcaOrder.totalAmountOut.toNumber()
+    );
+
+    try {
+      await crank();
+      assert.fail("Should have thrown error");
+    } catch (err: any) {
+      const errMsg = err.toString();
+      assert.isTrue(
+        errMsg.includes("DcaFillNotDue") || errMsg.includes("6056"),
+        "Should fail with DcaFillNotDue error"
+      );
+    }
+
+    await program.methods
+      .topUpDcaOrder(new BN(1_000_000))
+      .accounts({
+        owner: user3.publicKey,
+        poolState: poolStatePDA,
+        inputMint: tokenMintA,
+        dcaOrder: dcaOrderPDA,
+        dcaEscrow: dcaEscrowPDA,
+        userTokenIn: user3TokenA,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([user3])
+      .rpc();
+
+    dcaOrder = await program.account.dcaOrder.fetch(dcaOrderPDA);
+    assert.equal(dcaOrder.remainingAmount.toNumber(), 3_000_000);
+
+    const tokenABefore = await getAccount(connection, user3TokenA);
+    const cancelTx = await program.methods
+      .cancelDcaOrder()
+      .accounts({
+        owner: user3.publicKey,
+        poolState: poolStatePDA,
+        inputMint: tokenMintA,
+        dcaOrder: dcaOrderPDA,
+        dcaEscrow: dcaEscrowPDA,
+        userTokenIn: user3TokenA,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([user3])
+      .rpc();
+    await confirmTx(cancelTx);
+
+    const tokenAAfter = await getAccount(connection, user3TokenA);
+    assert.equal(Number(tokenAAfter.amount) - Number(tokenABefore.amount), 3_000_000);
+    assert.isNull(await connection.getAccountInfo(dcaOrderPDA));
+    console.log(" DCA order filled one slice, topped up and cancelled");
+  });
+
+  it("Should fire a stop-loss order and hold an untriggered take-profit", async () => {
+    console.log("\n Testing trigger orders...");
+
+    const poolState = await program.account.poolState.fetch(poolStatePDA);
+    const spotPrice = poolState.reserveB.mul(new BN(1_000_000)).div(poolState.reserveA);
+    const amountIn = new BN(1_500_000);
+
+    const triggerOrderPDA = (triggerPrice: BN) =>
+      PublicKey.findProgramAddressSync(
+        [
+          Buffer.from("trigger_order"),
+          poolStatePDA.toBuffer(),
+          user2.publicKey.toBuffer(),
+          triggerPrice.toArrayLike(Buffer, "le", 8),
+          Buffer.from([1]),
+        ],
+        program.programId
+      )[0];
+    const triggerEscrowPDA = (triggerOrder: PublicKey) =>
+      PublicKey.findProgramAddressSync([Buffer.from("trigger_escrow"), triggerOrder.toBuffer()], program.programId)[0];
+
+    const placeOrder = async (kind: any, triggerPrice: BN) => {
+      const triggerOrder = triggerOrderPDA(triggerPrice);
+      const tx = await program.methods
+        .placeTriggerOrder(amountIn, new BN(1), true, kind, triggerPrice, new BN(0))
+        .accounts({
+          user: user2.publicKey,
+          poolState: poolStatePDA,
+          inputMint: tokenMintA,
+          triggerOrder,
+          triggerEscrow: triggerEscrowPDA(triggerOrder),
+          userTokenIn: user2TokenA,
+          userTokenOut: user2TokenB,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([user2])
+        .rpc();
+      await confirmTx(tx);
+      return triggerOrder;
+    };
+
+    const executeOrder = (triggerOrder: PublicKey) =>
+      program.methods
+        .executeTriggerOrder()
+        .accounts({
+          swap: {
+            user: user1.publicKey,
+            poolState: poolStatePDA,
+            poolAuthority: poolAuthorityPDA,
+            tokenMintA,
+            tokenMintB,
+            vaultA: vaultAPDA,
+            vaultB: vaultBPDA,
+            userTokenA: user1TokenA,
+            userTokenB: user1TokenB,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            config: null,
+            discountTokenAccount: null,
+            traderStats: null,
+            feeDiscount: null,
+            instructionsSysvar: null,
+          },
+          triggerOrder,
+          triggerEscrow: triggerEscrowPDA(triggerOrder),
+          owner: user2.publicKey,
+          ownerTokenOut: user2TokenB,
+        })
+        .signers([user1])
+        .rpc();
+
+    const stopLoss = await placeOrder({ stopLoss: {} }, spotPrice.muln(11).divn(10));
+    const tokenBBefore = await getAccount(connection, user2TokenB);
+    await confirmTx(await executeOrder(stopLoss));
+
+    const tokenBAfter = await getAccount(connection, user2TokenB);
+    assert.isAbove(Number(tokenBAfter.amount), Number(tokenBBefore.amount));
+    assert.isNull(await connection.getAccountInfo(stopLoss));
+
+    const takeProfit = await placeOrder({ takeProfit: {} }, spotPrice.muln(2));
+    try {
+      await executeOrder(takeProfit);
+      assert.fail("Should have thrown error");
+    } catch (err: any) {
+      const errMsg = err.toString();
+      assert.isTrue(
+        errMsg.includes("TriggerNotReached") || errMsg.includes("6060"),
+        "Should fail with TriggerNotReached error"
+      );
+    }
+
+    const cancelTx = await program.methods
+      .cancelTriggerOrder()
+      .accounts({
+        owner: user2.publicKey,
+        poolState: poolStatePDA,
+        inputMint: tokenMintA,
+        triggerOrder: takeProfit,
+        triggerEscrow: triggerEscrowPDA(takeProfit),
+        userTokenIn: user2TokenA,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([user2])
+      .rpc();
+    await confirmTx(cancelTx);
+
+    assert.isNull(await connection.getAccountInfo(takeProfit));
+    console.log(" Stop-loss fired; take-profit cancelled");
+  });
+
+  it("Should fill a swap from a signed maker quote and route the rest through the pool", async () => {
+    console.log("\n Testing RFQ swap...");
+
+    const maker = Keypair.generate();
+    const makerTokenA = (
+      await getOrCreateAssociatedTokenAccount(connection, admin, tokenMintA, maker.publicKey)
+    ).address;
+    const makerTokenB = (
+      await getOrCreateAssociatedTokenAccount(connection, admin, tokenMintB, maker.publicKey)
+    ).address;
+    await mintTo(connection, admin, tokenMintB, makerTokenB, admin, 10_000_000, [], undefined, TOKEN_PROGRAM_ID);
+
+    const [rfqAuthorityPDA] = PublicKey.findProgramAddressSync([Buffer.from("rfq_authority")], program.programId);
+    await approve(connection, admin, makerTokenB, rfqAuthorityPDA, maker, 10_000_000);
+
+    await program.methods
+      .setRfqMakers([maker.publicKey])
+      .accounts({
+        admin: admin.publicKey,
+        config: configPDA,
+        poolState: poolStatePDA,
+      })
+      .signers([admin])
+      .rpc();
+
+    const quote = {
+      pool: poolStatePDA,
+      maker: maker.publicKey,
+      taker: user2.publicKey,
+      isAToB: true,
+      amountIn: new BN(2_000_000),
+      amountOut: new BN(2_000_000),
+      expiry: new BN(Math.floor(Date.now() / 1000) + 600),
+      nonce: new BN(1),
+    };
+    const message = Buffer.concat([
+      quote.pool.toBuffer(),
+      quote.maker.toBuffer(),
+      quote.taker.toBuffer(),
+      Buffer.from([1]),
+      quote.amountIn.toArrayLike(Buffer, "le", 8),
+      quote.amountOut.toArrayLike(Buffer, "le", 8),
+      quote.expiry.toArrayLike(Buffer, "le", 8),
+      quote.nonce.toArrayLike(Buffer, "le", 8),
+    ]);
+    const signatureIx = Ed25519Program.createInstructionWithPrivateKey({
+      privateKey: maker.secretKey,
+      message,
+    });
+
+    const [quoteFillPDA] = PublicKey.findProgramAddressSync(
+      [Buffer.from("rfq_quote"), maker.publicKey.toBuffer(), quote.nonce.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    );
+
+    const tokenBBefore = await getAccount(connection, user2TokenB);
+    const makerABefore = await getAccount(connection, makerTokenA);
+
+    const tx = await program.methods
+      .swapRfq(new BN(3_000_000), new BN(2_000_001), true, quote)
+      .accounts({
+        swap: {
+          user: user2.publicKey,
+          poolState: poolStatePDA,
+          poolAuthority: poolAuthorityPDA,
+          tokenMintA,
+          tokenMintB,
+          vaultA: vaultAPDA,
+          vaultB: vaultBPDA,
+          userTokenA: user2TokenA,
+          userTokenB: user2TokenB,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          config: null,
+          discountTokenAccount: null,
+          traderStats: null,
+          feeDiscount: null,
+          instructionsSysvar: SYSVAR_INSTRUCTIONS_PUBKEY,
+        },
+        maker: maker.publicKey,
+        makerTokenIn: makerTokenA,
+        makerTokenOut: makerTokenB,
+        rfqAuthority: rfqAuthorityPDA,
+        quoteFill: quoteFillPDA,
+        systemProgram: SystemProgram.programId,
+      })
+      .preInstructions([signatureIx])
+      .signers([user2])
+      .rpc();
+    await confirmTx(tx);
+
+    const tokenBAfter = await getAccount(connection, user2TokenB);
+    const makerAAfter = await getAccount(connection, makerTokenA);
+    assert.equal(Number(makerAAfter.amount) - Number(makerABefore.amount), 2_000_000);
+    assert.isAbove(Number(tokenBAfter.amount) - Number(tokenBBefore.amount), 2_000_000);
+
+    const quoteFill = await program.account.rfqQuoteFill.fetch(quoteFillPDA);
+    assert.ok(quoteFill.taker.equals(user2.publicKey));
+    assert.equal(quoteFill.expiry.toNumber(), quote.expiry.toNumber());
+
+    try {
+      await program.methods
+        .closeRfqQuoteFill()
+        .accounts({
+          taker: user2.publicKey,
+          quoteFill: quoteFillPDA,
+        })
+        .signers([user2])
+        .rpc();
+      assert.fail("Should have failed");
+    } catch (err: any) {
+      const errMsg = err.toString();
+      assert.ok(errMsg.includes("QuoteNotExpired") || errMsg.includes("6069"));
+    }
+
+    await program.methods
+      .setRfqMakers([])
+      .accounts({
+        admin: admin.publicKey,
+        config: configPDA,
+        poolState: poolStatePDA,
+      })
+      .signers([admin])
+      .rpc();
+    console.log(" RFQ quote filled with pool remainder");
+  });
+
+  it("Should run a batch of swaps and skip failing entries on request", async () => {
+    console.log("\n Testing batch swap...");
+
+    const swapAccounts = [
+      { pubkey: user2.publicKey, isSigner: true, isWritable: true },
+      { pubkey: poolStatePDA, isSigner: false, isWritable: true },
+      { pubkey: poolAuthorityPDA, isSigner: false, isWritable: false },
+      { pubkey: tokenMintA, isSigner: false, isWritable: false },
+      { pubkey: tokenMintB, isSigner: false, isWritable: false },
+      { pubkey: vaultAPDA, isSigner: false, isWritable: true },
+      { pubkey: vaultBPDA, isSigner: false, isWritable: true },
+      { pubkey: user2TokenA, isSigner: false, isWritable: true },
+      { pubkey: user2TokenB, isSigner: false, isWritable: true },
+      { pubkey: TOKEN_PROGRAM_ID, isSigner: false, isWritable: false },
+      // feeDiscount, config, discountTokenAccount, traderStats, instructionsSysvar
+      ...Array(5).fill({ pubkey: program.programId, isSigner: false, isWritable: false }),
+    ];
+
+    const entries = [
+      { pool: poolStatePDA, isAToB: true, amountIn: new BN(1_000_000), minAmountOut: new BN(1) },
+      { pool: poolStatePDA, isAToB: false, amountIn: new BN(1_000_000), minAmountOut: new BN("1000000000000") },
+    ];
+
+    const tokenABefore = await getAccount(connection, user2TokenA);
+    const tx = await program.methods
+      .batchSwap(entries, true)
+      .accounts({ user: user2.publicKey })
+      .remainingAccounts([...swapAccounts, ...swapAccounts])
+      .signers([user2])
+      .rpc();
+    await confirmTx(tx);
+
+    const tokenAAfter = await getAccount(connection, user2TokenA);
+    assert.equal(Number(tokenABefore.amount) - Number(tokenAAfter.amount), 1_000_000);
+
+    try {
+      await program.methods
+        .batchSwap(entries, false)
+        .accounts({ user: user2.publicKey })
+        .remainingAccounts([...swapAccounts, ...swapAccounts])
+        .signers([user2])
+        .rpc();
+      assert.fail("Should have thrown error");
+    } catch (err: any) {
+      const errMsg = err.toString();
+      assert.isTrue(
+        errMsg.includes("SlippageExceeded") || errMsg.includes("6013"),
+        "Should fail with SlippageExceeded error"
+      );
+    }
+    console.log(" Batch swap skipped the failing entry");
+  });
+
+  it("Should reject opposite batch entries with sandwich protection", async () => {
+    console.log("\n Testing batch swap sandwich protection...");
+
+    const configAccounts = { admin: admin.publicKey, config: configPDA, poolState: poolStatePDA };
+
+    const enableTx = await program.methods
+      .setSandwichProtection(true, [])
+      .accounts(configAccounts)
+      .signers([admin])
+      .rpc();
+    await confirmTx(enableTx);
+
+    const swapAccounts = [
+      { pubkey: user2.publicKey, isSigner: true, isWritable: true },
+      { pubkey: poolStatePDA, isSigner: false, isWritable: true },
+      { pubkey: poolAuthorityPDA, isSigner: false, isWritable: false },
+      { pubkey: tokenMintA, isSigner: false, isWritable: false },
+      { pubkey: tokenMintB, isSigner: false, isWritable: false },
+      { pubkey: vaultAPDA, isSigner: false, isWritable: true },
+      { pubkey: vaultBPDA, isSigner: false, isWritable: true },
+      { pubkey: user2TokenA, isSigner: false, isWritable: true },
+      { pubkey: user2TokenB, isSigner: false, isWritable: true },
+      { pubkey: TOKEN_PROGRAM_ID, isSigner: false, isWritable: false },
+      // feeDiscount, config, discountTokenAccount, traderStats
+      ...Array(4).fill({ pubkey: program.programId, isSigner: false, isWritable: false }),
+      { pubkey: SYSVAR_INSTRUCTIONS_PUBKEY, isSigner: false, isWritable: false },
+    ];
+
+    const entries = [
+      { pool: poolStatePDA, isAToB: true, amountIn: new BN(1_000_000), minAmountOut: new BN(1) },
+      { pool: poolStatePDA, isAToB: false, amountIn: new BN(1_000_000), minAmountOut: new BN(1) },
+    ];
+
+    try {
+      await program.methods
+        .batchSwap(entries, false)
+        .accounts({ user: user2.publicKey })
+        .remainingAccounts([...swapAccounts, ...swapAccounts])
+        .signers([user2])
+        .rpc();
+      assert.fail("Should have thrown error");
+    } catch (err: any) {
+      const errMsg = err.toString();
+      assert.isTrue(
+        errMsg.includes("SandwichDetected") || errMsg.includes("6041"),
+        "Should fail with SandwichDetected error"
+      );
+      console.log(" Opposite batch entries rejected");
+    }
+
+    const disableTx = await program.methods
+      .setSandwichProtection(false, [])
+      .accounts(configAccounts)
+      .signers([admin])
+      .rpc();
+    await confirmTx(disableTx);
+  });
+
+  it("Should raise the fee after volatile swaps when dynamic fees are enabled", async () => {
+    console.log("\n Testing dynamic fee...");
+
+    const configAccounts = { admin: admin.publicKey, config: configPDA, poolState: poolStatePDA };
+
+    const enableTx = await program.methods
+      .setDynamicFee(new BN(10), new BN(100), new BN(3600))
+      .accounts(configAccounts)
+      .signers([admin])
+      .rpc();
+    await confirmTx(enableTx);
+
+    const amountIn = new BN(30_000_000);
+    const calmQuote = await program.methods
+      .quoteSwap(amountIn, true)
+      .accounts({
+        poolState: poolStatePDA,
+        feeDiscount: null,
+        config: null,
+        discountTokenAccount: null,
+        traderStats: null,
+        instructionsSysvar: null,
+      })
+      .view();
+    assert.equal(calmQuote.feeNumerator.toNumber(), FEE_NUMERATOR.toNumber());
+
+    const tx = await program.methods
+      .swap(amountIn, new BN(1), true)
+      .accounts({
+        user: user2.publicKey,
+        poolState: poolStatePDA,
+        poolAuthority: poolAuthorityPDA,
+        tokenMintA,
+        tokenMintB,
+        vaultA: vaultAPDA,
+        vaultB: vaultBPDA,
+        userTokenA: user2TokenA,
+        userTokenB: user2TokenB,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        config: null,
+        discountTokenAccount: null,
+        traderStats: null,
+        instructionsSysvar: null,
+        feeDiscount: null,
+      })
+      .signers([user2])
+      .rpc();
+    await confirmTx(tx);
+
+    const volatileQuote = await program.methods
+      .quoteSwap(amountIn, true)
+      .accounts({
+        poolState: poolStatePDA,
+        feeDiscount: null,
+        config: null,
+        discountTokenAccount: null,
+        traderStats: null,
+        instructionsSysvar: null,
+      })
+      .view();
+    assert.isAbove(volatileQuote.feeNumerator.toNumber(), FEE_NUMERATOR.toNumber());
+    assert.isAtMost(volatileQuote.feeNumerator.toNumber(), 10);
+    console.log(" Fee after volatility:", volatileQuote.feeNumerator.toNumber(), "/", FEE_DENOMINATOR.toNumber());
+
+    const disableTx = await program.methods
+      .setDynamicFee(new BN(0), new BN(0), new BN(0))
+      .accounts(configAccounts)
+      .signers([admin])
+      .rpc();
+    await confirmTx(disableTx);
+  });
+
+  it("Should track fees earned by a position", async () => {
+    console.log("\n Testing LP position lifecycle...");
+
+    const [positionPDA] = PublicKey.findProgramAddressSync(
+      [Buffer.from("position"), poolStatePDA.toBuffer(), user3.publicKey.toBuffer()],
+      program.programId
+    );
+
+    const liquidity = {
+      owner: user3.publicKey,
+      poolState: poolStatePDA,
+      poolAuthority: poolAuthorityPDA,
+      tokenMintA,
+      tokenMintB,
+      vaultA: vaultAPDA,
+      vaultB: vaultBPDA,
+      userTokenA: user3TokenA,
+      userTokenB: user3TokenB,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    };
+
+    const poolBefore = await program.account.poolState.fetch(poolStatePDA);
+
+    await program.methods
+      .openPosition(new BN(100_000_000), new BN(500_000_000), new BN(0), new BN(0), new BN(1))
+      .accounts({ liquidity, position: positionPDA, systemProgram: SystemProgram.programId })
+      .signers([user3])
+      .rpc();
+
+    await program.methods
+      .increasePosition(new BN(50_000_000), new BN(500_000_000), new BN(0), new BN(0), new BN(1))
+      .accounts({ liquidity, position: positionPDA })
+      .signers([user3])
+      .rpc();
+
+    let position = await program.account.position.fetch(positionPDA);
+    assert.isTrue(position.lpShares.toNumber() > 0);
+    assert.equal(position.owner.toBase58(), user3.publicKey.toBase58());
+
+    await program.methods
+      .swap(new BN(20_000_000), new BN(1), true)
+      .accounts({
+        user: user2.publicKey,
+        poolState: poolStatePDA,
+        poolAuthority: poolAuthorityPDA,
+        tokenMintA,
+        tokenMintB,
+        vaultA: vaultAPDA,
+        vaultB: vaultBPDA,
+        userTokenA: user2TokenA,
+        userTokenB: user2TokenB,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        config: null,
+        discountTokenAccount: null,
+        traderStats: null,
+        instructionsSysvar: null,
+        feeDiscount: null,
+      })
+      .signers([user2])
+      .rpc();
+
+    const halfShares = position.lpShares.div(new BN(2));
+    await program.methods
+      .decreasePosition(halfShares, new BN(0), new BN(0))
+      .accounts({ liquidity, position: positionPDA })
+      .signers([user3])
+      .rpc();
+
+    position = await program.account.position.fetch(positionPDA);
+    assert.isTrue(position.feesEarnedA.toNumber() > 0);
+    assert.equal(position.feesEarnedB.toNumber(), 0);
+
+    const tx = await program.methods
+      .closePosition(new BN(0), new BN(0))
+      .accounts({ liquidity, position: positionPDA })
+      .signers([user3])
+      .rpc();
+
+    await confirmTx(tx);
+
+    const closed = await connection.getAccountInfo(positionPDA);
+    const poolAfter = await program.account.poolState.fetch(poolStatePDA);
+
+    assert.isNull(closed);
+    assert.equal(poolAfter.totalSupply.toNumber(), poolBefore.totalSupply.toNumber());
+    console.log(" Position fees earned:", position.feesEarnedA.toNumber() / 1e9, "Token A");
+  });
+
+  it("Should handle second liquidity provider", async () => {
+    console.log("\n Testing second LP...");
+
+    const lpAcc = await getOrCreateAssociatedTokenAccount(
+      connection, user2, poolMintPDA, user2.publicKey, false, undefined, undefined, TOKEN_PROGRAM_ID
+    );
+    user2LpToken = lpAcc.address;
+
+    const poolBefore = await program.account.poolState.fetch(poolStatePDA);
+    const lpSupplyBefore = poolBefore.totalSupply.toNumber();
+
+    const amountA = new BN(250_000_000);
+
+    const tx = await program.methods
+      .addLiquidity(amountA, new BN(500_000_000), new BN(0), new BN(0), new BN(1))
+      .accounts({
+        user: user2.publicKey,
+        poolState: poolStatePDA,
+        poolAuthority: poolAuthorityPDA,
+        tokenMintA,
+        tokenMintB,
+        vaultA: vaultAPDA,
+        vaultB: vaultBPDA,
+        poolMint: poolMintPDA,
+        userTokenA: user2TokenA,
+        userTokenB: user2TokenB,
+        userLpToken: user2LpToken,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([user2])
+      .rpc();
+
+    await confirmTx(tx);
+
+    const poolAfter = await program.account.poolState.fetch(poolStatePDA);
+    const user2LpBalance = await getAccount(connection, user2LpToken);
+
+    assert.isTrue(poolAfter.totalSupply.toNumber() > lpSupplyBefore);
+    assert.isTrue(Number(user2LpBalance.amount) > 0);
+
+    console.log(" Second LP added liquidity");
+    console.log(" User2 LP tokens:", Number(user2LpBalance.amount) / 1e9);
+  });
+
+  it("Should handle third liquidity provider", async () => {
+    console.log("\n Testing third LP...");
+
+    const lpAcc = await getOrCreateAssociatedTokenAccount(
+      connection, user3, poolMintPDA, user3.publicKey, false, undefined, undefined, TOKEN_PROGRAM_ID
+    );
+    user3LpToken = lpAcc.address;
 
     const poolBefore = await program.account.poolState.fetch(poolStatePDA);
 
@@ -969,6 +3249,208 @@ describe("AMM", () => {
     }
   });
 
+  it("Should lock liquidity and refuse early unlock", async () => {
+    console.log("\n Testing liquidity lock...");
+
+    const lockId = new BN(1);
+    const lpAmount = new BN(100);
+    const [lockPDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("lock"),
+        poolStatePDA.toBuffer(),
+        user1.publicKey.toBuffer(),
+        lockId.toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId
+    );
+    const [lockEscrowPDA] = PublicKey.findProgramAddressSync(
+      [Buffer.from("lock_escrow"), lockPDA.toBuffer()],
+      program.programId
+    );
+
+    const unlockTime = new BN(Math.floor(Date.now() / 1000) + 3600);
+
+    const tx = await program.methods
+      .lockLiquidity(lockId, lpAmount, unlockTime, false)
+      .accounts({
+        owner: user1.publicKey,
+        poolState: poolStatePDA,
+        poolMint: poolMintPDA,
+        lock: lockPDA,
+        lockEscrow: lockEscrowPDA,
+        userLpToken: user1LpToken,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([user1])
+      .rpc();
+
+    await confirmTx(tx);
+
+    const escrow = await getAccount(connection, lockEscrowPDA);
+    assert.equal(Number(escrow.amount), 100);
+
+    try {
+      await program.methods
+        .unlockLiquidity()
+        .accounts({
+          owner: user1.publicKey,
+          poolState: poolStatePDA,
+          poolMint: poolMintPDA,
+          lock: lockPDA,
+          lockEscrow: lockEscrowPDA,
+          userLpToken: user1LpToken,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([user1])
+        .rpc();
+
+      assert.fail("Should have thrown error");
+    } catch (err: any) {
+      const errMsg = err.toString();
+      assert.isTrue(
+        errMsg.includes("LiquidityStillLocked") || errMsg.includes("6032"),
+        "Should fail with LiquidityStillLocked error"
+      );
+      console.log(" Correctly refused early unlock");
+    }
+  });
+
+  it("Should stake LP tokens in a farm and accrue rewards", async () => {
+    console.log("\n Testing LP farm...");
+
+    const rewardMint = await createMint(
+      connection,
+      admin,
+      admin.publicKey,
+      null,
+      6,
+      undefined,
+      undefined,
+      TOKEN_PROGRAM_ID
+    );
+    const adminReward = await getOrCreateAssociatedTokenAccount(connection, admin, rewardMint, admin.publicKey);
+    await mintTo(connection, admin, rewardMint, adminReward.address, admin, 1_000_000_000);
+    const user1Reward = await getOrCreateAssociatedTokenAccount(connection, user1, rewardMint, user1.publicKey);
+
+    const [farmPDA] = PublicKey.findProgramAddressSync(
+      [Buffer.from("farm"), poolMintPDA.toBuffer()],
+      program.programId
+    );
+    const [stakeVaultPDA] = PublicKey.findProgramAddressSync(
+      [Buffer.from("farm_stake_vault"), farmPDA.toBuffer()],
+      program.programId
+    );
+    const [rewardVaultPDA] = PublicKey.findProgramAddressSync(
+      [Buffer.from("farm_reward_vault"), farmPDA.toBuffer(), rewardMint.toBuffer()],
+      program.programId
+    );
+    const [stakePDA] = PublicKey.findProgramAddressSync(
+      [Buffer.from("farm_stake"), farmPDA.toBuffer(), user1.publicKey.toBuffer()],
+      program.programId
+    );
+
+    const now = Math.floor(Date.now() / 1000);
+    const rewardPerSecond = new BN(1000);
+
+    const createTx = await program.methods
+      .createFarm(rewardPerSecond, new BN(now + 1), new BN(now + 3601))
+      .accounts({
+        authority: admin.publicKey,
+        config: configPDA,
+        poolState: poolStatePDA,
+        poolMint: poolMintPDA,
+        farm: farmPDA,
+        stakeVault: stakeVaultPDA,
+        rewardMint,
+        rewardVault: rewardVaultPDA,
+        funderRewardToken: adminReward.address,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([admin])
+      .rpc();
+    await confirmTx(createTx);
+
+    const rewardVault = await getAccount(connection, rewardVaultPDA);
+    assert.equal(Number(rewardVault.amount), 3_600_000);
+
+    const stakeAccounts = {
+      owner: user1.publicKey,
+      farm: farmPDA,
+      stake: stakePDA,
+      poolMint: poolMintPDA,
+      stakeVault: stakeVaultPDA,
+      userLpToken: user1LpToken,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    };
+
+    const stakeTx = await program.methods
+      .stakeLp(new BN(100))
+      .accounts({ ...stakeAccounts, systemProgram: SystemProgram.programId })
+      .signers([user1])
+      .rpc();
+    await confirmTx(stakeTx);
+
+    const farm = await program.account.farm.fetch(farmPDA);
+    assert.equal(farm.totalStaked.toNumber(), 100);
+
+    await new Promise((resolve) => setTimeout(resolve, 3000));
+
+    const unstakeTx = await program.methods
+      .unstakeLp(new BN(100))
+      .accounts(stakeAccounts)
+      .signers([user1])
+      .rpc();
+    await confirmTx(unstakeTx);
+
+    const stake = await program.account.farmStake.fetch(stakePDA);
+    assert.equal(stake.amount.toNumber(), 0);
+    assert.isTrue(stake.pendingRewards[0].toNumber() > 0, "Should have accrued rewards");
+
+    const claimTx = await program.methods
+      .claimRewards(0)
+      .accounts({
+        owner: user1.publicKey,
+        farm: farmPDA,
+        stake: stakePDA,
+        rewardMint,
+        rewardVault: rewardVaultPDA,
+        userRewardToken: user1Reward.address,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([user1])
+      .rpc();
+    await confirmTx(claimTx);
+
+    const claimed = await getAccount(connection, user1Reward.address);
+    assert.equal(Number(claimed.amount), stake.pendingRewards[0].toNumber());
+    console.log(" Claimed farm rewards:", Number(claimed.amount));
+
+    await new Promise((resolve) => setTimeout(resolve, 2000));
+
+    const restakeTx = await program.methods
+      .stakeLp(new BN(100))
+      .accounts({ ...stakeAccounts, systemProgram: SystemProgram.programId })
+      .signers([user1])
+      .rpc();
+    await confirmTx(restakeTx);
+
+    const farmAfterIdle = await program.account.farm.fetch(farmPDA);
+    assert.isTrue(
+      farmAfterIdle.rewards[0].endTime.toNumber() > now + 3601,
+      "Idle time should be appended to the reward stream"
+    );
+    console.log(" Reward stream extended to:", farmAfterIdle.rewards[0].endTime.toNumber());
+
+    const finalUnstakeTx = await program.methods
+      .unstakeLp(new BN(100))
+      .accounts(stakeAccounts)
+      .signers([user1])
+      .rpc();
+    await confirmTx(finalUnstakeTx);
+  });
+
   it("Should reject removal with high minimum amounts", async () => {
     console.log("\n Testing minimum withdrawal protection...");
 
@@ -1031,6 +3513,11 @@ describe("AMM", () => {
         userTokenA: user2TokenA,
         userTokenB: user2TokenB,
         tokenProgram: TOKEN_PROGRAM_ID,
+        config: null,
+        discountTokenAccount: null,
+        traderStats: null,
+        instructionsSysvar: null,
+        feeDiscount: null,
       })
       .signers([user2])
       .rpc();